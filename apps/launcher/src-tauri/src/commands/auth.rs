@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Manager};
 
+use crate::minecraft::{http, redact};
+
 const MS_CLIENT_ID: &str = "00000000402b5328";
 const MS_AUTH_URL: &str = "https://login.live.com/oauth20_authorize.srf";
 const MS_TOKEN_URL: &str = "https://login.live.com/oauth20_token.srf";
@@ -98,8 +100,9 @@ pub async fn microsoft_auth_start(app: tauri::AppHandle) -> Result<(), String> {
 /// Exchange authorization code for full MC auth tokens.
 #[tauri::command]
 pub async fn exchange_auth_code(code: String, port: u16) -> Result<AuthTokens, String> {
+    redact::register_secret(&code);
     let redirect_uri = MS_REDIRECT_URI.to_string();
-    let client = reqwest::Client::new();
+    let client = http::client();
 
     // Step 1: Exchange code for MS tokens
     let ms_tokens = client
@@ -126,7 +129,8 @@ pub async fn exchange_auth_code(code: String, port: u16) -> Result<AuthTokens, S
 /// Refresh tokens using the MS refresh token.
 #[tauri::command]
 pub async fn refresh_token(refresh_token: String) -> Result<AuthTokens, String> {
-    let client = reqwest::Client::new();
+    redact::register_secret(&refresh_token);
+    let client = http::client();
 
     let ms_tokens = client
         .post(MS_TOKEN_URL)
@@ -149,7 +153,7 @@ pub async fn refresh_token(refresh_token: String) -> Result<AuthTokens, String>
 /// Get Minecraft profile using an existing MC access token.
 #[tauri::command]
 pub async fn get_minecraft_profile(mc_access_token: String) -> Result<MinecraftProfile, String> {
-    let client = reqwest::Client::new();
+    let client = http::client();
     let profile = client
         .get(MC_PROFILE_URL)
         .bearer_auth(&mc_access_token)
@@ -250,6 +254,11 @@ async fn complete_auth_chain(
         .await
         .map_err(|e| format!("Profile parse failed: {}", e))?;
 
+    // Kept out of every log and diagnostics export from here on
+    for token in [ms_access_token, ms_refresh_token, mc_access_token.as_str()] {
+        redact::register_secret(token);
+    }
+
     Ok(AuthTokens {
         ms_access_token: ms_access_token.to_string(),
         ms_refresh_token: ms_refresh_token.to_string(),
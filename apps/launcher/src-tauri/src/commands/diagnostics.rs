@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::minecraft::diagnostics::{self, DiagnosticsReport};
+use crate::minecraft::mclogs::{self, UploadedLog};
+
+/// Write a redacted diagnostics zip to `output_path`, with the last `session_logs` game logs.
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    output_path: String,
+    session_logs: Option<usize>,
+) -> Result<DiagnosticsReport, String> {
+    diagnostics::export(
+        &app,
+        &PathBuf::from(output_path),
+        session_logs.unwrap_or(diagnostics::DEFAULT_SESSION_LOGS),
+    )
+    .await
+}
+
+/// Share the log of `session_id`, or of the newest session, and return its link.
+#[tauri::command]
+pub async fn upload_log(app: AppHandle, session_id: Option<String>) -> Result<UploadedLog, String> {
+    mclogs::upload(&app, session_id.as_deref()).await
+}
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::minecraft::curseforge::{self, CurseImportReport};
+use crate::minecraft::disk::InstallError;
+use crate::minecraft::instance::{self, InstanceSummary};
+use crate::minecraft::jvm_args::{self, JvmPreset, LaunchOptions};
+use crate::minecraft::launch::{self, LaunchConfig, LaunchPreview};
+use crate::minecraft::loader::{self, LoaderChangeReport, LoaderVersion};
+use crate::minecraft::mrpack::{self, MrpackExportReport, MrpackImportReport};
+use crate::minecraft::packwiz::{self, PackwizInstanceReport, PackwizSyncReport};
+
+/// The main server instance followed by imported modpacks.
+#[tauri::command]
+pub async fn list_instances() -> Result<Vec<InstanceSummary>, String> {
+    tokio::task::spawn_blocking(instance::list)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Import a Modrinth `.mrpack` as a new instance.
+#[tauri::command]
+pub async fn import_mrpack(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<MrpackImportReport, InstallError> {
+    mrpack::import(&app, &PathBuf::from(path), name).await
+}
+
+/// Import a CurseForge modpack export zip as a new instance.
+#[tauri::command]
+pub async fn import_curseforge(
+    app: AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<CurseImportReport, InstallError> {
+    curseforge::import(&app, &PathBuf::from(path), name).await
+}
+
+/// Export an instance as a Modrinth `.mrpack` at `output_path`.
+#[tauri::command]
+pub async fn export_mrpack(
+    app: AppHandle,
+    instance_id: String,
+    output_path: String,
+    name: String,
+    version: String,
+) -> Result<MrpackExportReport, String> {
+    let game_dir = instance::dir(&instance_id)?;
+    mrpack::export(
+        &app,
+        &game_dir,
+        &PathBuf::from(output_path),
+        &name,
+        &version,
+    )
+    .await
+}
+
+/// Create an instance that follows the packwiz pack whose `pack.toml` is at `url`.
+#[tauri::command]
+pub async fn add_packwiz_instance(
+    app: AppHandle,
+    url: String,
+    name: Option<String>,
+) -> Result<PackwizInstanceReport, InstallError> {
+    packwiz::create(&app, &url, name).await
+}
+
+/// Sync a packwiz instance now instead of at the next launch.
+#[tauri::command]
+pub async fn sync_packwiz_instance(
+    app: AppHandle,
+    instance_id: String,
+) -> Result<PackwizSyncReport, InstallError> {
+    let game_dir = instance::dir(&instance_id)?;
+    packwiz::sync_instance(&app, &game_dir).await
+}
+
+/// Loader versions available for the instance's Minecraft version.
+#[tauri::command]
+pub async fn list_loader_versions(instance_id: String) -> Result<Vec<LoaderVersion>, String> {
+    let game_dir = instance::dir(&instance_id)?;
+    loader::list_versions(&game_dir).await
+}
+
+/// Switch the instance to `version` and pin it; `None` unpins and keeps the installed one.
+#[tauri::command]
+pub async fn set_loader_version(
+    app: AppHandle,
+    instance_id: String,
+    version: Option<String>,
+    force: Option<bool>,
+) -> Result<LoaderChangeReport, String> {
+    let game_dir = instance::dir(&instance_id)?;
+    loader::set_version(&app, &game_dir, version, force.unwrap_or(false)).await
+}
+
+/// JVM argument presets shipped with the launcher.
+#[tauri::command]
+pub fn list_jvm_presets() -> &'static [JvmPreset] {
+    jvm_args::PRESETS
+}
+
+#[tauri::command]
+pub async fn get_launch_options(instance_id: String) -> Result<LaunchOptions, String> {
+    Ok(LaunchOptions::load(&instance::dir(&instance_id)?))
+}
+
+/// Save the preset and extra arguments of an instance once they parse; whether the selected
+/// Java accepts them is checked at launch and in the preview.
+#[tauri::command]
+pub async fn set_launch_options(instance_id: String, options: LaunchOptions) -> Result<(), String> {
+    let game_dir = instance::dir(&instance_id)?;
+    jvm_args::resolve(&options, None)?;
+    options.save(&game_dir)
+}
+
+/// The command line `launch_minecraft` would run for the instance, with secrets masked.
+#[tauri::command]
+pub async fn preview_launch_command(
+    instance_id: String,
+    java_path: String,
+    max_memory_mb: u32,
+    player_name: Option<String>,
+    server_host: Option<String>,
+    server_port: Option<u16>,
+) -> Result<LaunchPreview, String> {
+    let config = LaunchConfig {
+        session_id: String::new(),
+        java_path: PathBuf::from(java_path),
+        max_memory_mb,
+        game_dir: instance::dir(&instance_id)?,
+        server_host,
+        server_port,
+        servers: Vec::new(),
+        player_name: player_name.unwrap_or_else(|| "Player".to_string()),
+        player_uuid: "00000000-0000-0000-0000-000000000000".to_string(),
+        access_token: String::new(),
+        ignore_mod_errors: false,
+    };
+    tokio::task::spawn_blocking(move || launch::preview(&config))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
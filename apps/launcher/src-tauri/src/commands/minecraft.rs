@@ -1,7 +1,15 @@
 use std::path::PathBuf;
 use tauri::{AppHandle, State};
 
-use crate::minecraft::{download, java, launch};
+use crate::minecraft::cache::MetaFetcher;
+use crate::minecraft::disk::{InstallError, InstallEstimate};
+use crate::minecraft::hud_mod::{self, HudModStatus};
+use crate::minecraft::memory::{self, MemoryRecommendation};
+use crate::minecraft::process::{self, RunningGame, SessionPolicy, StopReport};
+use crate::minecraft::remote_config::{self, LoadedConfig};
+use crate::minecraft::system::{self, SystemInfo};
+use crate::minecraft::{download, instance, java, launch};
+use crate::settings;
 use crate::AppState;
 use serde::Serialize;
 
@@ -15,6 +23,8 @@ pub struct JavaInfo {
 pub struct InstallStatus {
     pub installed: bool,
     pub game_dir: String,
+    pub minecraft_version: String,
+    pub hud_mod: HudModStatus,
 }
 
 #[tauri::command]
@@ -43,17 +53,75 @@ pub async fn detect_java(custom_path: Option<String>) -> Result<Option<JavaInfo>
     }
 }
 
+/// RAM, CPU threads and OS of this machine, for the settings page.
 #[tauri::command]
-pub async fn check_installation() -> Result<InstallStatus, String> {
+pub async fn get_system_info() -> Result<SystemInfo, String> {
+    tokio::task::spawn_blocking(system::info)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Heap size suited to the instance `instance_id` (the main one by default) on this machine.
+#[tauri::command]
+pub async fn recommend_memory(
+    instance_id: Option<String>,
+) -> Result<MemoryRecommendation, String> {
+    let game_dir = match instance_id {
+        Some(id) => instance::dir(&id)?,
+        None => download::get_game_dir(),
+    };
+    tokio::task::spawn_blocking(move || memory::recommend(&game_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[tauri::command]
+pub async fn check_installation(app: AppHandle) -> Result<InstallStatus, String> {
+    let config = remote_config::load(&app, &mut MetaFetcher::new(false)).await?.config;
     Ok(InstallStatus {
-        installed: download::is_installed(),
+        installed: download::is_installed(&config),
         game_dir: download::get_game_dir().to_string_lossy().to_string(),
+        minecraft_version: config.minecraft_version,
+        hud_mod: hud_mod::status(&download::get_game_dir()),
     })
 }
 
+/// The launcher configuration `install` would use, and whether it came from the server team.
+#[tauri::command]
+pub async fn get_launcher_config(
+    app: AppHandle,
+    offline: Option<bool>,
+) -> Result<LoadedConfig, String> {
+    remote_config::load(&app, &mut MetaFetcher::new(offline.unwrap_or(false))).await
+}
+
 #[tauri::command]
-pub async fn install_minecraft(app: AppHandle) -> Result<(), String> {
-    download::install(&app).await
+pub async fn install_minecraft(
+    app: AppHandle,
+    offline: Option<bool>,
+) -> Result<download::InstallReport, InstallError> {
+    download::install(&app, offline.unwrap_or(false)).await
+}
+
+/// Total and still-needed bytes for the game, loader, mods and (unless `java_path` works) Java.
+#[tauri::command]
+pub async fn estimate_install(
+    app: AppHandle,
+    java_path: Option<String>,
+    offline: Option<bool>,
+) -> Result<InstallEstimate, String> {
+    let mut parts = download::estimate(&app, offline.unwrap_or(false)).await?;
+    let java_available = match java_path {
+        Some(path) => PathBuf::from(path).exists(),
+        None => java::detect_java().is_some(),
+    };
+    parts.push(java::estimate_install(java_available).await);
+    Ok(InstallEstimate::new(&download::get_game_dir(), parts))
+}
+
+#[tauri::command]
+pub async fn verify_files(offline: Option<bool>) -> Result<download::VerifyReport, String> {
+    download::verify(offline.unwrap_or(false)).await
 }
 
 #[tauri::command]
@@ -68,49 +136,91 @@ pub async fn launch_minecraft(
     server_host: String,
     server_port: u16,
     game_dir: Option<String>,
-) -> Result<(), String> {
-    // Check if already running
-    {
-        let running = state
-            .minecraft_running
-            .lock()
-            .map_err(|e| e.to_string())?;
-        if *running {
-            return Err("마인크래프트가 이미 실행 중입니다.".to_string());
-        }
-    }
-
-    // Mark as running
-    {
-        let mut running = state
-            .minecraft_running
-            .lock()
-            .map_err(|e| e.to_string())?;
-        *running = true;
-    }
-
+    ignore_mod_errors: Option<bool>,
+) -> Result<String, String> {
     let game_directory = game_dir
         .map(PathBuf::from)
         .unwrap_or_else(download::get_game_dir);
 
+    // Fails when the session policy or another game's directory lock forbids this one
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let policy: SessionPolicy = settings::load(&app, process::SESSION_POLICY_KEY);
+    state.games.begin_launch(
+        policy,
+        RunningGame {
+            session_id: session_id.clone(),
+            pid: 0,
+            started_at: process::now_secs(),
+            instance_id: instance::id_of(&game_directory),
+            game_dir: game_directory.to_string_lossy().to_string(),
+            player_name: player_name.clone(),
+            player_uuid: player_uuid.clone(),
+            attached: false,
+            stopping: false,
+        },
+    )
+    .await?;
+
+    // Launching never waits on the network; the last verified configuration is enough here
+    let server_config = remote_config::load(&app, &mut MetaFetcher::new(true))
+        .await
+        .map(|loaded| loaded.config)
+        .unwrap_or_else(|_| remote_config::RemoteConfig::bundled());
+    let selected_addr = format!("{}:{}", server_host, server_port);
+    let mut servers = vec![(
+        server_config.server_name_for(&server_host).to_string(),
+        selected_addr.clone(),
+    )];
+    servers.extend(
+        server_config
+            .servers
+            .iter()
+            .map(|s| (s.name.clone(), format!("{}:{}", s.host, s.port)))
+            .filter(|(_, addr)| *addr != selected_addr),
+    );
+
     let config = launch::LaunchConfig {
+        session_id: session_id.clone(),
         java_path: PathBuf::from(java_path),
         max_memory_mb,
         game_dir: game_directory,
         server_host: Some(server_host),
         server_port: Some(server_port),
+        servers,
         player_name,
         player_uuid,
         access_token,
+        ignore_mod_errors: ignore_mod_errors.unwrap_or(false),
     };
 
-    launch::launch(&app, config).await?;
+    if let Err(e) = launch::launch(&app, config).await {
+        // Nothing was spawned, so nothing will release the session on exit
+        state.games.cancel_launch(&session_id);
+        return Err(e);
+    }
+
+    Ok(session_id)
+}
+
+/// Ask the game of `session_id` to quit, killing it after a grace period or right away with
+/// `force`.
+#[tauri::command]
+pub async fn stop_game(
+    state: State<'_, AppState>,
+    session_id: String,
+    force: Option<bool>,
+) -> Result<StopReport, String> {
+    process::stop(&state.games, &session_id, force.unwrap_or(false)).await
+}
 
-    Ok(())
+/// Games started by this launcher, including ones re-attached after a restart.
+#[tauri::command]
+pub fn get_running_games(state: State<'_, AppState>) -> Vec<RunningGame> {
+    state.games.list()
 }
 
 #[tauri::command]
-pub async fn install_java(app: AppHandle) -> Result<String, String> {
+pub async fn install_java(app: AppHandle) -> Result<String, InstallError> {
     crate::minecraft::java::install_java_auto(&app).await
 }
 
@@ -1,3 +1,7 @@
 pub mod auth;
+pub mod diagnostics;
+pub mod instances;
 pub mod minecraft;
+pub mod mods;
 pub mod server;
+pub mod settings;
@@ -0,0 +1,94 @@
+use tauri::AppHandle;
+
+use crate::minecraft::download;
+use crate::minecraft::instance::Instance;
+use crate::minecraft::mod_meta::ScannedMod;
+use crate::minecraft::mod_toggle::{self, SafeModeReport};
+use crate::minecraft::mod_validate::{self, ValidationReport};
+use crate::minecraft::modrinth::{
+    self, ModInstallReport, ModSearchResult, ModUpdateReport, ModrinthVersion,
+};
+
+#[tauri::command]
+pub async fn search_mods(
+    query: String,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<ModSearchResult, String> {
+    modrinth::search(&query, offset.unwrap_or(0), limit.unwrap_or(20)).await
+}
+
+#[tauri::command]
+pub async fn get_mod_versions(project_id: String) -> Result<Vec<ModrinthVersion>, String> {
+    modrinth::list_versions(&project_id).await
+}
+
+#[tauri::command]
+pub async fn install_mod_version(
+    app: AppHandle,
+    version_id: String,
+) -> Result<ModInstallReport, String> {
+    modrinth::install_version(&app, &version_id).await
+}
+
+#[tauri::command]
+pub async fn update_all_mods(app: AppHandle) -> Result<ModUpdateReport, String> {
+    modrinth::update_all(&app).await
+}
+
+/// Mods in the game directory with the metadata read from each jar.
+#[tauri::command]
+pub async fn list_mods() -> Result<Vec<ScannedMod>, String> {
+    let game_dir = download::get_game_dir();
+    tokio::task::spawn_blocking(move || mod_toggle::list(&game_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// The same dependency and conflict check `launch_minecraft` runs before starting Java.
+#[tauri::command]
+pub async fn validate_mods() -> Result<ValidationReport, String> {
+    let game_dir = download::get_game_dir();
+    let instance = Instance::load(&game_dir).ok_or("설치된 버전이 없습니다")?;
+    tokio::task::spawn_blocking(move || {
+        mod_validate::validate_dir(&game_dir.join("mods"), &instance, None)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Turn a mod on or off by renaming it to or from `.jar.disabled`; returns the new file name.
+#[tauri::command]
+pub async fn set_mod_enabled(
+    filename: String,
+    enabled: bool,
+    force: Option<bool>,
+) -> Result<String, String> {
+    let game_dir = download::get_game_dir();
+    tokio::task::spawn_blocking(move || {
+        mod_toggle::set_enabled(&game_dir, &filename, enabled, force.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_safe_mode() -> Result<SafeModeReport, String> {
+    Ok(mod_toggle::safe_mode_status(&download::get_game_dir()))
+}
+
+#[tauri::command]
+pub async fn enter_safe_mode() -> Result<SafeModeReport, String> {
+    let game_dir = download::get_game_dir();
+    tokio::task::spawn_blocking(move || mod_toggle::enter_safe_mode(&game_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub async fn restore_mods() -> Result<SafeModeReport, String> {
+    let game_dir = download::get_game_dir();
+    tokio::task::spawn_blocking(move || mod_toggle::restore(&game_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,80 @@
+use tauri::AppHandle;
+
+use crate::minecraft::curseforge::{self, CurseForgeSettings};
+use crate::minecraft::http::{self, NetworkSettings};
+use crate::minecraft::mclogs::{self, LogUploadSettings};
+use crate::minecraft::process::{self, SessionPolicy};
+use crate::minecraft::remote_config;
+use crate::settings;
+
+#[tauri::command]
+pub async fn get_network_settings(app: AppHandle) -> Result<NetworkSettings, String> {
+    Ok(http::masked(&http::load_settings(&app)))
+}
+
+/// Validate by building a client first so a bad proxy or certificate never replaces a working one.
+#[tauri::command]
+pub async fn set_network_settings(
+    app: AppHandle,
+    mut network: NetworkSettings,
+) -> Result<(), String> {
+    http::unmask(&mut network);
+    http::configure(&network)?;
+    http::save_settings(&app, &network)
+}
+
+/// Point the launcher at another signed configuration; `None` restores the default URL.
+#[tauri::command]
+pub async fn set_launcher_config_url(app: AppHandle, url: Option<String>) -> Result<(), String> {
+    if let Some(ref url) = url {
+        url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    }
+    settings::save(&app, remote_config::CONFIG_URL_KEY, &url)
+}
+
+#[tauri::command]
+pub async fn get_curseforge_settings(app: AppHandle) -> Result<CurseForgeSettings, String> {
+    Ok(settings::load(&app, curseforge::CURSEFORGE_SETTINGS_KEY))
+}
+
+#[tauri::command]
+pub async fn set_curseforge_settings(
+    app: AppHandle,
+    curseforge: CurseForgeSettings,
+) -> Result<(), String> {
+    if let Some(ref base) = curseforge.api_base {
+        if !base.trim().is_empty() {
+            url::Url::parse(base).map_err(|e| format!("Invalid URL: {}", e))?;
+        }
+    }
+    settings::save(&app, curseforge::CURSEFORGE_SETTINGS_KEY, &curseforge)
+}
+
+#[tauri::command]
+pub async fn get_session_policy(app: AppHandle) -> Result<SessionPolicy, String> {
+    Ok(settings::load(&app, process::SESSION_POLICY_KEY))
+}
+
+/// Applies to the next launch; games already running are left alone.
+#[tauri::command]
+pub async fn set_session_policy(app: AppHandle, policy: SessionPolicy) -> Result<(), String> {
+    settings::save(&app, process::SESSION_POLICY_KEY, &policy)
+}
+
+#[tauri::command]
+pub async fn get_log_upload_settings(app: AppHandle) -> Result<LogUploadSettings, String> {
+    Ok(settings::load(&app, mclogs::LOG_UPLOAD_SETTINGS_KEY))
+}
+
+#[tauri::command]
+pub async fn set_log_upload_settings(
+    app: AppHandle,
+    log_upload: LogUploadSettings,
+) -> Result<(), String> {
+    if let Some(ref base) = log_upload.api_base {
+        if !base.trim().is_empty() {
+            url::Url::parse(base).map_err(|e| format!("Invalid URL: {}", e))?;
+        }
+    }
+    settings::save(&app, mclogs::LOG_UPLOAD_SETTINGS_KEY, &log_upload)
+}
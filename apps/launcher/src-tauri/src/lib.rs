@@ -1,10 +1,9 @@
 mod commands;
 mod minecraft;
-
-use std::sync::Mutex;
+mod settings;
 
 pub struct AppState {
-    pub minecraft_running: Mutex<bool>,
+    pub games: minecraft::process::GameRegistry,
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -14,7 +13,15 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(AppState {
-            minecraft_running: Mutex::new(false),
+            games: Default::default(),
+        })
+        .setup(|app| {
+            let network = minecraft::http::load_settings(app.handle());
+            if let Err(e) = minecraft::http::configure(&network) {
+                minecraft::launcher_log::write("WARN", &format!("Network settings ignored: {}", e));
+            }
+            minecraft::process::reattach(app.handle());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::auth::microsoft_auth_start,
@@ -24,9 +31,49 @@ pub fn run() {
             commands::server::ping_server,
             commands::minecraft::check_installation,
             commands::minecraft::install_minecraft,
+            commands::minecraft::verify_files,
+            commands::minecraft::estimate_install,
+            commands::minecraft::get_launcher_config,
             commands::minecraft::launch_minecraft,
+            commands::minecraft::stop_game,
+            commands::minecraft::get_running_games,
             commands::minecraft::detect_java,
+            commands::minecraft::get_system_info,
+            commands::minecraft::recommend_memory,
             commands::minecraft::install_java,
+            commands::instances::list_instances,
+            commands::instances::import_mrpack,
+            commands::instances::export_mrpack,
+            commands::instances::import_curseforge,
+            commands::instances::add_packwiz_instance,
+            commands::instances::sync_packwiz_instance,
+            commands::instances::list_loader_versions,
+            commands::instances::set_loader_version,
+            commands::instances::list_jvm_presets,
+            commands::instances::get_launch_options,
+            commands::instances::set_launch_options,
+            commands::instances::preview_launch_command,
+            commands::mods::list_mods,
+            commands::mods::validate_mods,
+            commands::mods::set_mod_enabled,
+            commands::mods::get_safe_mode,
+            commands::mods::enter_safe_mode,
+            commands::mods::restore_mods,
+            commands::mods::search_mods,
+            commands::mods::get_mod_versions,
+            commands::mods::install_mod_version,
+            commands::mods::update_all_mods,
+            commands::settings::get_network_settings,
+            commands::settings::set_network_settings,
+            commands::settings::set_launcher_config_url,
+            commands::settings::get_curseforge_settings,
+            commands::settings::set_curseforge_settings,
+            commands::settings::get_session_policy,
+            commands::settings::set_session_policy,
+            commands::settings::get_log_upload_settings,
+            commands::settings::set_log_upload_settings,
+            commands::diagnostics::export_diagnostics,
+            commands::diagnostics::upload_log,
 
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::download;
+
+/// Validators stored next to each cached document so the next request can be conditional.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+/// Fetches metadata documents (version manifests, loader meta, mod lookups) through
+/// an on-disk cache using ETag / If-Modified-Since revalidation.
+///
+/// Each URL is requested at most once per fetcher. In offline mode, or when the
+/// network is unreachable, the cached copy is served instead and the remote check
+/// is recorded in `skipped`.
+pub struct MetaFetcher {
+    dir: PathBuf,
+    offline: bool,
+    skipped: Vec<String>,
+    memo: HashMap<String, Vec<u8>>,
+}
+
+impl MetaFetcher {
+    pub fn new(offline: bool) -> Self {
+        Self {
+            dir: get_cache_dir(),
+            offline,
+            skipped: Vec::new(),
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Labels of remote checks that were answered from the cache instead of the network.
+    pub fn into_skipped(self) -> Vec<String> {
+        self.skipped
+    }
+
+    pub async fn fetch_json<T: serde::de::DeserializeOwned>(
+        &mut self,
+        label: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, String> {
+        let body = self.fetch(label, request).await?;
+        serde_json::from_slice(&body).map_err(|e| format!("{} parse failed: {}", label, e))
+    }
+
+    /// Fetch a document once, revalidating the cached copy when one exists.
+    pub async fn fetch(
+        &mut self,
+        label: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Vec<u8>, String> {
+        let (client, request) = request.build_split();
+        let request = request.map_err(|e| format!("{} request failed: {}", label, e))?;
+        let url = request.url().to_string();
+        if let Some(body) = self.memo.get(&url) {
+            return Ok(body.clone());
+        }
+        let body = self.fetch_uncached(label, client, request).await?;
+        self.memo.insert(url, body.clone());
+        Ok(body)
+    }
+
+    async fn fetch_uncached(
+        &mut self,
+        label: &str,
+        client: reqwest::Client,
+        mut request: reqwest::Request,
+    ) -> Result<Vec<u8>, String> {
+        let url = request.url().to_string();
+        let key = cache_key(&url);
+        let entry_path = self.dir.join(format!("{}.json", key));
+        let body_path = self.dir.join(format!("{}.bin", key));
+
+        let entry: Option<CacheEntry> = std::fs::read(&entry_path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .filter(|_| body_path.exists());
+
+        if self.offline {
+            return match entry {
+                Some(_) => {
+                    self.skipped.push(label.to_string());
+                    std::fs::read(&body_path).map_err(|e| format!("Read cache failed: {}", e))
+                }
+                None => Err(format!(
+                    "{}: 오프라인 모드이지만 캐시된 데이터가 없습니다",
+                    label
+                )),
+            };
+        }
+
+        if let Some(ref entry) = entry {
+            let headers = request.headers_mut();
+            if let Some(ref etag) = entry.etag {
+                if let Ok(value) = etag.parse() {
+                    headers.insert(reqwest::header::IF_NONE_MATCH, value);
+                }
+            }
+            if let Some(ref last_modified) = entry.last_modified {
+                if let Ok(value) = last_modified.parse() {
+                    headers.insert(reqwest::header::IF_MODIFIED_SINCE, value);
+                }
+            }
+        }
+
+        let response = match client.execute(request).await {
+            Ok(response) => response,
+            Err(e) if entry.is_some() && (e.is_connect() || e.is_timeout()) => {
+                self.skipped.push(format!("{} (네트워크 연결 실패)", label));
+                return std::fs::read(&body_path).map_err(|e| format!("Read cache failed: {}", e));
+            }
+            Err(e) => return Err(format!("{} fetch failed: {}", label, e)),
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED && entry.is_some() {
+            return std::fs::read(&body_path).map_err(|e| format!("Read cache failed: {}", e));
+        }
+
+        let response = response
+            .error_for_status()
+            .map_err(|e| format!("{} fetch failed: {}", label, e))?;
+
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let new_entry = CacheEntry {
+            url,
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+            fetched_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        };
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| format!("{} read failed: {}", label, e))?;
+
+        // A failed cache write only costs a re-download next time
+        if std::fs::create_dir_all(&self.dir).is_ok() {
+            let _ = std::fs::write(&body_path, &body);
+            if let Ok(json) = serde_json::to_vec(&new_entry) {
+                let _ = std::fs::write(&entry_path, json);
+            }
+        }
+
+        Ok(body.to_vec())
+    }
+}
+
+pub fn get_cache_dir() -> PathBuf {
+    download::get_launcher_dir().join("cache").join("meta")
+}
+
+fn cache_key(url: &str) -> String {
+    use sha1::Digest;
+    hex::encode(sha1::Sha1::digest(url.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn fetcher_in(dir: &CacheDir, offline: bool) -> MetaFetcher {
+        MetaFetcher {
+            dir: dir.0.clone(),
+            offline,
+            skipped: Vec::new(),
+            memo: HashMap::new(),
+        }
+    }
+
+    /// A cache directory, removed on drop.
+    struct CacheDir(PathBuf);
+
+    impl CacheDir {
+        fn new() -> Self {
+            CacheDir(std::env::temp_dir().join(format!("cache-test-{}", uuid::Uuid::new_v4())))
+        }
+
+        /// Store `body` for `url` as an earlier fetch would have.
+        fn seed(&self, url: &str, body: &[u8]) {
+            std::fs::create_dir_all(&self.0).unwrap();
+            let key = cache_key(url);
+            let entry = CacheEntry {
+                url: url.to_string(),
+                etag: None,
+                last_modified: None,
+                fetched_at: 0,
+            };
+            std::fs::write(self.0.join(format!("{}.bin", key)), body).unwrap();
+            std::fs::write(
+                self.0.join(format!("{}.json", key)),
+                serde_json::to_vec(&entry).unwrap(),
+            )
+            .unwrap();
+        }
+    }
+
+    impl Drop for CacheDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Answers one request per reply in `replies`, returning the request headers.
+    async fn stand_in(listener: TcpListener, replies: Vec<String>) -> Vec<String> {
+        let mut headers = Vec::new();
+        for reply in replies {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    headers.push(text[..end].to_ascii_lowercase());
+                    break;
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            stream.write_all(reply.as_bytes()).await.unwrap();
+        }
+        headers
+    }
+
+    fn ok(body: &str, etag: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            etag,
+            body.len(),
+            body
+        )
+    }
+
+    const NOT_MODIFIED: &str =
+        "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    #[tokio::test]
+    async fn caches_and_revalidates() {
+        let dir = CacheDir::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/manifest.json", listener.local_addr().unwrap());
+        let server = tokio::spawn(stand_in(
+            listener,
+            vec![ok("{\"v\":1}", "\"v1\""), NOT_MODIFIED.to_string()],
+        ));
+        let client = reqwest::Client::new();
+
+        let mut first = fetcher_in(&dir, false);
+        assert_eq!(
+            first.fetch("manifest", client.get(&url)).await.unwrap(),
+            b"{\"v\":1}"
+        );
+        // Asked again by the same fetcher: answered from memory without a request
+        assert_eq!(
+            first.fetch("manifest", client.get(&url)).await.unwrap(),
+            b"{\"v\":1}"
+        );
+
+        let mut second = fetcher_in(&dir, false);
+        let body: serde_json::Value = second
+            .fetch_json("manifest", client.get(&url))
+            .await
+            .unwrap();
+        assert_eq!(body["v"], 1);
+        assert!(second.into_skipped().is_empty());
+
+        let headers = server.await.unwrap();
+        assert_eq!(headers.len(), 2);
+        assert!(!headers[0].contains("if-none-match"));
+        assert!(
+            headers[1].contains("if-none-match: \"v1\""),
+            "{}",
+            headers[1]
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_serves_cache_or_fails() {
+        let dir = CacheDir::new();
+        let url = "http://127.0.0.1:1/manifest.json";
+        let client = reqwest::Client::new();
+
+        let mut fetcher = fetcher_in(&dir, true);
+        let err = fetcher.fetch("manifest", client.get(url)).await;
+        assert!(err.err().unwrap().contains("캐시된 데이터가 없습니다"));
+
+        dir.seed(url, b"cached");
+        let mut fetcher = fetcher_in(&dir, true);
+        assert_eq!(
+            fetcher.fetch("manifest", client.get(url)).await.unwrap(),
+            b"cached"
+        );
+        assert_eq!(fetcher.into_skipped(), ["manifest"]);
+    }
+
+    #[tokio::test]
+    async fn unreachable_network_falls_back_to_cache() {
+        let dir = CacheDir::new();
+        // Bound and dropped, so nothing listens there
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let url = format!("http://{}/manifest.json", addr);
+        let client = reqwest::Client::new();
+
+        let mut fetcher = fetcher_in(&dir, false);
+        assert!(fetcher.fetch("manifest", client.get(&url)).await.is_err());
+
+        dir.seed(&url, b"cached");
+        let mut fetcher = fetcher_in(&dir, false);
+        assert_eq!(
+            fetcher.fetch("manifest", client.get(&url)).await.unwrap(),
+            b"cached"
+        );
+        assert_eq!(fetcher.into_skipped(), ["manifest (네트워크 연결 실패)"]);
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_errors() {
+        let dir = CacheDir::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/manifest.json", listener.local_addr().unwrap());
+        let error =
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let server = tokio::spawn(stand_in(listener, vec![error.to_string()]));
+
+        let mut fetcher = fetcher_in(&dir, false);
+        let client = reqwest::Client::new();
+        assert!(fetcher.fetch("manifest", client.get(&url)).await.is_err());
+        server.await.unwrap();
+
+        let mut offline = fetcher_in(&dir, true);
+        assert!(offline.fetch("manifest", client.get(&url)).await.is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::redact;
+
+const MAX_MIXIN_FAILURES: usize = 10;
+
+struct KnownCause {
+    id: &'static str,
+    /// Matched case-insensitively against the report (without its system details) and the log.
+    patterns: &'static [&'static str],
+    message: &'static str,
+}
+
+const KNOWN_CAUSES: &[KnownCause] = &[
+    KnownCause {
+        id: "wrongJava",
+        patterns: &[
+            "unsupportedclassversionerror",
+            "compiled by a more recent version of the java runtime",
+            "incompatible java version",
+            "requires java",
+        ],
+        message: "Java 버전이 맞지 않습니다. 이 버전에 맞는 Java로 실행해주세요.",
+    },
+    KnownCause {
+        id: "outOfMemory",
+        patterns: &[
+            "java.lang.outofmemoryerror",
+            "insufficient memory for the java runtime environment",
+            "could not reserve enough space",
+            "native memory allocation (malloc) failed",
+        ],
+        message: "메모리가 부족합니다. 할당 메모리를 조정하거나 다른 프로그램을 종료해주세요.",
+    },
+    KnownCause {
+        id: "missingDependency",
+        patterns: &[
+            "incompatible mods found",
+            "modresolutionexception",
+            "mod resolution failed",
+            "missing or unsupported mandatory dependencies",
+            "which is missing!",
+            "requires any version of",
+        ],
+        message: "필요한 모드가 없거나 버전이 맞지 않습니다. 모드 의존성을 확인해주세요.",
+    },
+    KnownCause {
+        id: "graphicsDriver",
+        patterns: &[
+            "pixel format not accelerated",
+            "glfw error 65542",
+            "glfw error 65543",
+            "the driver does not appear to support opengl",
+            "failed to create opengl context",
+            "atio6axx.dll",
+            "atioglxx.dll",
+            "nvoglv64.dll",
+            "ig9icd64.dll",
+            "ig75icd64.dll",
+            "libnvidia-glcore",
+        ],
+        message: "그래픽 드라이버 또는 OpenGL 오류입니다. 그래픽 드라이버를 업데이트해주세요.",
+    },
+];
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashCause {
+    pub id: String,
+    pub message: String,
+}
+
+/// Payload of the `game-crashed` event.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub session_id: String,
+    pub exit_code: i32,
+    /// `crash-reports/…` or `hs_err_pid….log` written during the session, if any. Kept as is
+    /// so the UI can open it; every text field below is redacted.
+    pub report_path: Option<String>,
+    pub description: Option<String>,
+    pub exception: Option<String>,
+    pub suspected_mods: Vec<String>,
+    pub mixin_failures: Vec<String>,
+    /// Known causes the report or log matched.
+    pub causes: Vec<CrashCause>,
+}
+
+/// Newest file in `dir` accepted by `matches` that was written at or after `since`.
+fn newest_since(dir: &Path, since: SystemTime, matches: impl Fn(&str) -> bool) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| matches(&entry.file_name().to_string_lossy()))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            (modified >= since).then(|| (modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Text after `prefix` on the first line starting with it.
+fn field<'a>(lines: &[&'a str], prefix: &str) -> Option<&'a str> {
+    lines
+        .iter()
+        .find_map(|line| line.trim().strip_prefix(prefix))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// "Suspected Mods: A (a), B (b)" or the same list on the indented lines below it.
+fn suspected_mods(lines: &[&str]) -> Vec<String> {
+    let Some(start) = lines
+        .iter()
+        .position(|l| l.trim().starts_with("Suspected Mods:"))
+    else {
+        return Vec::new();
+    };
+    let inline = lines[start].trim()["Suspected Mods:".len()..].trim();
+    let entries: Vec<&str> = if inline.is_empty() {
+        lines[start + 1..]
+            .iter()
+            .take_while(|l| l.starts_with('\t') || l.starts_with(' '))
+            .map(|l| l.trim())
+            .collect()
+    } else {
+        inline.split(',').map(str::trim).collect()
+    };
+    entries
+        .into_iter()
+        .filter(|e| {
+            !e.is_empty() && !e.eq_ignore_ascii_case("none") && !e.eq_ignore_ascii_case("unknown")
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+fn mixin_failures(lines: &[&str]) -> Vec<String> {
+    let mut failures: Vec<String> = Vec::new();
+    for line in lines {
+        let lower = line.to_lowercase();
+        let is_failure = lower.contains("mixin apply")
+            || lower.contains("mixinapplyerror")
+            || lower.contains("invalidinjectionexception")
+            || lower.contains("mixintransformererror");
+        let line = line.trim().to_string();
+        if is_failure && !failures.contains(&line) {
+            failures.push(line);
+        }
+        if failures.len() >= MAX_MIXIN_FAILURES {
+            break;
+        }
+    }
+    failures
+}
+
+/// The part of a crash report worth matching; the system details list every mod and the
+/// GPU, which would match almost anything.
+fn without_system_details(text: &str) -> &str {
+    text.split("-- System Details --").next().unwrap_or(text)
+}
+
+/// The `#` header of an hs_err file and its problematic frame; the rest lists every loaded
+/// library.
+fn hs_err_summary(text: &str) -> String {
+    text.lines()
+        .take_while(|line| line.starts_with('#') || line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Look at what the session left behind after the game `game_dir` exited with `exit_code`.
+///
+/// `since` is when the game was spawned; older crash reports belong to earlier sessions.
+pub fn analyze(
+    session_id: &str,
+    game_dir: &Path,
+    since: SystemTime,
+    exit_code: i32,
+    log_tail: &[String],
+) -> CrashReport {
+    let crash_report = newest_since(&game_dir.join("crash-reports"), since, |name| {
+        name.starts_with("crash-") && name.ends_with(".txt")
+    });
+    let hs_err = newest_since(game_dir, since, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    });
+
+    let mut report = CrashReport {
+        session_id: session_id.to_string(),
+        exit_code,
+        report_path: None,
+        description: None,
+        exception: None,
+        suspected_mods: Vec::new(),
+        mixin_failures: Vec::new(),
+        causes: Vec::new(),
+    };
+    let mut haystack = log_tail.join("\n");
+
+    if let Some(path) = crash_report {
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        report.description = field(&lines, "Description:").map(str::to_string);
+        // The exception is the first line after the description's blank separator
+        report.exception = lines
+            .iter()
+            .skip_while(|l| !l.starts_with("Description:"))
+            .skip(1)
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty())
+            .map(str::to_string);
+        report.suspected_mods = suspected_mods(&lines);
+        haystack.push('\n');
+        haystack.push_str(without_system_details(&text));
+        report.report_path = Some(path.to_string_lossy().to_string());
+    } else if let Some(path) = hs_err {
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        let summary = hs_err_summary(&text);
+        let lines: Vec<&str> = summary.lines().collect();
+        report.description = lines
+            .iter()
+            .map(|l| l.trim_start_matches('#').trim())
+            .find(|l| !l.is_empty())
+            .map(str::to_string);
+        // "# Problematic frame:" is followed by "# C  [atio6axx.dll+0x…]"
+        report.exception = lines
+            .iter()
+            .skip_while(|l| !l.contains("Problematic frame:"))
+            .nth(1)
+            .map(|l| l.trim_start_matches('#').trim().to_string());
+        haystack.push('\n');
+        haystack.push_str(&summary);
+        report.report_path = Some(path.to_string_lossy().to_string());
+    }
+
+    let lines: Vec<&str> = haystack.lines().collect();
+    report.mixin_failures = mixin_failures(&lines);
+    let haystack = haystack.to_lowercase();
+    report.causes = KNOWN_CAUSES
+        .iter()
+        .filter(|cause| cause.patterns.iter().any(|p| haystack.contains(p)))
+        .map(|cause| CrashCause {
+            id: cause.id.to_string(),
+            message: cause.message.to_string(),
+        })
+        .collect();
+    report.description = report.description.as_deref().map(redact::redact);
+    report.exception = report.exception.as_deref().map(redact::redact);
+    report.suspected_mods = report
+        .suspected_mods
+        .iter()
+        .map(|m| redact::redact(m))
+        .collect();
+    report.mixin_failures = report
+        .mixin_failures
+        .iter()
+        .map(|m| redact::redact(m))
+        .collect();
+    if !report.mixin_failures.is_empty() {
+        report.causes.push(CrashCause {
+            id: "mixinFailure".to_string(),
+            message: "모드의 Mixin 적용에 실패했습니다. 모드 버전과 호환성을 확인해주세요."
+                .to_string(),
+        });
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FABRIC_REPORT: &str = "---- Minecraft Crash Report ----
+// Who set us up the TNT?
+
+Time: 2026-01-02 03:04:05
+Description: Initializing game
+
+java.lang.RuntimeException: Could not execute entrypoint stage 'client' due to errors, provided by 'examplemod' at 'com.example.ExampleClient'!
+\tat net.fabricmc.loader.impl.FabricLoaderImpl.invokeEntrypoints(FabricLoaderImpl.java:388)
+Caused by: org.spongepowered.asm.mixin.injection.throwables.InvalidInjectionException: Critical injection failure: @Inject annotation on onRender could not find any targets matching 'render' [PREINJECT Applicator Phase -> examplemod.mixins.json:HudMixin from mod examplemod]
+\tat org.spongepowered.asm.mixin.injection.struct.InjectionInfo.validateTargets(InjectionInfo.java:656)
+
+
+A detailed walkthrough of the error, its code path and all known details is as follows:
+---------------------------------------------------------------------------------------
+
+-- Head --
+Thread: Render thread
+Suspected Mods: Example Mod (examplemod), Fabric API (fabric-api)
+Stacktrace:
+\tat net.fabricmc.loader.impl.FabricLoaderImpl.invokeEntrypoints(FabricLoaderImpl.java:388)
+
+-- System Details --
+Details:
+\tMinecraft Version: 1.21.11
+\tJava Version: 21.0.2, Eclipse Adoptium
+\tFabric Mods: 
+\t\tatifix: atio6axx.dll crash fix 1.0.0
+\t\texamplemod: Example Mod 1.0.0
+\tGraphics card #0 name: NVIDIA GeForce RTX 3070
+";
+
+    const HS_ERR: &str = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffb2c8e1234, pid=1234, tid=5678
+#
+# JRE version: OpenJDK Runtime Environment Temurin-21.0.2+13 (21.0.2+13) (build 21.0.2+13-LTS)
+# Problematic frame:
+# C  [atio6axx.dll+0x1a1234]
+#
+# The crash happened outside the Java Virtual Machine in native code.
+#
+
+---------------  S U M M A R Y ------------
+
+Internal exceptions (1 events):
+Event: 2.345 Thread 0x000001f2a1b2c3d0 Exception <a 'java/lang/UnsupportedClassVersionError'{0x0000000712345678}> (0x0000000712345678)
+";
+
+    /// A game directory with the given files in it, removed on drop.
+    struct GameDir(PathBuf);
+
+    impl GameDir {
+        fn with(files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!("crash-test-{}", uuid::Uuid::new_v4()));
+            for (path, text) in files {
+                let path = dir.join(path);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, text).unwrap();
+            }
+            GameDir(dir)
+        }
+    }
+
+    impl Drop for GameDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn cause_ids(report: &CrashReport) -> Vec<&str> {
+        report.causes.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn reads_fabric_crash_report() {
+        let dir = GameDir::with(&[(
+            "crash-reports/crash-2026-01-02_03.04.05-client.txt",
+            FABRIC_REPORT,
+        )]);
+        let report = analyze("session", &dir.0, SystemTime::UNIX_EPOCH, -1, &[]);
+        assert!(report
+            .report_path
+            .as_deref()
+            .is_some_and(|p| p.ends_with("crash-2026-01-02_03.04.05-client.txt")));
+        assert_eq!(report.description.as_deref(), Some("Initializing game"));
+        assert!(report
+            .exception
+            .as_deref()
+            .is_some_and(|e| e.starts_with("java.lang.RuntimeException: Could not execute")));
+        assert_eq!(
+            report.suspected_mods,
+            ["Example Mod (examplemod)", "Fabric API (fabric-api)"]
+        );
+        assert_eq!(report.mixin_failures.len(), 1);
+        assert!(report.mixin_failures[0].contains("InvalidInjectionException"));
+        // atio6axx.dll only appears in the mod list of the system details
+        assert_eq!(cause_ids(&report), ["mixinFailure"]);
+    }
+
+    #[test]
+    fn reads_indented_suspected_mods() {
+        let text = "Suspected Mods: \n\tExample Mod (examplemod)\n\tSodium (sodium)\nStacktrace:\n";
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            suspected_mods(&lines),
+            ["Example Mod (examplemod)", "Sodium (sodium)"]
+        );
+
+        assert!(suspected_mods(&["Suspected Mods: NONE"]).is_empty());
+        assert!(suspected_mods(&["Suspected Mods: Unknown"]).is_empty());
+        assert!(suspected_mods(&["Thread: Render thread"]).is_empty());
+    }
+
+    #[test]
+    fn matches_known_causes_in_log_tail() {
+        let dir = GameDir::with(&[]);
+        let tail = vec![
+            "Error: LinkageError occurred while loading main class net.fabricmc.loader.impl.launch.knot.KnotClient".to_string(),
+            "java.lang.UnsupportedClassVersionError: has been compiled by a more recent version of the Java Runtime".to_string(),
+        ];
+        let report = analyze("session", &dir.0, SystemTime::UNIX_EPOCH, 1, &tail);
+        assert!(report.report_path.is_none());
+        assert!(report.description.is_none());
+        assert_eq!(cause_ids(&report), ["wrongJava"]);
+    }
+
+    #[test]
+    fn reads_hs_err_header() {
+        assert!(!hs_err_summary(HS_ERR).contains("Internal exceptions"));
+
+        let dir = GameDir::with(&[("hs_err_pid1234.log", HS_ERR)]);
+        let report = analyze("session", &dir.0, SystemTime::UNIX_EPOCH, 1, &[]);
+        assert!(report
+            .report_path
+            .as_deref()
+            .is_some_and(|p| p.ends_with("hs_err_pid1234.log")));
+        assert_eq!(
+            report.description.as_deref(),
+            Some("A fatal error has been detected by the Java Runtime Environment:")
+        );
+        assert_eq!(
+            report.exception.as_deref(),
+            Some("C  [atio6axx.dll+0x1a1234]")
+        );
+        // The event log after the header is not matched
+        assert_eq!(cause_ids(&report), ["graphicsDriver"]);
+    }
+}
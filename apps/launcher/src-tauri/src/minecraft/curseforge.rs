@@ -0,0 +1,547 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::disk::InstallError;
+use super::download::{self, emit_progress};
+use super::http;
+use super::instance::{self, Instance};
+use super::mod_sync;
+use super::mrpack;
+use super::remote_config::LoaderSpec;
+use crate::settings;
+
+pub const CURSEFORGE_SETTINGS_KEY: &str = "curseforge_settings";
+
+pub const DEFAULT_API_BASE: &str = "https://api.curseforge.com";
+
+/// API key for the official CurseForge API, injected at build time.
+const BUILD_API_KEY: Option<&str> = option_env!("NYARU_CURSEFORGE_API_KEY");
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// CurseForge `classId` of resource packs and shader packs; everything else goes to `mods/`.
+const CLASS_RESOURCE_PACKS: u64 = 12;
+const CLASS_SHADERS: u64 = 6552;
+
+/// Which CurseForge-compatible API resolves pack files; any server implementing
+/// `POST /v1/mods` and `POST /v1/mods/files` works.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeSettings {
+    /// `None` uses the official API.
+    pub api_base: Option<String>,
+    /// Sent as `x-api-key`; `None` uses the key built into the launcher, if any.
+    pub api_key: Option<String>,
+}
+
+impl CurseForgeSettings {
+    fn api_base(&self) -> &str {
+        self.api_base
+            .as_deref()
+            .map(|b| b.trim().trim_end_matches('/'))
+            .filter(|b| !b.is_empty())
+            .unwrap_or(DEFAULT_API_BASE)
+    }
+
+    /// The built-in key is only ever sent to the official API.
+    fn api_key(&self) -> Option<&str> {
+        let user_key = self.api_key.as_deref().filter(|k| !k.trim().is_empty());
+        if self.api_base() == DEFAULT_API_BASE {
+            user_key.or(BUILD_API_KEY)
+        } else {
+            user_key
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseManifest {
+    minecraft: CurseMinecraft,
+    #[serde(default)]
+    manifest_type: String,
+    name: String,
+    files: Vec<CurseManifestFile>,
+    #[serde(default = "default_overrides")]
+    overrides: String,
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseMinecraft {
+    version: String,
+    #[serde(default)]
+    mod_loaders: Vec<CurseModLoader>,
+}
+
+#[derive(Deserialize)]
+struct CurseModLoader {
+    id: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Deserialize)]
+struct CurseManifestFile {
+    #[serde(rename = "projectID")]
+    project_id: u64,
+    #[serde(rename = "fileID")]
+    file_id: u64,
+    #[serde(default = "default_required")]
+    required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct ApiResponse<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseFile {
+    id: u64,
+    file_name: String,
+    /// `null` when the author does not allow third-party downloads.
+    download_url: Option<String>,
+    #[serde(default)]
+    file_length: u64,
+    #[serde(default)]
+    hashes: Vec<CurseHash>,
+}
+
+#[derive(Deserialize)]
+struct CurseHash {
+    value: String,
+    /// 1 = sha1, 2 = md5
+    algo: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseMod {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    class_id: Option<u64>,
+    #[serde(default)]
+    links: Option<CurseLinks>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseLinks {
+    website_url: Option<String>,
+}
+
+/// A file the player has to download from the CurseForge website and put in `target_dir`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualDownload {
+    pub project_id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub file_name: String,
+    pub url: Option<String>,
+    /// Absolute directory the file belongs in.
+    pub target_dir: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseImportReport {
+    pub instance_id: String,
+    pub name: String,
+    pub game_dir: String,
+    pub minecraft_version: String,
+    pub loader: Option<String>,
+    pub downloaded: usize,
+    pub overrides: usize,
+    /// Files whose authors disallow downloads outside CurseForge.
+    pub manual: Vec<ManualDownload>,
+    /// Files the pack lists as disabled.
+    pub skipped: Vec<String>,
+}
+
+struct PlannedFile {
+    project: String,
+    file: CurseFile,
+    class_id: Option<u64>,
+    website_url: Option<String>,
+    project_id: u64,
+}
+
+impl PlannedFile {
+    fn dir(&self) -> &'static str {
+        match self.class_id {
+            Some(CLASS_RESOURCE_PACKS) => "resourcepacks",
+            Some(CLASS_SHADERS) => "shaderpacks",
+            _ => "mods",
+        }
+    }
+}
+
+fn read_manifest(pack_path: &Path) -> Result<CurseManifest, String> {
+    let data = mrpack::read_manifest(pack_path, MANIFEST_FILE)?;
+    let json = String::from_utf8_lossy(&data);
+    serde_json::from_str(json.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("{} parse failed: {}", MANIFEST_FILE, e))
+}
+
+/// `fabric-0.16.10` → Fabric 0.16.10, likewise `quilt-`, `forge-` and `neoforge-`; the primary
+/// loader wins when several are listed.
+fn loader_spec(loaders: &[CurseModLoader]) -> Result<LoaderSpec, String> {
+    let loader = loaders
+        .iter()
+        .find(|l| l.primary)
+        .or(loaders.first())
+        .ok_or("모드팩에 로더 정보가 없습니다")?;
+    match loader.id.split_once('-') {
+        Some((kind @ ("fabric" | "quilt" | "forge" | "neoforge"), version)) => Ok(LoaderSpec {
+            kind: kind.to_string(),
+            version: Some(version.to_string()),
+        }),
+        _ => Err(format!("지원하지 않는 로더입니다: {}", loader.id)),
+    }
+}
+
+async fn post<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    settings: &CurseForgeSettings,
+    path: &str,
+    body: serde_json::Value,
+) -> Result<T, String> {
+    let mut request = client
+        .post(format!("{}{}", settings.api_base(), path))
+        .json(&body);
+    if let Some(key) = settings.api_key() {
+        request = request.header("x-api-key", key);
+    }
+    let response: ApiResponse<T> = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("CurseForge 요청 실패: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("CurseForge 응답 parse failed: {}", e))?;
+    Ok(response.data)
+}
+
+/// Look up every required manifest entry; nothing is written before all of them resolve.
+async fn resolve(
+    client: &reqwest::Client,
+    settings: &CurseForgeSettings,
+    entries: &[&CurseManifestFile],
+) -> Result<Vec<PlannedFile>, String> {
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let file_ids: Vec<u64> = entries.iter().map(|f| f.file_id).collect();
+    let mod_ids: Vec<u64> = entries.iter().map(|f| f.project_id).collect();
+    let files: Vec<CurseFile> = post(
+        client,
+        settings,
+        "/v1/mods/files",
+        serde_json::json!({ "fileIds": file_ids }),
+    )
+    .await?;
+    let mods: Vec<CurseMod> = post(
+        client,
+        settings,
+        "/v1/mods",
+        serde_json::json!({ "modIds": mod_ids }),
+    )
+    .await?;
+
+    let mut files: HashMap<u64, CurseFile> = files.into_iter().map(|f| (f.id, f)).collect();
+    let mods: HashMap<u64, CurseMod> = mods.into_iter().map(|m| (m.id, m)).collect();
+    entries
+        .iter()
+        .map(|entry| {
+            let file = files.remove(&entry.file_id).ok_or_else(|| {
+                format!(
+                    "CurseForge에서 파일을 찾을 수 없습니다: {}/{}",
+                    entry.project_id, entry.file_id
+                )
+            })?;
+            let is_plain = Path::new(&file.file_name)
+                .file_name()
+                .and_then(|n| n.to_str())
+                == Some(file.file_name.as_str());
+            if !is_plain {
+                return Err(format!("잘못된 파일 이름입니다: {}", file.file_name));
+            }
+            let project = mods.get(&entry.project_id);
+            Ok(PlannedFile {
+                project: project
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| entry.project_id.to_string()),
+                class_id: project.and_then(|m| m.class_id),
+                website_url: project
+                    .and_then(|m| m.links.as_ref())
+                    .and_then(|l| l.website_url.clone()),
+                project_id: entry.project_id,
+                file,
+            })
+        })
+        .collect()
+}
+
+async fn download_planned(
+    client: &reqwest::Client,
+    planned: &PlannedFile,
+    url: &str,
+    target: &Path,
+) -> Result<(), String> {
+    let data = download::download_bytes(client, url).await?;
+    let sha1 = planned
+        .file
+        .hashes
+        .iter()
+        .find(|h| h.algo == 1)
+        .map(|h| h.value.as_str());
+    match sha1 {
+        Some(sha1) => mod_sync::verify_hashes(&data, Some(sha1), None)
+            .map_err(|e| format!("{}: {}", planned.file.file_name, e))?,
+        None if planned.file.file_length != data.len() as u64 => {
+            return Err(format!("{}: 파일 크기가 다릅니다", planned.file.file_name));
+        }
+        None => {}
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(target, &data).map_err(|e| format!("Write file failed: {}", e))
+}
+
+/// Import a CurseForge modpack export into a new instance.
+///
+/// Files whose authors disallow third-party downloads are not an error; they are listed in
+/// `manual` so the player can fetch them from the website. The instance directory is
+/// removed again when anything else fails.
+pub async fn import(
+    app: &AppHandle,
+    pack_path: &Path,
+    name: Option<String>,
+) -> Result<CurseImportReport, InstallError> {
+    let manifest = read_manifest(pack_path)?;
+    if !manifest.manifest_type.is_empty() && manifest.manifest_type != "minecraftModpack" {
+        return Err(format!(
+            "지원하지 않는 모드팩 형식입니다: {}",
+            manifest.manifest_type
+        )
+        .into());
+    }
+    let loader = loader_spec(&manifest.minecraft.mod_loaders)?;
+
+    let settings: CurseForgeSettings = settings::load(app, CURSEFORGE_SETTINGS_KEY);
+    let client = http::client();
+    let (required, optional): (Vec<&CurseManifestFile>, Vec<&CurseManifestFile>) =
+        manifest.files.iter().partition(|f| f.required);
+    emit_progress(app, "CurseForge 파일 확인 중...", "curseforge", 0, 1, 0.0);
+    let planned = resolve(&client, &settings, &required).await?;
+
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| manifest.name.clone());
+    let (instance_id, dir) = instance::create_dir(&name)?;
+
+    let result = import_into(app, &client, pack_path, &manifest, &loader, &planned, &dir).await;
+    let (mut instance, downloaded, overrides, manual) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    instance.name = Some(name.clone());
+    instance.save(&dir)?;
+
+    emit_progress(app, "모드팩 가져오기 완료!", "complete", 1, 1, 100.0);
+    Ok(CurseImportReport {
+        instance_id,
+        name,
+        game_dir: dir.to_string_lossy().to_string(),
+        minecraft_version: manifest.minecraft.version,
+        loader: instance.loader.map(|l| format!("{} {}", l.kind, l.version)),
+        downloaded,
+        overrides,
+        manual,
+        skipped: optional
+            .iter()
+            .map(|f| format!("{}/{}", f.project_id, f.file_id))
+            .collect(),
+    })
+}
+
+async fn import_into(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    pack_path: &Path,
+    manifest: &CurseManifest,
+    loader: &LoaderSpec,
+    planned: &[PlannedFile],
+    dir: &Path,
+) -> Result<(Instance, usize, usize, Vec<ManualDownload>), InstallError> {
+    let mut fetcher = MetaFetcher::new(false);
+    let pack_bytes: u64 = planned
+        .iter()
+        .filter(|p| p.file.download_url.is_some())
+        .map(|p| p.file.file_length)
+        .sum();
+    let instance = download::install_game(
+        app,
+        client,
+        &mut fetcher,
+        &manifest.minecraft.version,
+        loader,
+        pack_bytes,
+    )
+    .await?;
+
+    let total = planned.len() as u64;
+    let mut downloaded = 0;
+    let mut manual = Vec::new();
+    for (i, p) in planned.iter().enumerate() {
+        let target_dir = dir.join(p.dir());
+        let Some(ref url) = p.file.download_url else {
+            manual.push(ManualDownload {
+                project_id: p.project_id,
+                file_id: p.file.id,
+                name: p.project.clone(),
+                file_name: p.file.file_name.clone(),
+                url: p
+                    .website_url
+                    .as_ref()
+                    .map(|site| format!("{}/files/{}", site.trim_end_matches('/'), p.file.id)),
+                target_dir: target_dir.to_string_lossy().to_string(),
+            });
+            continue;
+        };
+        emit_progress(
+            app,
+            &format!("모드팩 파일: {}", p.file.file_name),
+            "curseforge",
+            i as u64,
+            total,
+            100.0 * i as f64 / total.max(1) as f64,
+        );
+        download_planned(client, p, url, &target_dir.join(&p.file.file_name)).await?;
+        downloaded += 1;
+    }
+
+    emit_progress(
+        app,
+        "덮어쓰기 파일 적용 중...",
+        "curseforge",
+        total,
+        total,
+        100.0,
+    );
+    let prefix = format!("{}/", manifest.overrides.trim_end_matches('/'));
+    let overrides = mrpack::extract_overrides(pack_path, &[prefix.as_str()], dir)?;
+    Ok((instance, downloaded, overrides, manual))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers `count` API requests with an empty `data` list, returning their headers.
+    async fn stand_in(listener: TcpListener, count: usize) -> Vec<String> {
+        let mut headers = Vec::new();
+        for _ in 0..count {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        headers.push(text[..end].to_ascii_lowercase());
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let body = r#"{"data":[]}"#;
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(reply.as_bytes()).await.unwrap();
+        }
+        headers
+    }
+
+    #[test]
+    fn keeps_builtin_key_for_official_api() {
+        let custom = CurseForgeSettings {
+            api_base: Some("http://127.0.0.1:1".into()),
+            api_key: None,
+        };
+        assert_eq!(custom.api_key(), None);
+
+        let own_key = CurseForgeSettings {
+            api_base: Some("http://127.0.0.1:1".into()),
+            api_key: Some("mine".into()),
+        };
+        assert_eq!(own_key.api_key(), Some("mine"));
+
+        assert_eq!(CurseForgeSettings::default().api_key(), BUILD_API_KEY);
+    }
+
+    #[tokio::test]
+    async fn sends_no_key_to_custom_api() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let settings = CurseForgeSettings {
+            api_base: Some(format!("http://{}", listener.local_addr().unwrap())),
+            api_key: None,
+        };
+        let server = tokio::spawn(stand_in(listener, 2));
+
+        let entry = CurseManifestFile {
+            project_id: 1,
+            file_id: 2,
+            required: true,
+        };
+        let error = resolve(&reqwest::Client::new(), &settings, &[&entry])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error, "CurseForge에서 파일을 찾을 수 없습니다: 1/2");
+
+        let headers = server.await.unwrap();
+        assert_eq!(headers.len(), 2);
+        assert!(headers.iter().all(|h| !h.contains("x-api-key")));
+    }
+}
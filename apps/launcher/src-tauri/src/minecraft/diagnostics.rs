@@ -0,0 +1,190 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::download;
+use super::game_log;
+use super::hud_mod;
+use super::instance;
+use super::java;
+use super::launcher_log;
+use super::process;
+use super::redact;
+use super::remote_config;
+use super::system;
+use crate::settings;
+
+/// Session logs included when the caller does not say.
+pub const DEFAULT_SESSION_LOGS: usize = 3;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub path: String,
+    /// Entries written to the zip.
+    pub files: Vec<String>,
+}
+
+/// The value, or what went wrong getting it, so one failing part does not lose the others.
+fn or_error<T: Serialize>(result: Result<T, String>) -> Value {
+    match result {
+        Ok(value) => serde_json::to_value(value).unwrap_or(Value::Null),
+        Err(e) => json!({ "error": e }),
+    }
+}
+
+fn json_entry(name: &str, value: &Value) -> (String, Vec<u8>) {
+    let redacted = redact::redact_json(value);
+    let data = serde_json::to_vec_pretty(&redacted).unwrap_or_default();
+    (name.to_string(), data)
+}
+
+fn text_entry(name: String, path: &Path) -> Option<(String, Vec<u8>)> {
+    let data = std::fs::read(path).ok()?;
+    let text = redact::redact(&String::from_utf8_lossy(&data));
+    Some((name, text.into_bytes()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Newest crash report or JVM error file of any instance.
+fn latest_crash_report() -> Option<PathBuf> {
+    instance::list()
+        .iter()
+        .flat_map(|summary| {
+            let game_dir = PathBuf::from(&summary.game_dir);
+            let reports = std::fs::read_dir(game_dir.join("crash-reports"))
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("crash-"));
+            let hs_errs = std::fs::read_dir(&game_dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("hs_err_pid"));
+            reports.chain(hs_errs).collect::<Vec<_>>()
+        })
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn java_report() -> Value {
+    let describe = |path: Option<PathBuf>| {
+        path.map(|path| {
+            json!({
+                "path": path.to_string_lossy(),
+                "version": java::get_java_version(&path),
+            })
+        })
+    };
+    json!({
+        "detected": describe(java::detect_java()),
+        "managed": describe(java::find_managed_java()),
+        "javaHome": std::env::var("JAVA_HOME").ok(),
+    })
+}
+
+async fn install_report(app: &AppHandle) -> Value {
+    // The cached configuration is enough; a bundle is often exported while offline
+    let loaded = remote_config::load(app, &mut MetaFetcher::new(true)).await;
+    let game_dir = download::get_game_dir();
+    json!({
+        "gameDir": game_dir.to_string_lossy(),
+        "installed": loaded.as_ref().ok().map(|l| download::is_installed(&l.config)),
+        "config": or_error(loaded),
+        "instance": instance::Instance::load(&game_dir),
+        "hudMod": hud_mod::status(&game_dir),
+    })
+}
+
+fn write_zip(output: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let file = std::fs::File::create(output).map_err(|e| format!("진단 파일 생성 실패: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, data) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(data).map_err(|e| e.to_string())?;
+    }
+    zip.finish()
+        .map_err(|e| format!("진단 파일 저장 실패: {}", e))?;
+    Ok(())
+}
+
+/// Bundle what is needed to look into a problem into a zip at `output`. Every text and JSON
+/// file is redacted; parts that cannot be collected are recorded as errors instead.
+pub async fn export(
+    app: &AppHandle,
+    output: &Path,
+    session_logs: usize,
+) -> Result<DiagnosticsReport, String> {
+    let mut entries = Vec::new();
+
+    let system = tokio::task::spawn_blocking(system::info).await.ok();
+    entries.push(json_entry(
+        "system.json",
+        &json!({
+            "launcherVersion": app.package_info().version.to_string(),
+            "createdAt": game_log::format_utc(process::now_millis()),
+            "system": system,
+        }),
+    ));
+    entries.push(json_entry("install.json", &install_report(app).await));
+    entries.push(json_entry(
+        "verify.json",
+        &or_error(download::verify(true).await),
+    ));
+    entries.push(json_entry(
+        "java.json",
+        &tokio::task::spawn_blocking(java_report)
+            .await
+            .unwrap_or(Value::Null),
+    ));
+    entries.push(json_entry(
+        "settings.json",
+        &Value::Object(settings::all(app)),
+    ));
+
+    for summary in instance::list() {
+        let path = Path::new(&summary.game_dir).join("instance.json");
+        let value: Value = std::fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or(Value::Null);
+        entries.push(json_entry(
+            &format!("instances/{}.json", summary.id),
+            &value,
+        ));
+    }
+
+    for path in launcher_log::files() {
+        entries.extend(text_entry(file_name(&path), &path));
+    }
+    for path in game_log::list_session_logs().into_iter().take(session_logs) {
+        entries.extend(text_entry(format!("sessions/{}", file_name(&path)), &path));
+    }
+    if let Some(path) = latest_crash_report() {
+        entries.extend(text_entry(format!("crash/{}", file_name(&path)), &path));
+    }
+
+    let output_path = output.to_path_buf();
+    let files = entries.iter().map(|(name, _)| name.clone()).collect();
+    tokio::task::spawn_blocking(move || write_zip(&output_path, &entries))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+
+    Ok(DiagnosticsReport {
+        path: output.to_string_lossy().to_string(),
+        files,
+    })
+}
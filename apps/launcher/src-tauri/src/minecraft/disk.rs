@@ -0,0 +1,144 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Headroom kept free on top of the estimate for temporary archives and logs.
+pub const SAFETY_MARGIN_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimatePart {
+    pub name: String,
+    pub total_bytes: u64,
+    pub needed_bytes: u64,
+    /// False when some sizes were unknown (no size in the metadata, or offline).
+    pub exact: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallEstimate {
+    pub target_dir: String,
+    pub total_bytes: u64,
+    pub needed_bytes: u64,
+    pub available_bytes: Option<u64>,
+    pub enough_space: bool,
+    pub parts: Vec<EstimatePart>,
+}
+
+impl InstallEstimate {
+    pub fn new(target_dir: &Path, parts: Vec<EstimatePart>) -> Self {
+        let total_bytes = parts.iter().map(|p| p.total_bytes).sum();
+        let needed_bytes = parts.iter().map(|p| p.needed_bytes).sum();
+        let available_bytes = available_space(target_dir);
+        Self {
+            target_dir: target_dir.to_string_lossy().to_string(),
+            total_bytes,
+            needed_bytes,
+            available_bytes,
+            enough_space: has_room(available_bytes, needed_bytes),
+            parts,
+        }
+    }
+}
+
+/// Error returned by install commands so the frontend can tell a full disk from other failures.
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum InstallError {
+    #[serde(rename_all = "camelCase")]
+    InsufficientSpace {
+        path: String,
+        required_bytes: u64,
+        available_bytes: u64,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl From<String> for InstallError {
+    fn from(message: String) -> Self {
+        InstallError::Failed { message }
+    }
+}
+
+impl From<&str> for InstallError {
+    fn from(message: &str) -> Self {
+        InstallError::Failed {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for InstallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallError::InsufficientSpace {
+                path,
+                required_bytes,
+                available_bytes,
+            } => write!(
+                f,
+                "디스크 공간이 부족합니다: {} 필요, {} 사용 가능 ({})",
+                format_bytes(*required_bytes),
+                format_bytes(*available_bytes),
+                path
+            ),
+            InstallError::Failed { message } => f.write_str(message),
+        }
+    }
+}
+
+/// Free space on the filesystem that holds `path`, walking up to the nearest existing ancestor.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let mut current: Option<PathBuf> = Some(path.to_path_buf());
+    while let Some(dir) = current {
+        if dir.exists() {
+            return fs4::available_space(&dir).ok();
+        }
+        current = dir.parent().map(Path::to_path_buf);
+    }
+    None
+}
+
+/// Refuse to start when `needed_bytes` (plus the safety margin) does not fit on `path`'s disk.
+pub fn ensure_free_space(path: &Path, needed_bytes: u64) -> Result<(), InstallError> {
+    let available = available_space(path);
+    match available {
+        Some(available_bytes) if !has_room(available, needed_bytes) => {
+            Err(InstallError::InsufficientSpace {
+                path: path.to_string_lossy().to_string(),
+                required_bytes: needed_bytes + SAFETY_MARGIN_BYTES,
+                available_bytes,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn has_room(available: Option<u64>, needed_bytes: u64) -> bool {
+    match available {
+        // Nothing to write means nothing can fail, even on a full disk
+        Some(_) if needed_bytes == 0 => true,
+        Some(available) => available >= needed_bytes + SAFETY_MARGIN_BYTES,
+        None => true,
+    }
+}
+
+/// Whether `path` already exists with the expected size; estimates use this instead of hashing.
+pub fn present_with_size(path: &Path, size: u64) -> bool {
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.len() == size)
+        .unwrap_or(false)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
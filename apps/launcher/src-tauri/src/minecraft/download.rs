@@ -2,14 +2,21 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter};
 
+use super::cache::MetaFetcher;
+use super::disk::{self, EstimatePart, InstallError};
+use super::forge;
+use super::http;
+use super::hud_mod::{self, HudModReport};
+use super::instance::{Instance, InstalledLoader};
+use super::launcher_log;
+use super::loader;
+use super::mod_sync::{self, ModSyncReport};
+use super::remote_config::{self, LoaderSpec, RemoteConfig};
+
 const VERSION_MANIFEST_URL: &str =
     "https://launchermeta.mojang.com/mc/game/version_manifest_v2.json";
-const TARGET_VERSION: &str = "1.21.11";
 const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
-const MODRINTH_API: &str = "https://api.modrinth.com/v2";
-const FABRIC_API_PROJECT: &str = "P7dR8mSH";
-
-
+const QUILT_META_URL: &str = "https://meta.quiltmc.org";
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -38,6 +45,28 @@ struct VersionMeta {
     libraries: Vec<Library>,
     #[serde(rename = "assetIndex")]
     asset_index: AssetIndexInfo,
+    #[serde(default)]
+    logging: Option<Logging>,
+}
+
+/// The log4j2 configuration the official launcher passes to the game.
+#[derive(Deserialize)]
+struct Logging {
+    client: Option<LoggingClient>,
+}
+
+#[derive(Deserialize)]
+struct LoggingClient {
+    /// e.g. `-Dlog4j.configurationFile=${path}`
+    argument: String,
+    file: LoggingFile,
+}
+
+#[derive(Deserialize)]
+struct LoggingFile {
+    id: String,
+    url: String,
+    sha1: String,
 }
 
 #[derive(Deserialize)]
@@ -88,6 +117,7 @@ struct AssetIndexInfo {
     id: String,
     url: String,
     sha1: String,
+    size: u64,
     #[serde(rename = "totalSize")]
     total_size: u64,
 }
@@ -103,10 +133,12 @@ struct AssetObject {
     size: u64,
 }
 
+/// Loader list entry of the Fabric and Quilt meta APIs; Quilt has no `stable` flag.
 #[derive(Deserialize)]
 struct FabricLoaderEntry {
     version: String,
-    stable: bool,
+    #[serde(default)]
+    stable: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -115,78 +147,209 @@ struct FabricProfileLib {
     url: String,
 }
 
-#[derive(Deserialize)]
-struct ModrinthVersionFile {
-    url: String,
-    primary: bool,
-    filename: String,
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallReport {
+    /// False when something the server requires (e.g. the HUD mod) could not be installed.
+    pub complete: bool,
+    pub problems: Vec<String>,
+    pub offline: bool,
+    pub minecraft_version: String,
+    pub config_source: remote_config::ConfigSource,
+    pub mods: Option<ModSyncReport>,
+    pub hud_mod: Option<HudModReport>,
+    pub skipped_remote_checks: Vec<String>,
 }
 
-#[derive(Deserialize)]
-struct ModrinthVersion {
-    files: Vec<ModrinthVersionFile>,
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub checked: u64,
+    pub missing: Vec<String>,
+    pub corrupted: Vec<String>,
+    pub skipped_remote_checks: Vec<String>,
 }
 
-pub fn get_game_dir() -> PathBuf {
+pub fn get_launcher_dir() -> PathBuf {
     let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
-    base.join("nyaru-launcher").join("minecraft")
+    base.join("nyaru-launcher")
 }
 
-pub async fn install(app: &AppHandle) -> Result<(), String> {
-    let game_dir = get_game_dir();
-    std::fs::create_dir_all(&game_dir).map_err(|e| format!("Create game dir failed: {}", e))?;
+pub fn get_game_dir() -> PathBuf {
+    get_launcher_dir().join("minecraft")
+}
 
-    let client = reqwest::Client::new();
-
-    // Step 1: Fetch version manifest
-    emit_progress(app, "버전 정보 확인 중...", "manifest", 0, 1, 0.0);
-    let manifest: VersionManifest = client
-        .get(VERSION_MANIFEST_URL)
-        .send()
-        .await
-        .map_err(|e| format!("Manifest fetch failed: {}", e))?
-        .json()
-        .await
-        .map_err(|e| format!("Manifest parse failed: {}", e))?;
+/// Resolve the version JSON through the metadata cache, returning it parsed and as raw bytes.
+async fn fetch_version_meta(
+    fetcher: &mut MetaFetcher,
+    client: &reqwest::Client,
+    version: &str,
+) -> Result<(VersionMeta, Vec<u8>), String> {
+    let manifest: VersionManifest = fetcher
+        .fetch_json("Mojang 버전 목록", client.get(VERSION_MANIFEST_URL))
+        .await?;
 
     let version_entry = manifest
         .versions
         .iter()
-        .find(|v| v.id == TARGET_VERSION)
-        .ok_or(format!("Version {} not found", TARGET_VERSION))?;
+        .find(|v| v.id == version)
+        .ok_or(format!("Version {} not found", version))?;
 
-    // Step 2: Fetch version metadata
-    emit_progress(app, "버전 메타데이터 다운로드 중...", "metadata", 0, 1, 5.0);
-    let version_meta: VersionMeta = client
-        .get(&version_entry.url)
-        .send()
-        .await
-        .map_err(|e| format!("Version meta fetch failed: {}", e))?
-        .json()
-        .await
+    let meta_bytes = fetcher
+        .fetch("Mojang 버전 메타데이터", client.get(&version_entry.url))
+        .await?;
+    let version_meta: VersionMeta = serde_json::from_slice(&meta_bytes)
         .map_err(|e| format!("Version meta parse failed: {}", e))?;
+    Ok((version_meta, meta_bytes))
+}
+
+pub async fn install(app: &AppHandle, offline: bool) -> Result<InstallReport, InstallError> {
+    let game_dir = get_game_dir();
+    std::fs::create_dir_all(&game_dir).map_err(|e| format!("Create game dir failed: {}", e))?;
+
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(offline);
+
+    // Step 0: Load the server team's launcher configuration
+    emit_progress(app, "런처 설정 확인 중...", "config", 0, 1, 0.0);
+    let loaded = remote_config::load(app, &mut fetcher).await?;
+    if let Some(ref warning) = loaded.warning {
+        emit_progress(app, &format!("기본 설정 사용: {}", warning), "config", 0, 1, 0.0);
+    }
+    let config = &loaded.config;
+    let version = config.minecraft_version.as_str();
+
+    // A loader version the player pinned wins over "latest stable", not over a server pin
+    let previous = Instance::load(&game_dir);
+    let previous_profile = previous
+        .as_ref()
+        .and_then(|i| i.loader.as_ref())
+        .map(|l| l.profile_id.clone());
+    let pinned_loader = previous
+        .filter(|i| {
+            i.loader
+                .as_ref()
+                .is_some_and(|l| l.kind == config.loader.kind)
+        })
+        .and_then(|i| i.pinned_loader);
+    let loader = LoaderSpec {
+        kind: config.loader.kind.clone(),
+        version: config.loader.version.clone().or(pinned_loader.clone()),
+    };
+
+    let mods_part = estimate_mods(&client, &mut fetcher, &game_dir, config)
+        .await
+        .unwrap_or_else(|_| unknown_part("모드"));
+    let mut instance = install_game(
+        app,
+        &client,
+        &mut fetcher,
+        version,
+        &loader,
+        mods_part.needed_bytes,
+    )
+    .await?;
+    instance.pinned_loader = pinned_loader;
+
+    let mut mods = None;
+    let mut hud = None;
+    let mut problems = Vec::new();
+    if instance.loader.is_some() {
+        // Step 8: Sync the server's required mods
+        let report =
+            mod_sync::sync(app, &client, &mut fetcher, &game_dir, version, &config.mods).await?;
+        if !report.failed.is_empty() {
+            let failed: Vec<String> = report
+                .failed
+                .iter()
+                .map(|f| format!("{} ({})", f.id, f.error))
+                .collect();
+            return Err(format!("필수 모드 설치 실패: {}", failed.join(", ")).into());
+        }
+        mods = Some(report);
+
+        // Step 9: Install or update the server HUD mod
+        if let Some(ref source) = config.hud_mod {
+            match hud_mod::install(app, &client, &mut fetcher, &game_dir, source).await {
+                Ok(report) => hud = Some(report),
+                Err(e) => problems.push(format!("HUD 모드 설치 실패: {}", e)),
+            }
+        }
+    }
+    instance.name = Some(config.server_name.clone());
+    instance.save(&game_dir)?;
+
+    // A newer stable loader replaced the previous one; its profile is not launched anymore
+    let profile = instance.loader.as_ref().map(|l| l.profile_id.as_str());
+    if let Some(previous) = previous_profile.filter(|p| Some(p.as_str()) != profile) {
+        if let Err(e) = loader::cleanup_profile(&previous) {
+            launcher_log::write("WARN", &format!("이전 로더 정리 실패: {}", e));
+        }
+    }
+
+    let skipped_remote_checks = fetcher.into_skipped();
+    if !skipped_remote_checks.is_empty() {
+        emit_progress(
+            app,
+            &format!("원격 확인 건너뜀: {}", skipped_remote_checks.join(", ")),
+            "offline",
+            0,
+            1,
+            100.0,
+        );
+    }
+
+    if problems.is_empty() {
+        emit_progress(app, "설치 완료!", "complete", 1, 1, 100.0);
+    } else {
+        let message = format!("설치 미완료: {}", problems.join(", "));
+        emit_progress(app, &message, "incomplete", 1, 1, 100.0);
+    }
+    Ok(InstallReport {
+        complete: problems.is_empty(),
+        problems,
+        offline,
+        minecraft_version: version.to_string(),
+        config_source: loaded.source,
+        mods,
+        hud_mod: hud,
+        skipped_remote_checks,
+    })
+}
+
+/// Install the vanilla files of `version` and its loader into the shared game directory.
+///
+/// `extra_needed_bytes` is what the caller will write afterwards (mods, pack files), so the
+/// disk check covers the whole operation.
+pub async fn install_game(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    version: &str,
+    loader: &LoaderSpec,
+    extra_needed_bytes: u64,
+) -> Result<Instance, InstallError> {
+    let game_dir = get_game_dir();
+    std::fs::create_dir_all(&game_dir).map_err(|e| format!("Create game dir failed: {}", e))?;
+
+    // Step 1-2: Fetch version manifest and version metadata
+    emit_progress(app, "버전 정보 확인 중...", "manifest", 0, 1, 0.0);
+    let (version_meta, meta_bytes) = fetch_version_meta(fetcher, client, version).await?;
+
+    // Refuse early instead of failing halfway with a full disk
+    let parts = estimate_parts(client, fetcher, &game_dir, version, loader, &version_meta).await;
+    let needed_bytes: u64 = parts.iter().map(|p| p.needed_bytes).sum();
+    disk::ensure_free_space(&game_dir, needed_bytes + extra_needed_bytes)?;
 
     // Save version meta JSON
-    let meta_path = game_dir.join("versions").join(TARGET_VERSION);
+    let meta_path = game_dir.join("versions").join(version);
     std::fs::create_dir_all(&meta_path)
         .map_err(|e| format!("Create version dir failed: {}", e))?;
-
-    let meta_json = client
-        .get(&version_entry.url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .text()
-        .await
+    std::fs::write(meta_path.join(format!("{}.json", version)), &meta_bytes)
         .map_err(|e| e.to_string())?;
-    std::fs::write(
-        meta_path.join(format!("{}.json", TARGET_VERSION)),
-        &meta_json,
-    )
-    .map_err(|e| e.to_string())?;
 
     // Step 3: Download client JAR
-    let client_jar_path = meta_path.join(format!("{}.jar", TARGET_VERSION));
+    let client_jar_path = meta_path.join(format!("{}.jar", version));
     if !file_valid(&client_jar_path, &version_meta.downloads.client.sha1) {
         emit_progress(
             app,
@@ -196,7 +359,7 @@ pub async fn install(app: &AppHandle) -> Result<(), String> {
             version_meta.downloads.client.size,
             10.0,
         );
-        download_file(&client, &version_meta.downloads.client.url, &client_jar_path).await?;
+        download_file(client, &version_meta.downloads.client.url, &client_jar_path).await?;
     }
 
     // Step 4: Download libraries
@@ -218,7 +381,7 @@ pub async fn install(app: &AppHandle) -> Result<(), String> {
                         total_libs,
                         progress,
                     );
-                    download_file(&client, &artifact.url, &lib_path).await?;
+                    download_file(client, &artifact.url, &lib_path).await?;
                 }
             }
         }
@@ -232,7 +395,7 @@ pub async fn install(app: &AppHandle) -> Result<(), String> {
     let index_path = indexes_dir.join(format!("{}.json", version_meta.asset_index.id));
     if !file_valid(&index_path, &version_meta.asset_index.sha1) {
         emit_progress(app, "에셋 인덱스 다운로드 중...", "asset_index", 0, 1, 70.0);
-        download_file(&client, &version_meta.asset_index.url, &index_path).await?;
+        download_file(client, &version_meta.asset_index.url, &index_path).await?;
     }
 
     // Step 6: Download assets
@@ -263,43 +426,319 @@ pub async fn install(app: &AppHandle) -> Result<(), String> {
                 "https://resources.download.minecraft.net/{}/{}",
                 prefix, obj.hash
             );
-            download_file(&client, &url, &obj_path).await?;
+            download_file(client, &url, &obj_path).await?;
         }
     }
 
-    // Step 7: Install Fabric Loader
-    if let Err(e) = install_fabric(app, &client, &game_dir).await {
-        emit_progress(app, &format!("Fabric 경고: {}", e), "fabric", 0, 1, 0.0);
-    } else {
-        // Step 8: Install mods
-        if let Err(e) = install_mods(app, &client, &game_dir).await {
-            emit_progress(app, &format!("모드 경고: {}", e), "mods", 0, 3, 0.0);
+    // Step 6b: Download the log4j2 config that makes the game log structured events
+    if let Some(logging) = version_meta.logging.as_ref().and_then(|l| l.client.as_ref()) {
+        let config_path = assets_dir.join("log_configs").join(&logging.file.id);
+        if !file_valid(&config_path, &logging.file.sha1) {
+            download_file(client, &logging.file.url, &config_path).await?;
         }
     }
 
-    emit_progress(app, "설치 완료!", "complete", 1, 1, 100.0);
-    Ok(())
+    // Step 7: Install the mod loader
+    let installed = install_loader(app, client, fetcher, &game_dir, version, loader)
+        .await
+        .map_err(|e| format!("{} 설치 실패: {}", loader_name(&loader.kind), e))?;
+    Ok(Instance {
+        name: None,
+        minecraft_version: version.to_string(),
+        loader: Some(installed),
+        pinned_loader: None,
+        packwiz_url: None,
+    })
+}
+
+/// Sizes of the vanilla, loader and mod files `install` would write, without downloading them.
+pub async fn estimate(app: &AppHandle, offline: bool) -> Result<Vec<EstimatePart>, String> {
+    let game_dir = get_game_dir();
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(offline);
+    let config = remote_config::load(app, &mut fetcher).await?.config;
+    let version = config.minecraft_version.as_str();
+    let (version_meta, _) = fetch_version_meta(&mut fetcher, &client, version).await?;
+    let mut parts = estimate_parts(
+        &client,
+        &mut fetcher,
+        &game_dir,
+        version,
+        &config.loader,
+        &version_meta,
+    )
+    .await;
+    parts.push(
+        estimate_mods(&client, &mut fetcher, &game_dir, &config)
+            .await
+            .unwrap_or_else(|_| unknown_part("모드")),
+    );
+    Ok(parts)
+}
+
+async fn estimate_parts(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    version: &str,
+    loader: &LoaderSpec,
+    version_meta: &VersionMeta,
+) -> Vec<EstimatePart> {
+    vec![
+        estimate_vanilla(game_dir, version, version_meta),
+        estimate_loader(client, fetcher, game_dir, version, loader)
+            .await
+            .unwrap_or_else(|_| unknown_part(loader_name(&loader.kind))),
+    ]
+}
+
+pub fn unknown_part(name: &str) -> EstimatePart {
+    EstimatePart {
+        name: name.to_string(),
+        total_bytes: 0,
+        needed_bytes: 0,
+        exact: false,
+    }
+}
+
+fn estimate_vanilla(game_dir: &Path, version: &str, version_meta: &VersionMeta) -> EstimatePart {
+    let mut total = 0;
+    let mut needed = 0;
+    let mut add = |path: &Path, size: u64| {
+        total += size;
+        if !disk::present_with_size(path, size) {
+            needed += size;
+        }
+    };
+
+    let client = &version_meta.downloads.client;
+    add(
+        &game_dir
+            .join("versions")
+            .join(version)
+            .join(format!("{}.jar", version)),
+        client.size,
+    );
+
+    let lib_dir = game_dir.join("libraries");
+    for lib in filter_libraries(&version_meta.libraries) {
+        if let Some(artifact) = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref()) {
+            add(&lib_dir.join(&artifact.path), artifact.size);
+        }
+    }
+
+    let assets_dir = game_dir.join("assets");
+    let index_info = &version_meta.asset_index;
+    let index_path = assets_dir
+        .join("indexes")
+        .join(format!("{}.json", index_info.id));
+    add(&index_path, index_info.size);
+
+    // Without a local index only the total is known
+    let asset_index = std::fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<AssetIndex>(&json).ok());
+    match asset_index {
+        Some(asset_index) => {
+            let objects_dir = assets_dir.join("objects");
+            for obj in asset_index.objects.values() {
+                add(&objects_dir.join(&obj.hash[..2]).join(&obj.hash), obj.size);
+            }
+        }
+        None => {
+            total += index_info.total_size;
+            needed += index_info.total_size;
+        }
+    }
+
+    EstimatePart {
+        name: format!("Minecraft {}", version),
+        total_bytes: total,
+        needed_bytes: needed,
+        exact: true,
+    }
+}
+
+/// Loader sizes from the Fabric or Quilt profile; Forge and NeoForge only know theirs after
+/// running the installer, so they are reported as unknown.
+async fn estimate_loader(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    mc_version: &str,
+    loader: &LoaderSpec,
+) -> Result<EstimatePart, String> {
+    let (meta_url, api) = fabric_like_meta(&loader.kind)
+        .ok_or_else(|| format!("{}: 크기를 미리 알 수 없습니다", loader.kind))?;
+    let loader_version = get_loader_version(client, fetcher, mc_version, loader).await?;
+    let profile_url = format!(
+        "{}/{}/versions/loader/{}/{}/profile/json",
+        meta_url, api, mc_version, loader_version
+    );
+    let profile: serde_json::Value = fetcher
+        .fetch_json(
+            &format!("{} 프로필", loader_name(&loader.kind)),
+            client.get(&profile_url),
+        )
+        .await?;
+
+    let mut part = EstimatePart {
+        name: format!("{} {}", loader_name(&loader.kind), loader_version),
+        total_bytes: 0,
+        needed_bytes: 0,
+        exact: true,
+    };
+    let lib_dir = game_dir.join("libraries");
+    for lib in profile["libraries"].as_array().into_iter().flatten() {
+        let (Some(name), Some(url)) = (lib["name"].as_str(), lib["url"].as_str()) else {
+            continue;
+        };
+        let rel_path = maven_name_to_path(name);
+        if let Ok(meta) = std::fs::metadata(lib_dir.join(&rel_path)) {
+            part.total_bytes += meta.len();
+            continue;
+        }
+        // Fabric and Quilt profiles carry no sizes, so ask the Maven server
+        let size = if fetcher.is_offline() {
+            None
+        } else {
+            client
+                .head(format!("{}{}", url, rel_path))
+                .send()
+                .await
+                .ok()
+                .and_then(|r| r.content_length())
+        };
+        match size {
+            Some(size) => {
+                part.total_bytes += size;
+                part.needed_bytes += size;
+            }
+            None => part.exact = false,
+        }
+    }
+    Ok(part)
+}
+
+async fn estimate_mods(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    config: &RemoteConfig,
+) -> Result<EstimatePart, String> {
+    let mut part = EstimatePart {
+        name: "모드".to_string(),
+        total_bytes: 0,
+        needed_bytes: 0,
+        exact: true,
+    };
+    let mods_dir = game_dir.join("mods");
+    for m in config.mods.iter().filter(|m| m.required) {
+        let file = mod_sync::resolve(client, fetcher, m, &config.minecraft_version).await?;
+        if file.size == 0 {
+            part.exact = false;
+        }
+        part.total_bytes += file.size;
+        if !disk::present_with_size(&mods_dir.join(&file.filename), file.size) {
+            part.needed_bytes += file.size;
+        }
+    }
+    Ok(part)
+}
+
+/// Check every vanilla file of the installed version against its metadata without downloading anything.
+pub async fn verify(offline: bool) -> Result<VerifyReport, String> {
+    let game_dir = get_game_dir();
+    let instance = Instance::load(&game_dir).ok_or("설치된 버전이 없습니다")?;
+    let version = instance.minecraft_version.as_str();
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(offline);
+    let (version_meta, _) = fetch_version_meta(&mut fetcher, &client, version).await?;
+
+    let mut report = VerifyReport {
+        checked: 0,
+        missing: Vec::new(),
+        corrupted: Vec::new(),
+        skipped_remote_checks: Vec::new(),
+    };
+    let mut check = |path: &Path, sha1: &str, label: String| {
+        report.checked += 1;
+        if !path.exists() {
+            report.missing.push(label);
+        } else if !file_valid(path, sha1) {
+            report.corrupted.push(label);
+        }
+    };
+
+    let client_jar_path = game_dir
+        .join("versions")
+        .join(version)
+        .join(format!("{}.jar", version));
+    check(
+        &client_jar_path,
+        &version_meta.downloads.client.sha1,
+        format!("{}.jar", version),
+    );
+
+    let lib_dir = game_dir.join("libraries");
+    for lib in filter_libraries(&version_meta.libraries) {
+        if let Some(artifact) = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref()) {
+            check(&lib_dir.join(&artifact.path), &artifact.sha1, artifact.path.clone());
+        }
+    }
+
+    let assets_dir = game_dir.join("assets");
+    let index_path = assets_dir
+        .join("indexes")
+        .join(format!("{}.json", version_meta.asset_index.id));
+    check(
+        &index_path,
+        &version_meta.asset_index.sha1,
+        format!("assets/indexes/{}.json", version_meta.asset_index.id),
+    );
+
+    if let Ok(index_json) = std::fs::read_to_string(&index_path) {
+        let asset_index: AssetIndex = serde_json::from_str(&index_json)
+            .map_err(|e| format!("Parse asset index: {}", e))?;
+        let objects_dir = assets_dir.join("objects");
+        for (name, obj) in &asset_index.objects {
+            let obj_path = objects_dir.join(&obj.hash[..2]).join(&obj.hash);
+            check(&obj_path, &obj.hash, name.clone());
+        }
+    }
+
+    report.skipped_remote_checks = fetcher.into_skipped();
+    Ok(report)
 }
 
-pub fn is_installed() -> bool {
+/// Whether the installed version and loader are the ones `config` asks for.
+pub fn is_installed(config: &RemoteConfig) -> bool {
     let game_dir = get_game_dir();
+    let Some(instance) = Instance::load(&game_dir) else {
+        return false;
+    };
+    let loader_matches = match (&config.loader.version, &instance.loader) {
+        (Some(pinned), Some(loader)) => &loader.version == pinned,
+        (_, loader) => loader.is_some(),
+    };
     let jar = game_dir
         .join("versions")
-        .join(TARGET_VERSION)
-        .join(format!("{}.jar", TARGET_VERSION));
-    jar.exists()
+        .join(&instance.minecraft_version)
+        .join(format!("{}.jar", instance.minecraft_version));
+    // The HUD mod is optional: a failed HUD install never fails the install either
+    instance.minecraft_version == config.minecraft_version && loader_matches && jar.exists()
 }
 
-pub fn get_version_meta_path() -> PathBuf {
+pub fn get_version_meta_path(version: &str) -> PathBuf {
     get_game_dir()
         .join("versions")
-        .join(TARGET_VERSION)
-        .join(format!("{}.json", TARGET_VERSION))
+        .join(version)
+        .join(format!("{}.json", version))
 }
 
-pub fn get_classpath() -> Result<String, String> {
+pub fn get_classpath(version: &str) -> Result<String, String> {
     let game_dir = get_game_dir();
-    let meta_path = get_version_meta_path();
+    let meta_path = get_version_meta_path(version);
     let meta_json =
         std::fs::read_to_string(&meta_path).map_err(|e| format!("Read version meta: {}", e))?;
     let meta: VersionMeta =
@@ -322,15 +761,15 @@ pub fn get_classpath() -> Result<String, String> {
     // Add client JAR
     let client_jar = game_dir
         .join("versions")
-        .join(TARGET_VERSION)
-        .join(format!("{}.jar", TARGET_VERSION));
+        .join(version)
+        .join(format!("{}.jar", version));
     paths.push(client_jar.to_string_lossy().to_string());
 
     Ok(paths.join(sep))
 }
 
-pub fn get_asset_index_id() -> Result<String, String> {
-    let meta_path = get_version_meta_path();
+pub fn get_asset_index_id(version: &str) -> Result<String, String> {
+    let meta_path = get_version_meta_path(version);
     let meta_json =
         std::fs::read_to_string(&meta_path).map_err(|e| format!("Read version meta: {}", e))?;
     let meta: VersionMeta =
@@ -338,58 +777,176 @@ pub fn get_asset_index_id() -> Result<String, String> {
     Ok(meta.asset_index.id)
 }
 
+fn log_config_path(id: &str) -> PathBuf {
+    get_game_dir().join("assets").join("log_configs").join(id)
+}
+
+/// The JVM argument pointing the game at its log4j2 config, once `install` downloaded it.
+pub fn get_logging_argument(version: &str) -> Option<String> {
+    let meta_json = std::fs::read_to_string(get_version_meta_path(version)).ok()?;
+    let meta: VersionMeta = serde_json::from_str(&meta_json).ok()?;
+    let client = meta.logging?.client?;
+    let path = log_config_path(&client.file.id);
+    if !path.exists() {
+        return None;
+    }
+    Some(client.argument.replace("${path}", &path.to_string_lossy()))
+}
+
+/// `group:artifact:version[:classifier][@extension]` → path under `libraries/`.
 pub fn maven_name_to_path(name: &str) -> String {
-    let parts: Vec<&str> = name.split(':').collect();
+    let (coords, extension) = name.split_once('@').unwrap_or((name, "jar"));
+    let parts: Vec<&str> = coords.split(':').collect();
     if parts.len() >= 3 {
         let group = parts[0].replace('.', "/");
         let artifact = parts[1];
         let version = parts[2];
-        format!("{}/{}/{}/{}-{}.jar", group, artifact, version, artifact, version)
+        let classifier = parts.get(3).map(|c| format!("-{}", c)).unwrap_or_default();
+        format!(
+            "{}/{}/{}/{}-{}{}.{}",
+            group, artifact, version, artifact, version, classifier, extension
+        )
     } else {
         format!("{}.jar", name)
     }
 }
 
-async fn get_fabric_loader_version(client: &reqwest::Client) -> Result<String, String> {
-    let versions: Vec<FabricLoaderEntry> = client
-        .get(format!("{}/v2/versions/loader", FABRIC_META_URL))
-        .send().await.map_err(|e| e.to_string())?
-        .json().await.map_err(|e| e.to_string())?;
-    versions.into_iter()
-        .find(|v| v.stable)
-        .map(|v| v.version)
-        .ok_or("No stable Fabric Loader found".to_string())
+/// Library paths under `libraries/` of a version or loader profile JSON.
+pub fn profile_library_paths(profile: &serde_json::Value) -> Vec<String> {
+    profile["libraries"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|lib| {
+            // Forge and vanilla profiles give the path; Fabric and Quilt only the coordinates
+            match (
+                lib["downloads"]["artifact"]["path"].as_str(),
+                lib["name"].as_str(),
+            ) {
+                (Some(path), _) => Some(path.to_string()),
+                (None, Some(name)) => Some(maven_name_to_path(name)),
+                (None, None) => None,
+            }
+        })
+        .collect()
 }
 
-async fn install_fabric(app: &AppHandle, client: &reqwest::Client, game_dir: &std::path::Path) -> Result<(), String> {
-    emit_progress(app, "Fabric Loader 버전 확인 중...", "fabric", 0, 1, 0.0);
-    let loader_version = get_fabric_loader_version(client).await?;
+/// Meta server and API version of the loaders that publish ready-made launcher profiles.
+pub fn fabric_like_meta(kind: &str) -> Option<(&'static str, &'static str)> {
+    match kind {
+        "fabric" => Some((FABRIC_META_URL, "v2")),
+        "quilt" => Some((QUILT_META_URL, "v3")),
+        _ => None,
+    }
+}
 
-    let fabric_id = format!("fabric-loader-{}-{}", loader_version, TARGET_VERSION);
-    let fabric_dir = game_dir.join("versions").join(&fabric_id);
-    let profile_path = fabric_dir.join(format!("{}.json", fabric_id));
+pub fn loader_name(kind: &str) -> &'static str {
+    match kind {
+        "fabric" => "Fabric Loader",
+        "quilt" => "Quilt Loader",
+        "forge" => "Forge",
+        "neoforge" => "NeoForge",
+        _ => "Mod Loader",
+    }
+}
 
-    if profile_path.exists() {
-        let _ = std::fs::write(game_dir.join("fabric-version.txt"), &fabric_id);
-        return Ok(());
+async fn get_loader_version(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    mc_version: &str,
+    loader: &LoaderSpec,
+) -> Result<String, String> {
+    if let Some(ref pinned) = loader.version {
+        return Ok(pinned.clone());
     }
+    if matches!(loader.kind.as_str(), "forge" | "neoforge") {
+        return forge::latest_version(client, fetcher, &loader.kind, mc_version).await;
+    }
+    let (meta_url, api) = fabric_like_meta(&loader.kind)
+        .ok_or_else(|| format!("지원하지 않는 로더입니다: {}", loader.kind))?;
+    let name = loader_name(&loader.kind);
+    let versions: Vec<FabricLoaderEntry> = fetcher
+        .fetch_json(
+            &format!("{} 버전 목록", name),
+            client.get(format!("{}/{}/versions/loader", meta_url, api)),
+        )
+        .await?;
+    versions
+        .into_iter()
+        .find(|v| v.stable.unwrap_or(!v.version.contains('-')))
+        .map(|v| v.version)
+        .ok_or(format!("No stable {} found", name))
+}
 
-    let profile_url = format!(
-        "{}/v2/versions/loader/{}/{}/profile/json",
-        FABRIC_META_URL, TARGET_VERSION, loader_version
-    );
+pub async fn install_loader(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    mc_version: &str,
+    loader: &LoaderSpec,
+) -> Result<InstalledLoader, String> {
+    let name = loader_name(&loader.kind);
+    emit_progress(app, &format!("{} 버전 확인 중...", name), "loader", 0, 1, 0.0);
+    let loader_version = get_loader_version(client, fetcher, mc_version, loader).await?;
+    if fabric_like_meta(&loader.kind).is_some() {
+        install_fabric_like(
+            app,
+            client,
+            fetcher,
+            game_dir,
+            mc_version,
+            &loader.kind,
+            &loader_version,
+        )
+        .await
+    } else {
+        forge::install(app, client, fetcher, game_dir, mc_version, &loader.kind, &loader_version)
+            .await
+    }
+}
 
-    emit_progress(app, "Fabric Loader 다운로드 중...", "fabric", 0, 1, 30.0);
-    let profile_bytes = client
-        .get(&profile_url)
-        .send().await.map_err(|e| format!("Fabric profile fetch: {}", e))?
-        .bytes().await.map_err(|e| e.to_string())?;
+/// Fabric and Quilt: the meta server hands out the finished profile, only its libraries
+/// need downloading.
+async fn install_fabric_like(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    mc_version: &str,
+    kind: &str,
+    loader_version: &str,
+) -> Result<InstalledLoader, String> {
+    let (meta_url, api) = fabric_like_meta(kind).ok_or("Not a Fabric-like loader")?;
+    let label = loader_name(kind);
+    let fabric_id = format!("{}-loader-{}-{}", kind, loader_version, mc_version);
+    let fabric_dir = game_dir.join("versions").join(&fabric_id);
+    let profile_path = fabric_dir.join(format!("{}.json", fabric_id));
+    let installed = InstalledLoader {
+        kind: kind.to_string(),
+        version: loader_version.to_string(),
+        profile_id: fabric_id.clone(),
+    };
+
+    // A saved profile is reused, but its libraries are still checked below
+    let saved = std::fs::read(&profile_path).ok();
+    let profile_bytes = match saved {
+        Some(ref bytes) => bytes.clone(),
+        None => {
+            let profile_url = format!(
+                "{}/{}/versions/loader/{}/{}/profile/json",
+                meta_url, api, mc_version, loader_version
+            );
+            emit_progress(app, &format!("{} 다운로드 중...", label), "loader", 0, 1, 30.0);
+            fetcher
+                .fetch(&format!("{} 프로필", label), client.get(&profile_url))
+                .await
+                .map_err(|e| format!("{} profile fetch: {}", label, e))?
+        }
+    };
 
     let profile_val: serde_json::Value = serde_json::from_slice(&profile_bytes)
-        .map_err(|e| format!("Fabric profile parse: {}", e))?;
-
-    std::fs::create_dir_all(&fabric_dir).map_err(|e| e.to_string())?;
-    std::fs::write(&profile_path, &profile_bytes).map_err(|e| e.to_string())?;
+        .map_err(|e| format!("{} profile parse: {}", label, e))?;
 
     let lib_dir = game_dir.join("libraries");
     if let Some(libs) = profile_val["libraries"].as_array() {
@@ -397,68 +954,24 @@ async fn install_fabric(app: &AppHandle, client: &reqwest::Client, game_dir: &st
         for (i, lib) in libs.iter().enumerate() {
             if let (Some(name), Some(url)) = (lib["name"].as_str(), lib["url"].as_str()) {
                 let rel_path = maven_name_to_path(name);
-                let lib_path = lib_dir.join(&rel_path);
-                if !lib_path.exists() {
-                    let download_url = format!("{}{}", url, rel_path);
-                    emit_progress(app, &format!("Fabric: {}", short_name(name)), "fabric_libs",
-                        i as u64, total, 40.0 + (50.0 * i as f64 / total as f64));
-                    let _ = download_file(client, &download_url, &lib_path).await;
-                }
+                emit_progress(app, &format!("{}: {}", label, short_name(name)), "loader_libs",
+                    i as u64, total, 40.0 + (50.0 * i as f64 / total as f64));
+                let download_url = format!("{}{}", url, rel_path);
+                let (sha1, size) = (lib["sha1"].as_str(), lib["size"].as_u64());
+                let path = lib_dir.join(&rel_path);
+                install_maven_library(client, fetcher, &download_url, &path, sha1, size)
+                    .await
+                    .map_err(|e| format!("{}: {}", short_name(name), e))?;
             }
         }
     }
 
-    std::fs::write(game_dir.join("fabric-version.txt"), &fabric_id)
-        .map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-async fn get_modrinth_download_url(client: &reqwest::Client, project_id: &str, mc_version: &str) -> Result<String, String> {
-    let versions: Vec<ModrinthVersion> = client
-        .get(format!("{}/project/{}/version", MODRINTH_API, project_id))
-        .query(&[("loaders", "[\"fabric\"]"), ("game_versions", &format!("[\"{}\"]", mc_version))])
-        .header("User-Agent", "nyaru-launcher/0.1.1 (github.com/INIRU/Tinklepaw)")
-        .send().await.map_err(|e| e.to_string())?
-        .json().await.map_err(|e| e.to_string())?;
-
-    let version = versions.into_iter().next()
-        .ok_or_else(|| format!("No Fabric mod found for MC {}", mc_version))?;
-    let file = version.files.iter().find(|f| f.primary).or_else(|| version.files.first())
-        .ok_or("No files in mod version")?;
-    Ok(file.url.clone())
-}
-
-fn remove_old_mod(mods_dir: &std::path::Path, prefix: &str) {
-    if let Ok(entries) = std::fs::read_dir(mods_dir) {
-        for entry in entries.flatten() {
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with(prefix) && name.ends_with(".jar") {
-                let _ = std::fs::remove_file(entry.path());
-            }
-        }
+    // Written last, so a profile never points at libraries that failed to install
+    if saved.is_none() {
+        std::fs::create_dir_all(&fabric_dir).map_err(|e| e.to_string())?;
+        std::fs::write(&profile_path, &profile_bytes).map_err(|e| e.to_string())?;
     }
-}
-
-async fn install_mods(app: &AppHandle, client: &reqwest::Client, game_dir: &std::path::Path) -> Result<(), String> {
-    let mods_dir = game_dir.join("mods");
-    std::fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
-
-    // Fabric API
-    emit_progress(app, "Fabric API 설치 중...", "mods", 1, 3, 10.0);
-    match get_modrinth_download_url(client, FABRIC_API_PROJECT, TARGET_VERSION).await {
-        Ok(url) => {
-            let filename = url.split('/').last().unwrap_or("fabric-api.jar").to_string();
-            let mod_path = mods_dir.join(&filename);
-            if !mod_path.exists() {
-                remove_old_mod(&mods_dir, "fabric-api");
-                let _ = download_file(client, &url, &mod_path).await;
-            }
-        }
-        Err(e) => emit_progress(app, &format!("Fabric API 건너뜀: {}", e), "mods", 1, 3, 10.0),
-    }
-
-    Ok(())
+    Ok(installed)
 }
 
 fn filter_libraries(libraries: &[Library]) -> Vec<&Library> {
@@ -529,23 +1042,121 @@ async fn download_file(client: &reqwest::Client, url: &str, path: &Path) -> Resu
         std::fs::create_dir_all(parent).map_err(|e| format!("Create dir failed: {}", e))?;
     }
 
-    let response = client
+    let bytes = download_bytes(client, url).await?;
+    std::fs::write(path, &bytes).map_err(|e| format!("Write file failed: {}", e))?;
+
+    Ok(())
+}
+
+/// Fetch `url` into memory, honouring the bandwidth limit.
+pub async fn download_bytes(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let mut response = client
         .get(url)
         .send()
         .await
+        .and_then(|r| r.error_for_status())
         .map_err(|e| format!("Download failed: {} - {}", url, e))?;
 
-    let bytes = response
-        .bytes()
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
         .await
-        .map_err(|e| format!("Read bytes failed: {}", e))?;
+        .map_err(|e| format!("Read bytes failed: {}", e))?
+    {
+        http::throttle(chunk.len()).await;
+        bytes.extend_from_slice(&chunk);
+    }
 
-    std::fs::write(path, &bytes).map_err(|e| format!("Write file failed: {}", e))?;
+    Ok(bytes)
+}
 
-    Ok(())
+/// The `.sha1` file Maven repositories publish next to each artifact, if there is one.
+///
+/// Kept next to the jar as `<jar>.sha1` so later checks need no request; offline, only that
+/// copy is used.
+async fn maven_sha1_sidecar(
+    client: &reqwest::Client,
+    fetcher: &MetaFetcher,
+    url: &str,
+    path: &Path,
+) -> Option<String> {
+    fn parse(data: &[u8]) -> Option<String> {
+        let text = std::str::from_utf8(data).ok()?;
+        let hash = text.split_whitespace().next()?.to_ascii_lowercase();
+        (hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+    }
+
+    let cached = sidecar_path(path);
+    if let Some(hash) = std::fs::read(&cached).ok().and_then(|data| parse(&data)) {
+        return Some(hash);
+    }
+    if fetcher.is_offline() {
+        return None;
+    }
+    let hash = parse(&download_bytes(client, &format!("{}.sha1", url)).await.ok()?)?;
+    if let Some(parent) = cached.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&cached, &hash);
+    Some(hash)
+}
+
+fn sidecar_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.sha1", path.to_string_lossy()))
+}
+
+/// Make sure the Maven artifact at `url` is at `path` and intact, downloading it otherwise.
+///
+/// Checked against `sha1`/`size` when the profile gives them and the repository's `.sha1`
+/// sidecar otherwise. Without any hash an existing file is accepted on its size, or on
+/// existence alone when the size is unknown too.
+pub async fn install_maven_library(
+    client: &reqwest::Client,
+    fetcher: &MetaFetcher,
+    url: &str,
+    path: &Path,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<(), String> {
+    let sidecar = sha1.is_none();
+    let sha1 = match sha1 {
+        Some(sha1) => Some(sha1.to_ascii_lowercase()),
+        None => maven_sha1_sidecar(client, fetcher, url, path).await,
+    };
+    if path.exists() {
+        let valid = match (&sha1, size) {
+            (Some(sha1), _) => file_valid(path, sha1),
+            (None, Some(size)) => disk::present_with_size(path, size),
+            (None, None) => true,
+        };
+        if valid {
+            return Ok(());
+        }
+        let _ = std::fs::remove_file(path);
+    }
+
+    let data = download_bytes(client, url).await?;
+    if let Some(size) = size {
+        if data.len() as u64 != size {
+            return Err(format!("크기가 다릅니다 (예상 {}, 실제 {})", size, data.len()));
+        }
+    }
+    if let Some(ref sha1) = sha1 {
+        if let Err(e) = mod_sync::verify_hashes(&data, Some(sha1), None) {
+            // A stale or broken cached sidecar is fetched again next time
+            if sidecar {
+                let _ = std::fs::remove_file(sidecar_path(path));
+            }
+            return Err(e);
+        }
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Create dir failed: {}", e))?;
+    }
+    std::fs::write(path, &data).map_err(|e| format!("Write file failed: {}", e))
 }
 
-fn emit_progress(
+pub fn emit_progress(
     app: &AppHandle,
     file_name: &str,
     stage: &str,
@@ -553,6 +1164,7 @@ fn emit_progress(
     total: u64,
     percent: f64,
 ) {
+    launcher_log::progress(stage, file_name);
     let _ = app.emit(
         "download-progress",
         DownloadProgress {
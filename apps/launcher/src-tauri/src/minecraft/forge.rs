@@ -0,0 +1,565 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::download::{self, emit_progress};
+use super::instance::InstalledLoader;
+use super::java;
+use super::mod_sync;
+
+const FORGE_MAVEN: &str = "https://maven.minecraftforge.net";
+const FORGE_PROMOTIONS_URL: &str =
+    "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json";
+const NEOFORGE_MAVEN: &str = "https://maven.neoforged.net/releases";
+const NEOFORGE_VERSIONS_URL: &str =
+    "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge";
+
+/// NeoForge's first release line still used Forge's coordinates: `net.neoforged:forge:1.20.1-47.1.*`.
+const NEOFORGE_LEGACY_MC: &str = "1.20.1";
+const NEOFORGE_LEGACY_VERSIONS_URL: &str =
+    "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/forge";
+
+/// Scratch space for files the processors read out of the installer.
+const WORK_DIR: &str = ".nyaru-forge-install";
+
+/// Largest installer entry read into memory; the bundled loader jars and patches stay well below.
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct Promotions {
+    promos: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct MavenVersions {
+    versions: Vec<String>,
+}
+
+/// `install_profile.json` of a 1.13+ Forge or NeoForge installer.
+#[derive(Deserialize)]
+struct InstallProfile {
+    #[serde(default)]
+    data: HashMap<String, SidedValue>,
+    #[serde(default)]
+    processors: Vec<Processor>,
+    #[serde(default)]
+    libraries: Vec<ProfileLibrary>,
+    /// Path of the version JSON inside the installer.
+    json: String,
+}
+
+#[derive(Deserialize)]
+struct SidedValue {
+    client: String,
+}
+
+#[derive(Deserialize)]
+struct Processor {
+    #[serde(default)]
+    sides: Option<Vec<String>>,
+    jar: String,
+    #[serde(default)]
+    classpath: Vec<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    /// Output file to expected sha1, both possibly `{DATA}` references.
+    #[serde(default)]
+    outputs: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ProfileLibrary {
+    name: String,
+    #[serde(default)]
+    downloads: Option<ProfileLibraryDownloads>,
+}
+
+#[derive(Deserialize)]
+struct ProfileLibraryDownloads {
+    artifact: Option<ProfileArtifact>,
+}
+
+#[derive(Deserialize)]
+struct ProfileArtifact {
+    path: String,
+    /// Empty for files shipped inside the installer under `maven/`.
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    sha1: Option<String>,
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct VersionJson {
+    id: String,
+    #[serde(default)]
+    libraries: Vec<ProfileLibrary>,
+}
+
+fn display_name(kind: &str) -> &'static str {
+    if kind == "neoforge" {
+        "NeoForge"
+    } else {
+        "Forge"
+    }
+}
+
+/// Leading numeric components, for ordering Maven versions like `21.1.77` or `21.1.0-beta`.
+fn numeric(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-'])
+        .map_while(|c| c.parse().ok())
+        .collect()
+}
+
+/// Newest loader build for `mc_version`: Forge's recommended (else latest) promotion, or the
+/// newest stable NeoForge release of the matching line (`1.21.1` → `21.1.*`, `1.20.1` → `47.1.*`).
+pub async fn latest_version(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    kind: &str,
+    mc_version: &str,
+) -> Result<String, String> {
+    if kind == "forge" {
+        let promotions: Promotions = fetcher
+            .fetch_json("Forge 버전 목록", client.get(FORGE_PROMOTIONS_URL))
+            .await?;
+        return ["recommended", "latest"]
+            .iter()
+            .find_map(|tag| promotions.promos.get(&format!("{}-{}", mc_version, tag)))
+            .cloned()
+            .ok_or_else(|| format!("Minecraft {}용 Forge가 없습니다", mc_version));
+    }
+
+    if mc_version == NEOFORGE_LEGACY_MC {
+        let versions: MavenVersions = fetcher
+            .fetch_json(
+                "NeoForge 버전 목록",
+                client.get(NEOFORGE_LEGACY_VERSIONS_URL),
+            )
+            .await?;
+        let prefix = format!("{}-", mc_version);
+        let matching: Vec<String> = versions
+            .versions
+            .iter()
+            .filter_map(|v| v.strip_prefix(&prefix).map(str::to_string))
+            .collect();
+        return newest(&matching, mc_version);
+    }
+
+    let versions: MavenVersions = fetcher
+        .fetch_json("NeoForge 버전 목록", client.get(NEOFORGE_VERSIONS_URL))
+        .await?;
+    let mut mc = mc_version.split('.').skip(1);
+    let prefix = format!(
+        "{}.{}.",
+        mc.next().unwrap_or_default(),
+        mc.next().unwrap_or("0")
+    );
+    let matching: Vec<String> = versions
+        .versions
+        .into_iter()
+        .filter(|v| v.starts_with(&prefix))
+        .collect();
+    newest(&matching, mc_version)
+}
+
+/// Newest stable NeoForge build of `versions`, or the newest beta when there is no stable one.
+fn newest(versions: &[String], mc_version: &str) -> Result<String, String> {
+    versions
+        .iter()
+        .filter(|v| !v.contains('-'))
+        .max_by_key(|v| numeric(v))
+        .or_else(|| versions.iter().max_by_key(|v| numeric(v)))
+        .cloned()
+        .ok_or_else(|| format!("Minecraft {}용 NeoForge가 없습니다", mc_version))
+}
+
+fn installer_coords(kind: &str, mc_version: &str, version: &str) -> (String, &'static str) {
+    if kind == "neoforge" && mc_version == NEOFORGE_LEGACY_MC {
+        (
+            format!("net.neoforged:forge:{}-{}:installer", mc_version, version),
+            NEOFORGE_MAVEN,
+        )
+    } else if kind == "neoforge" {
+        (
+            format!("net.neoforged:neoforge:{}:installer", version),
+            NEOFORGE_MAVEN,
+        )
+    } else {
+        (
+            format!(
+                "net.minecraftforge:forge:{}-{}:installer",
+                mc_version, version
+            ),
+            FORGE_MAVEN,
+        )
+    }
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<Vec<u8>, String> {
+    let entry = archive
+        .by_name(name.trim_start_matches('/'))
+        .map_err(|_| format!("설치 프로그램에 {}이(가) 없습니다", name))?;
+    let mut data = Vec::new();
+    entry
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Read {} failed: {}", name, e))?;
+    if data.len() as u64 > MAX_ENTRY_BYTES {
+        return Err(format!("설치 프로그램의 {}이(가) 너무 큽니다", name));
+    }
+    Ok(data)
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, data).map_err(|e| format!("Write file failed: {}", e))
+}
+
+/// `Main-Class` from a jar's manifest.
+fn main_class(jar: &Path) -> Result<String, String> {
+    let file =
+        std::fs::File::open(jar).map_err(|e| format!("Open {} failed: {}", jar.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid jar {}: {}", jar.display(), e))?;
+    let manifest = read_entry(&mut archive, "META-INF/MANIFEST.MF")?;
+    String::from_utf8_lossy(&manifest)
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|c| c.trim().to_string())
+        .ok_or_else(|| format!("{}: Main-Class가 없습니다", jar.display()))
+}
+
+fn sha1_matches(path: &Path, expected: &str) -> bool {
+    std::fs::read(path)
+        .map(|data| mod_sync::verify_hashes(&data, Some(expected), None).is_ok())
+        .unwrap_or(false)
+}
+
+/// Download the installer's and the version's libraries, taking `url`-less ones from the
+/// installer's bundled `maven/` directory.
+///
+/// `url`-less libraries missing from the installer are produced by the processors, like
+/// `net.minecraftforge:forge:<mc>-<ver>:client`; they are returned for [`check_generated`].
+async fn install_libraries<'a>(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &MetaFetcher,
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    lib_dir: &Path,
+    libraries: &[&'a ProfileLibrary],
+    label: &str,
+) -> Result<Vec<&'a ProfileLibrary>, String> {
+    let mut generated = Vec::new();
+    let total = libraries.len() as u64;
+    for (i, lib) in libraries.iter().enumerate() {
+        let Some(artifact) = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref()) else {
+            continue;
+        };
+        let path = lib_dir.join(&artifact.path);
+        if !artifact.url.is_empty() {
+            emit_progress(
+                app,
+                &format!("{}: {}", label, lib.name),
+                "loader_libs",
+                i as u64,
+                total,
+                40.0 + 20.0 * i as f64 / total.max(1) as f64,
+            );
+            download::install_maven_library(
+                client,
+                fetcher,
+                &artifact.url,
+                &path,
+                artifact.sha1.as_deref(),
+                artifact.size,
+            )
+            .await
+            .map_err(|e| format!("{}: {}", lib.name, e))?;
+            continue;
+        }
+
+        // Bundled in the installer's `maven/` directory
+        let valid = match artifact.sha1 {
+            Some(ref sha1) => sha1_matches(&path, sha1),
+            None => path.exists(),
+        };
+        if valid {
+            continue;
+        }
+        let entry = format!("maven/{}", artifact.path);
+        if archive.index_for_name(&entry).is_none() {
+            // Installed later by the processors
+            generated.push(*lib);
+            continue;
+        }
+        let data = read_entry(archive, &entry)?;
+        if let Some(ref sha1) = artifact.sha1 {
+            mod_sync::verify_hashes(&data, Some(sha1), None)
+                .map_err(|e| format!("{}: {}", lib.name, e))?;
+        }
+        write_file(&path, &data)?;
+    }
+    Ok(generated)
+}
+
+/// Check that the processors produced every library [`install_libraries`] left to them.
+fn check_generated(lib_dir: &Path, generated: &[&ProfileLibrary]) -> Result<(), String> {
+    for lib in generated {
+        let Some(artifact) = lib.downloads.as_ref().and_then(|d| d.artifact.as_ref()) else {
+            continue;
+        };
+        let path = lib_dir.join(&artifact.path);
+        let valid = match artifact.sha1 {
+            Some(ref sha1) => sha1_matches(&path, sha1),
+            None => path.exists(),
+        };
+        if !valid {
+            return Err(format!(
+                "{}이(가) 설치되지 않았거나 손상되었습니다",
+                lib.name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a processor argument: `{KEY}` from the data map, `[coords]` to a library path.
+fn resolve_arg(arg: &str, vars: &HashMap<String, String>, lib_dir: &Path) -> String {
+    if let Some(key) = arg.strip_prefix('{').and_then(|a| a.strip_suffix('}')) {
+        if let Some(value) = vars.get(key) {
+            return value.clone();
+        }
+    }
+    if let Some(coords) = arg.strip_prefix('[').and_then(|a| a.strip_suffix(']')) {
+        return lib_dir
+            .join(download::maven_name_to_path(coords))
+            .to_string_lossy()
+            .to_string();
+    }
+    arg.to_string()
+}
+
+async fn run_processors(
+    app: &AppHandle,
+    java: &Path,
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    profile: &InstallProfile,
+    mut vars: HashMap<String, String>,
+    game_dir: &Path,
+    label: &str,
+) -> Result<(), String> {
+    let lib_dir = &game_dir.join("libraries");
+    let work_dir = &game_dir.join(WORK_DIR);
+    // Data values are library coordinates, quoted literals or files inside the installer
+    for (key, value) in &profile.data {
+        let value = &value.client;
+        let resolved =
+            if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+                literal.to_string()
+            } else if value.starts_with('[') {
+                resolve_arg(value, &vars, lib_dir)
+            } else if value.starts_with('/') {
+                let target = work_dir.join(value.trim_start_matches('/'));
+                write_file(&target, &read_entry(archive, value)?)?;
+                target.to_string_lossy().to_string()
+            } else {
+                value.clone()
+            };
+        vars.insert(key.clone(), resolved);
+    }
+
+    let sep = if cfg!(windows) { ";" } else { ":" };
+    let processors: Vec<&Processor> = profile
+        .processors
+        .iter()
+        .filter(|p| {
+            p.sides
+                .as_ref()
+                .is_none_or(|sides| sides.iter().any(|s| s == "client"))
+        })
+        .collect();
+    let total = processors.len() as u64;
+    for (i, processor) in processors.iter().enumerate() {
+        let outputs: Vec<(String, String)> = processor
+            .outputs
+            .iter()
+            .map(|(path, sha1)| {
+                (
+                    resolve_arg(path, &vars, lib_dir),
+                    resolve_arg(sha1, &vars, lib_dir),
+                )
+            })
+            .collect();
+        let outputs_valid = |outputs: &[(String, String)]| {
+            outputs
+                .iter()
+                .all(|(path, sha1)| sha1_matches(Path::new(path), sha1.trim_matches('\'')))
+        };
+        if !outputs.is_empty() && outputs_valid(&outputs) {
+            continue;
+        }
+
+        emit_progress(
+            app,
+            &format!("{} 설치 처리 중 ({}/{})", label, i + 1, total),
+            "loader_processors",
+            i as u64,
+            total,
+            60.0 + 35.0 * i as f64 / total.max(1) as f64,
+        );
+        let jar = lib_dir.join(download::maven_name_to_path(&processor.jar));
+        let mut classpath = vec![jar.to_string_lossy().to_string()];
+        classpath.extend(processor.classpath.iter().map(|c| {
+            lib_dir
+                .join(download::maven_name_to_path(c))
+                .to_string_lossy()
+                .to_string()
+        }));
+        let args: Vec<String> = processor
+            .args
+            .iter()
+            .map(|a| resolve_arg(a, &vars, lib_dir))
+            .collect();
+
+        let output = tokio::process::Command::new(java)
+            .arg("-cp")
+            .arg(classpath.join(sep))
+            .arg(main_class(&jar)?)
+            .args(&args)
+            .output()
+            .await
+            .map_err(|e| format!("Run {} failed: {}", processor.jar, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+            return Err(format!(
+                "{} 설치 처리 실패 ({}):\n{}",
+                label,
+                processor.jar,
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            ));
+        }
+        if !outputs_valid(&outputs) {
+            return Err(format!(
+                "{} 설치 처리 결과가 올바르지 않습니다 ({})",
+                label, processor.jar
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Install Forge or NeoForge for `mc_version` the way their installer does for a client:
+/// libraries, processors (run with the launcher's Java), then the version JSON.
+///
+/// The version JSON is written last, so its presence means the install completed.
+pub async fn install(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &MetaFetcher,
+    game_dir: &Path,
+    mc_version: &str,
+    kind: &str,
+    version: &str,
+) -> Result<InstalledLoader, String> {
+    let label = display_name(kind);
+    let lib_dir = game_dir.join("libraries");
+    let (coords, maven) = installer_coords(kind, mc_version, version);
+    let installer_path = lib_dir.join(download::maven_name_to_path(&coords));
+    emit_progress(
+        app,
+        &format!("{} 설치 프로그램 확인 중...", label),
+        "loader",
+        0,
+        1,
+        20.0,
+    );
+    // The processors run code from this jar, so a cached copy is checked like a fresh one
+    let url = format!("{}/{}", maven, download::maven_name_to_path(&coords));
+    download::install_maven_library(client, fetcher, &url, &installer_path, None, None)
+        .await
+        .map_err(|e| format!("{} 설치 프로그램: {}", label, e))?;
+
+    let file = std::fs::File::open(&installer_path).map_err(|e| e.to_string())?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Invalid installer: {}", e))?;
+    let profile_json: Value =
+        serde_json::from_slice(&read_entry(&mut archive, "install_profile.json")?)
+            .map_err(|e| format!("install_profile.json parse failed: {}", e))?;
+    if profile_json.get("versionInfo").is_some() {
+        return Err(format!(
+            "{} {}은(는) 지원하지 않는 구버전 설치 프로그램입니다",
+            label, version
+        ));
+    }
+    let profile: InstallProfile = serde_json::from_value(profile_json)
+        .map_err(|e| format!("install_profile.json parse failed: {}", e))?;
+    let version_bytes = read_entry(&mut archive, &profile.json)?;
+    let version_json: VersionJson = serde_json::from_slice(&version_bytes)
+        .map_err(|e| format!("version.json parse failed: {}", e))?;
+
+    let installed = InstalledLoader {
+        kind: kind.to_string(),
+        version: version.to_string(),
+        profile_id: version_json.id.clone(),
+    };
+    let profile_path = game_dir
+        .join("versions")
+        .join(&version_json.id)
+        .join(format!("{}.json", version_json.id));
+    let libraries: Vec<&ProfileLibrary> = profile
+        .libraries
+        .iter()
+        .chain(version_json.libraries.iter())
+        .collect();
+    let generated = install_libraries(
+        app,
+        client,
+        fetcher,
+        &mut archive,
+        &lib_dir,
+        &libraries,
+        label,
+    )
+    .await?;
+    // Processors only run once; the libraries above are re-checked every time
+    if profile_path.exists() && check_generated(&lib_dir, &generated).is_ok() {
+        return Ok(installed);
+    }
+
+    let java = java::find_managed_java()
+        .or_else(java::detect_java)
+        .ok_or_else(|| format!("{} 설치에 Java가 필요합니다", label))?;
+    let mc_jar = game_dir
+        .join("versions")
+        .join(mc_version)
+        .join(format!("{}.jar", mc_version));
+    let vars: HashMap<String, String> = [
+        ("SIDE", "client".to_string()),
+        ("MINECRAFT_VERSION", mc_version.to_string()),
+        ("MINECRAFT_JAR", mc_jar.to_string_lossy().to_string()),
+        ("ROOT", game_dir.to_string_lossy().to_string()),
+        ("INSTALLER", installer_path.to_string_lossy().to_string()),
+        ("LIBRARY_DIR", lib_dir.to_string_lossy().to_string()),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+    let result = run_processors(app, &java, &mut archive, &profile, vars, game_dir, label).await;
+    let _ = std::fs::remove_dir_all(game_dir.join(WORK_DIR));
+    result?;
+    check_generated(&lib_dir, &generated).map_err(|e| format!("{}: {}", label, e))?;
+
+    write_file(&profile_path, &version_bytes)?;
+    Ok(installed)
+}
@@ -0,0 +1,372 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::download;
+use super::process;
+use super::redact;
+
+/// Session logs kept in `launcher-logs/`; older ones are deleted when a game starts.
+const MAX_SESSION_LOGS: usize = 20;
+/// A session log stops growing here so a spamming mod cannot fill the disk.
+const MAX_SESSION_LOG_BYTES: u64 = 50 * 1024 * 1024;
+/// Lines kept in memory for crash analysis; errors such as a wrong Java version never make it
+/// into a crash report.
+const TAIL_LINES: usize = 300;
+/// An unfinished log4j event is dropped back to plain lines past this size.
+const MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// One log event of the game, or a plain line the game printed outside log4j.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEvent {
+    /// Unix milliseconds.
+    pub time: Option<u64>,
+    pub thread: Option<String>,
+    pub level: String,
+    pub logger: Option<String>,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+impl LogEvent {
+    pub fn plain(level: &str, message: &str) -> Self {
+        LogEvent {
+            time: Some(process::now_millis()),
+            thread: None,
+            level: level.to_string(),
+            logger: None,
+            message: message.to_string(),
+            throwable: None,
+        }
+    }
+
+    /// `2026-01-02T03:04:05.678Z [Render thread/INFO] (Minecraft) message`, plus the throwable.
+    pub fn to_text(&self) -> String {
+        let time = self.time.map(format_utc).unwrap_or_default();
+        let source = match self.thread {
+            Some(ref thread) => format!("{}/{}", thread, self.level),
+            None => self.level.clone(),
+        };
+        let logger = self
+            .logger
+            .as_ref()
+            .map(|l| format!(" ({})", l))
+            .unwrap_or_default();
+        let mut text = format!("{} [{}]{} {}", time, source, logger, self.message);
+        if let Some(ref throwable) = self.throwable {
+            text.push('\n');
+            text.push_str(throwable.trim_end());
+        }
+        text
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.mmmZ` for unix milliseconds.
+pub fn format_utc(millis: u64) -> String {
+    let secs = millis / 1000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        millis % 1000
+    )
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let end = start + tag[start..].find('"')?;
+    Some(unescape(&tag[start..end]))
+}
+
+/// Content of `<element><![CDATA[…]]></element>`; log4j splits a `]]>` inside the text into
+/// two CDATA sections.
+fn cdata(event: &str, element: &str) -> Option<String> {
+    let open = format!("<{}><![CDATA[", element);
+    let close = format!("]]></{}>", element);
+    let start = event.find(&open)? + open.len();
+    let end = start + event[start..].find(&close)?;
+    Some(event[start..end].replace("]]]]><![CDATA[>", "]]>"))
+}
+
+/// Turns the game's output into events. With the version's `logging.client` config the game
+/// prints log4j `LegacyXMLLayout` events, which may span several lines.
+#[derive(Default)]
+pub struct Log4jParser {
+    pending: Option<String>,
+}
+
+impl Log4jParser {
+    /// Feed one output line; `plain_level` is used for lines that are not log4j events.
+    ///
+    /// An event that never closes (the JVM died mid-write, a mod printed raw XML) is given up
+    /// past `MAX_PENDING_BYTES` and its lines come out as plain events.
+    pub fn push(&mut self, line: &str, plain_level: &str) -> Vec<LogEvent> {
+        let trimmed = line.trim_start();
+        let mut pending = match self.pending.take() {
+            Some(pending) => pending,
+            None if trimmed.starts_with("<log4j:Event") => String::new(),
+            None => return vec![LogEvent::plain(plain_level, line)],
+        };
+        if !pending.is_empty() {
+            pending.push('\n');
+        }
+        pending.push_str(line);
+        if !trimmed.ends_with("</log4j:Event>") {
+            if pending.len() > MAX_PENDING_BYTES {
+                return plain_lines(&pending, plain_level);
+            }
+            self.pending = Some(pending);
+            return Vec::new();
+        }
+
+        let tag_end = pending.find('>').unwrap_or(pending.len());
+        let tag = &pending[..tag_end];
+        vec![LogEvent {
+            time: attribute(tag, "timestamp").and_then(|t| t.parse().ok()),
+            thread: attribute(tag, "thread"),
+            level: attribute(tag, "level").unwrap_or_else(|| plain_level.to_string()),
+            logger: attribute(tag, "logger"),
+            message: cdata(&pending, "log4j:Message").unwrap_or_default(),
+            throwable: cdata(&pending, "log4j:Throwable"),
+        }]
+    }
+
+    /// The lines of an event still open when the output ended, as plain events.
+    pub fn finish(&mut self, plain_level: &str) -> Vec<LogEvent> {
+        self.pending
+            .take()
+            .map(|pending| plain_lines(&pending, plain_level))
+            .unwrap_or_default()
+    }
+}
+
+fn plain_lines(text: &str, plain_level: &str) -> Vec<LogEvent> {
+    text.split('\n')
+        .map(|line| LogEvent::plain(plain_level, line))
+        .collect()
+}
+
+pub fn session_logs_dir() -> PathBuf {
+    download::get_game_dir().join("launcher-logs")
+}
+
+/// Session log files, newest first.
+pub fn list_session_logs() -> Vec<PathBuf> {
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(session_logs_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".log"))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Everything one game session printed: written to `launcher-logs/` and the last lines kept
+/// for crash analysis.
+pub struct SessionOutput {
+    /// Unbuffered, so the log of a running game can be read (and uploaded) as it is.
+    file: Option<File>,
+    written: u64,
+    tail: VecDeque<String>,
+}
+
+impl SessionOutput {
+    /// Start the log of `session_id`, dropping the oldest logs beyond the limit. The game still
+    /// runs when the file cannot be created; only the tail is kept then.
+    pub fn create(session_id: &str, instance_id: &str) -> Self {
+        let dir = session_logs_dir();
+        for old in list_session_logs().into_iter().skip(MAX_SESSION_LOGS - 1) {
+            let _ = std::fs::remove_file(old);
+        }
+        let short_id: String = session_id.chars().take(8).collect();
+        let safe_instance: String = Path::new(instance_id)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = dir.join(format!(
+            "{}-{}-{}.log",
+            process::now_secs(),
+            safe_instance,
+            short_id
+        ));
+        let file = std::fs::create_dir_all(&dir)
+            .and_then(|_| File::create(&path))
+            .ok();
+        SessionOutput {
+            file,
+            written: 0,
+            tail: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: &LogEvent) {
+        let text = redact::redact_event(event).to_text();
+        if let Some(ref mut file) = self.file {
+            if self.written < MAX_SESSION_LOG_BYTES {
+                let _ = writeln!(file, "{}", text);
+                self.written += text.len() as u64 + 1;
+                if self.written >= MAX_SESSION_LOG_BYTES {
+                    let _ = writeln!(
+                        file,
+                        "[런처] 로그가 너무 커서 이후 내용은 저장하지 않습니다"
+                    );
+                }
+            }
+        }
+        if self.tail.len() == TAIL_LINES {
+            self.tail.pop_front();
+        }
+        self.tail.push_back(text);
+    }
+
+    pub fn tail(&self) -> Vec<String> {
+        self.tail.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all(parser: &mut Log4jParser, text: &str) -> Vec<LogEvent> {
+        text.lines()
+            .flat_map(|line| parser.push(line, "INFO"))
+            .collect()
+    }
+
+    #[test]
+    fn parses_single_line_event() {
+        let mut parser = Log4jParser::default();
+        let events = push_all(
+            &mut parser,
+            r#"<log4j:Event logger="Minecraft" timestamp="1767323045678" level="WARN" thread="Render thread"><log4j:Message><![CDATA[Tom &amp; Jerry]]></log4j:Message></log4j:Event>"#,
+        );
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.time, Some(1_767_323_045_678));
+        assert_eq!(event.level, "WARN");
+        assert_eq!(event.thread.as_deref(), Some("Render thread"));
+        assert_eq!(event.logger.as_deref(), Some("Minecraft"));
+        assert_eq!(event.message, "Tom &amp; Jerry");
+        assert!(event.throwable.is_none());
+        assert_eq!(
+            event.to_text(),
+            "2026-01-02T03:04:05.678Z [Render thread/WARN] (Minecraft) Tom &amp; Jerry"
+        );
+    }
+
+    #[test]
+    fn joins_multi_line_cdata() {
+        let mut parser = Log4jParser::default();
+        let events = push_all(
+            &mut parser,
+            "plain before\n\
+             <log4j:Event logger=\"Mixin\" timestamp=\"1\" level=\"ERROR\" thread=\"main\">\n\
+             \t<log4j:Message><![CDATA[first line\n\
+             second line]]></log4j:Message>\n\
+             \t<log4j:Throwable><![CDATA[java.lang.RuntimeException: boom\n\
+             \tat Foo.bar(Foo.java:1)\n\
+             ]]></log4j:Throwable>\n\
+             </log4j:Event>\n\
+             plain after",
+        );
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].message, "plain before");
+        assert_eq!(events[0].level, "INFO");
+        assert_eq!(events[1].level, "ERROR");
+        assert_eq!(events[1].message, "first line\nsecond line");
+        assert_eq!(
+            events[1].throwable.as_deref(),
+            Some("java.lang.RuntimeException: boom\n\tat Foo.bar(Foo.java:1)\n")
+        );
+        assert_eq!(events[2].message, "plain after");
+    }
+
+    #[test]
+    fn rejoins_split_cdata_end() {
+        let mut parser = Log4jParser::default();
+        let events = push_all(
+            &mut parser,
+            r#"<log4j:Event logger="x" timestamp="1" level="INFO" thread="main"><log4j:Message><![CDATA[a]]]]><![CDATA[>b]]></log4j:Message></log4j:Event>"#,
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, "a]]>b");
+    }
+
+    #[test]
+    fn flushes_truncated_event_at_end() {
+        let mut parser = Log4jParser::default();
+        let events = push_all(
+            &mut parser,
+            "<log4j:Event logger=\"x\" timestamp=\"1\" level=\"INFO\" thread=\"main\">\n\
+             \t<log4j:Message><![CDATA[cut off\n\
+             #  SIGSEGV (0xb) at pc=0x0000000000000000",
+        );
+        assert!(events.is_empty());
+
+        let events = parser.finish("WARN");
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.level == "WARN"));
+        assert_eq!(
+            events[2].message,
+            "#  SIGSEGV (0xb) at pc=0x0000000000000000"
+        );
+        assert!(parser.finish("WARN").is_empty());
+    }
+
+    #[test]
+    fn gives_up_on_oversized_event() {
+        let mut parser = Log4jParser::default();
+        assert!(parser
+            .push("<log4j:Event level=\"INFO\">", "INFO")
+            .is_empty());
+        let line = "x".repeat(1024);
+        let mut pushed = 1;
+        let flushed = loop {
+            let events = parser.push(&line, "INFO");
+            pushed += 1;
+            if !events.is_empty() {
+                break events;
+            }
+            assert!(pushed * line.len() <= 2 * MAX_PENDING_BYTES);
+        };
+        assert_eq!(flushed.len(), pushed);
+        assert_eq!(flushed[0].message, "<log4j:Event level=\"INFO\">");
+        assert_eq!(flushed[1].message, line);
+
+        // Output after the broken event is not swallowed
+        let after = parser.push("[main/INFO]: next", "INFO");
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].message, "[main/INFO]: next");
+    }
+}
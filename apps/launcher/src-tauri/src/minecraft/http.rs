@@ -0,0 +1,407 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::download;
+use crate::settings;
+
+pub const USER_AGENT: &str = concat!(
+    "nyaru-launcher/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/INIRU/Tinklepaw)"
+);
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 15;
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 60;
+
+/// Shown instead of a stored proxy password; sending it back keeps the saved one.
+pub const PASSWORD_MASK: &str = "********";
+
+/// The proxy password lives here instead of the settings store the frontend can read.
+const PROXY_PASSWORD_FILE: &str = "proxy-password";
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkSettings {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` proxy URL.
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    /// Extra root certificates (PEM or DER files) trusted in addition to the system store.
+    pub extra_ca_certs: Vec<String>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    /// Global download cap in KiB/s. `None` or 0 means unlimited.
+    pub bandwidth_limit_kbps: Option<u64>,
+}
+
+fn proxy_password_path() -> PathBuf {
+    download::get_launcher_dir().join(PROXY_PASSWORD_FILE)
+}
+
+/// Stored network settings with the proxy password filled in from its own file.
+/// A password left in the store by an older version is moved out on first read.
+pub fn load_settings(app: &AppHandle) -> NetworkSettings {
+    let mut network: NetworkSettings = settings::load(app, settings::NETWORK_SETTINGS_KEY);
+    if let Some(legacy) = network.proxy_password.take() {
+        if write_proxy_password(Some(&legacy)).is_ok() {
+            let _ = settings::save(app, settings::NETWORK_SETTINGS_KEY, &network);
+        }
+        network.proxy_password = Some(legacy);
+        return network;
+    }
+    network.proxy_password = std::fs::read_to_string(proxy_password_path())
+        .ok()
+        .filter(|p| !p.is_empty());
+    network
+}
+
+/// Persist `network`, keeping the current password when it comes back as [`PASSWORD_MASK`].
+pub fn save_settings(app: &AppHandle, network: &NetworkSettings) -> Result<(), String> {
+    let mut stored = network.clone();
+    match stored.proxy_password.take() {
+        Some(p) if p == PASSWORD_MASK => {}
+        Some(p) if !p.is_empty() => write_proxy_password(Some(&p))?,
+        _ => write_proxy_password(None)?,
+    }
+    settings::save(app, settings::NETWORK_SETTINGS_KEY, &stored)
+}
+
+/// Replace a masked password with the stored one so the settings can be applied.
+pub fn unmask(network: &mut NetworkSettings) {
+    if network.proxy_password.as_deref() == Some(PASSWORD_MASK) {
+        network.proxy_password = std::fs::read_to_string(proxy_password_path()).ok();
+    }
+}
+
+/// Copy safe to hand to the frontend.
+pub fn masked(network: &NetworkSettings) -> NetworkSettings {
+    let mut masked = network.clone();
+    if masked
+        .proxy_password
+        .as_deref()
+        .is_some_and(|p| !p.is_empty())
+    {
+        masked.proxy_password = Some(PASSWORD_MASK.to_string());
+    }
+    masked
+}
+
+fn write_proxy_password(password: Option<&str>) -> Result<(), String> {
+    let path = proxy_password_path();
+    let Some(password) = password else {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        };
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_private(&path, password).map_err(|e| format!("Save proxy password: {}", e))
+}
+
+/// Write `contents` to a file only the current user can read, from the moment it exists.
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files; tighten one an older version left readable
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+static CLIENT: RwLock<Option<reqwest::Client>> = RwLock::new(None);
+static LIMITER: Mutex<Option<TokenBucket>> = Mutex::new(None);
+
+struct TokenBucket {
+    bytes_per_sec: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(kbps: u64, now: Instant) -> Self {
+        let bytes_per_sec = (kbps * 1024) as f64;
+        TokenBucket {
+            bytes_per_sec,
+            available: bytes_per_sec,
+            last_refill: now,
+        }
+    }
+
+    /// Take `bytes` at `now`, returning how long to wait before using them.
+    fn take(&mut self, bytes: usize, now: Instant) -> Duration {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        // Allow at most one second of burst
+        self.available = (self.available + elapsed * self.bytes_per_sec).min(self.bytes_per_sec);
+        self.available -= bytes as f64;
+        if self.available < 0.0 {
+            Duration::from_secs_f64(-self.available / self.bytes_per_sec)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Shared HTTP client used for every launcher request. Cheap to clone.
+pub fn client() -> reqwest::Client {
+    if let Some(client) = CLIENT.read().ok().and_then(|c| c.clone()) {
+        return client;
+    }
+    let client = build_client(&NetworkSettings::default()).unwrap_or_default();
+    if let Ok(mut slot) = CLIENT.write() {
+        slot.get_or_insert(client).clone()
+    } else {
+        client
+    }
+}
+
+/// Rebuild the shared client and bandwidth limiter from `settings`.
+pub fn configure(settings: &NetworkSettings) -> Result<(), String> {
+    let client = build_client(settings)?;
+    *CLIENT.write().map_err(|e| e.to_string())? = Some(client);
+
+    let limit = settings.bandwidth_limit_kbps.filter(|&kbps| kbps > 0);
+    *LIMITER.lock().map_err(|e| e.to_string())? =
+        limit.map(|kbps| TokenBucket::new(kbps, Instant::now()));
+    Ok(())
+}
+
+pub fn build_client(settings: &NetworkSettings) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(
+            settings
+                .connect_timeout_secs
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+        ))
+        .read_timeout(Duration::from_secs(
+            settings
+                .read_timeout_secs
+                .unwrap_or(DEFAULT_READ_TIMEOUT_SECS),
+        ));
+
+    if let Some(proxy_url) = settings
+        .proxy_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        builder = builder.proxy(build_proxy(proxy_url.trim(), settings)?);
+    }
+
+    for path in &settings.extra_ca_certs {
+        let data = std::fs::read(path).map_err(|e| format!("Read certificate {}: {}", path, e))?;
+        let cert = if data.starts_with(b"-----BEGIN") {
+            reqwest::Certificate::from_pem(&data)
+        } else {
+            reqwest::Certificate::from_der(&data)
+        }
+        .map_err(|e| format!("Invalid certificate {}: {}", path, e))?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("HTTP client build failed: {}", e))
+}
+
+fn build_proxy(proxy_url: &str, settings: &NetworkSettings) -> Result<reqwest::Proxy, String> {
+    let mut url = url::Url::parse(proxy_url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    let username = settings.proxy_username.as_deref().filter(|u| !u.is_empty());
+    let password = settings.proxy_password.as_deref().unwrap_or("");
+
+    match url.scheme() {
+        // SOCKS credentials are only read from the URL itself
+        "socks5" | "socks5h" => {
+            if let Some(username) = username {
+                url.set_username(username)
+                    .and_then(|_| url.set_password(Some(password)))
+                    .map_err(|_| "Invalid proxy credentials".to_string())?;
+            }
+            reqwest::Proxy::all(url.as_str()).map_err(|e| format!("Invalid proxy: {}", e))
+        }
+        "http" | "https" => {
+            let proxy =
+                reqwest::Proxy::all(url.as_str()).map_err(|e| format!("Invalid proxy: {}", e))?;
+            Ok(match username {
+                Some(username) => proxy.basic_auth(username, password),
+                None => proxy,
+            })
+        }
+        other => Err(format!("Unsupported proxy scheme: {}", other)),
+    }
+}
+
+/// Wait until `bytes` may be consumed under the global bandwidth limit.
+pub async fn throttle(bytes: usize) {
+    let delay = {
+        let Ok(mut limiter) = LIMITER.lock() else {
+            return;
+        };
+        let Some(bucket) = limiter.as_mut() else {
+            return;
+        };
+        bucket.take(bytes, Instant::now())
+    };
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Acts as an HTTP proxy for one request, returning its request line and headers.
+    async fn stand_in_proxy(listener: TcpListener) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        let head = loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                break text[..end].to_ascii_lowercase();
+            }
+            if n == 0 {
+                break text.to_ascii_lowercase();
+            }
+        };
+        let reply = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+        stream.write_all(reply.as_bytes()).await.unwrap();
+        head
+    }
+
+    fn proxy_settings(proxy_url: &str, username: Option<&str>) -> NetworkSettings {
+        NetworkSettings {
+            proxy_url: Some(proxy_url.to_string()),
+            proxy_username: username.map(str::to_string),
+            proxy_password: Some("pa:ss".to_string()),
+            ..NetworkSettings::default()
+        }
+    }
+
+    #[test]
+    fn validates_proxy_urls() {
+        for url in ["http://127.0.0.1:8080", "socks5h://127.0.0.1:1080"] {
+            assert!(
+                build_proxy(url, &proxy_settings(url, Some("steve"))).is_ok(),
+                "{}",
+                url
+            );
+        }
+        let settings = NetworkSettings::default();
+        let err = build_proxy("ftp://127.0.0.1:21", &settings).err().unwrap();
+        assert!(err.contains("Unsupported proxy scheme"), "{}", err);
+        assert!(build_proxy("not a url", &settings).is_err());
+        // A blank proxy URL means no proxy
+        assert!(build_client(&proxy_settings("  ", None)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn sends_proxy_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let proxy = tokio::spawn(stand_in_proxy(listener));
+
+        let client = build_client(&proxy_settings(&proxy_url, Some("steve"))).unwrap();
+        let body = client
+            .get("http://meta.example.invalid/versions.json")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "ok");
+
+        let head = proxy.await.unwrap();
+        let credentials = base64::engine::general_purpose::STANDARD.encode("steve:pa:ss");
+        assert!(
+            head.starts_with("get http://meta.example.invalid/versions.json"),
+            "{}",
+            head
+        );
+        assert!(
+            head.contains(&format!(
+                "proxy-authorization: basic {}",
+                credentials.to_ascii_lowercase()
+            )),
+            "{}",
+            head
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_credentials_without_username() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let proxy = tokio::spawn(stand_in_proxy(listener));
+
+        let client = build_client(&proxy_settings(&proxy_url, Some(""))).unwrap();
+        client
+            .get("http://meta.example.invalid/versions.json")
+            .send()
+            .await
+            .unwrap();
+        assert!(!proxy.await.unwrap().contains("proxy-authorization"));
+    }
+
+    #[test]
+    fn throttles_past_the_burst() {
+        let start = Instant::now();
+        // 1 KiB/s
+        let mut bucket = TokenBucket::new(1, start);
+        assert_eq!(bucket.take(1024, start), Duration::ZERO);
+        assert_eq!(bucket.take(512, start), Duration::from_millis(500));
+
+        // The debt is paid back before new bytes go through
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.take(512, later), Duration::ZERO);
+
+        // Idle time refills at most one second's worth
+        let idle = later + Duration::from_secs(60);
+        assert_eq!(bucket.take(2048, idle), Duration::from_secs(1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn password_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("http-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let fresh = dir.join("fresh");
+        write_private(&fresh, "secret").unwrap();
+        let fresh_mode = mode(&fresh);
+
+        let old = dir.join("old");
+        std::fs::write(&old, "a much longer old secret").unwrap();
+        std::fs::set_permissions(&old, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&old, "secret").unwrap();
+        let old_mode = mode(&old);
+        let old_contents = std::fs::read_to_string(&old).unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(fresh_mode, 0o600);
+        assert_eq!(old_mode, 0o600);
+        assert_eq!(old_contents, "secret");
+    }
+}
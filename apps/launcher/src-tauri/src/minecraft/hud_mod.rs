@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::cache::MetaFetcher;
+use super::download::{self, emit_progress};
+use super::mod_meta::{self, ModMetadata};
+use super::mod_sync;
+
+/// Mod id of `apps/minecraft/hud-mod`.
+pub const HUD_MOD_ID: &str = "nyaru-hud";
+
+const GITHUB_API: &str = "https://api.github.com";
+
+/// Where the launcher gets the HUD jar from.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "source", rename_all = "camelCase")]
+pub enum HudModSource {
+    /// Release `{tag_prefix}{version}` of `repo`, e.g. `hud-v1.0.1`. The jar must match the
+    /// pinned hashes before it is moved into `mods/`.
+    #[serde(rename_all = "camelCase")]
+    GithubRelease {
+        repo: String,
+        #[serde(default = "default_tag_prefix")]
+        tag_prefix: String,
+        version: Option<String>,
+        sha1: Option<String>,
+        sha512: Option<String>,
+    },
+    /// A jar on disk; relative paths are resolved against the app's resource directory.
+    File { path: String },
+}
+
+fn default_tag_prefix() -> String {
+    "hud-v".to_string()
+}
+
+impl HudModSource {
+    /// A release source must pin the version and at least one hash of the jar.
+    pub fn validate(&self) -> Result<(), String> {
+        if let HudModSource::GithubRelease {
+            version,
+            sha1,
+            sha512,
+            ..
+        } = self
+        {
+            if version.is_none() {
+                return Err("HUD 모드 버전이 설정에 고정되어 있지 않습니다".to_string());
+            }
+            if sha1.is_none() && sha512.is_none() {
+                return Err("HUD 모드의 sha1 또는 sha512가 설정에 없습니다".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HudModStatus {
+    pub installed: bool,
+    pub version: Option<String>,
+    pub filename: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HudModReport {
+    pub version: String,
+    pub previous_version: Option<String>,
+    pub updated: bool,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+enum Candidate {
+    Remote {
+        version: String,
+        url: String,
+        sha1: Option<String>,
+        sha512: Option<String>,
+    },
+    Local {
+        version: String,
+        path: PathBuf,
+    },
+}
+
+impl Candidate {
+    fn version(&self) -> &str {
+        match self {
+            Candidate::Remote { version, .. } | Candidate::Local { version, .. } => version,
+        }
+    }
+}
+
+fn read_mod_json(path: &Path) -> Option<ModMetadata> {
+    mod_meta::read_jar(path).ok().flatten()
+}
+
+/// Every jar in `mods/` that declares the HUD mod id, whatever its file name.
+fn find_installed(mods_dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jar"))
+        .filter_map(|p| {
+            let info = read_mod_json(&p)?;
+            (info.id == HUD_MOD_ID).then_some((p, info.version))
+        })
+        .collect()
+}
+
+pub fn status(game_dir: &Path) -> HudModStatus {
+    let installed = find_installed(&game_dir.join("mods")).into_iter().next();
+    HudModStatus {
+        installed: installed.is_some(),
+        version: installed.as_ref().map(|(_, v)| v.clone()),
+        filename: installed
+            .and_then(|(p, _)| p.file_name().map(|n| n.to_string_lossy().to_string())),
+    }
+}
+
+/// Whether `candidate` should replace `installed`; unparsable versions compare by equality.
+fn is_newer(candidate: &str, installed: &str) -> bool {
+    match (
+        semver::Version::parse(candidate),
+        semver::Version::parse(installed),
+    ) {
+        (Ok(c), Ok(i)) => c > i,
+        _ => candidate != installed,
+    }
+}
+
+async fn latest(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    source: &HudModSource,
+) -> Result<Candidate, String> {
+    source.validate()?;
+    match source {
+        HudModSource::GithubRelease {
+            repo,
+            tag_prefix,
+            version,
+            sha1,
+            sha512,
+        } => {
+            let version = version.clone().unwrap_or_default();
+            let tag = format!("{}{}", tag_prefix, version);
+            let request = client
+                .get(format!(
+                    "{}/repos/{}/releases/tags/{}",
+                    GITHUB_API, repo, tag
+                ))
+                .header("Accept", "application/vnd.github+json");
+            let release: GithubRelease =
+                fetcher.fetch_json("Nyaru HUD 릴리스 조회", request).await?;
+            let asset = release
+                .assets
+                .into_iter()
+                .find(|a| a.name.ends_with(".jar"))
+                .ok_or_else(|| format!("{} 릴리스 {}에 jar가 없습니다", repo, tag))?;
+            Ok(Candidate::Remote {
+                version,
+                url: asset.browser_download_url,
+                sha1: sha1.clone(),
+                sha512: sha512.clone(),
+            })
+        }
+        HudModSource::File { path } => {
+            let mut path = PathBuf::from(path);
+            if path.is_relative() {
+                let resources = app.path().resource_dir().map_err(|e| e.to_string())?;
+                path = resources.join(path);
+            }
+            let info = read_mod_json(&path)
+                .ok_or_else(|| format!("{}: fabric.mod.json을 읽을 수 없습니다", path.display()))?;
+            if info.id != HUD_MOD_ID {
+                return Err(format!(
+                    "{}: {} 모드가 아닙니다",
+                    path.display(),
+                    HUD_MOD_ID
+                ));
+            }
+            Ok(Candidate::Local {
+                version: info.version,
+                path,
+            })
+        }
+    }
+}
+
+/// Install the HUD mod, or update it when the source has a newer version than the jar in `mods/`.
+pub async fn install(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    source: &HudModSource,
+) -> Result<HudModReport, String> {
+    let mods_dir = game_dir.join("mods");
+    std::fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+
+    emit_progress(app, "Nyaru HUD 확인 중...", "hud", 0, 1, 99.0);
+    let installed = find_installed(&mods_dir);
+    let previous_version = installed.first().map(|(_, v)| v.clone());
+    let candidate = latest(app, client, fetcher, source).await?;
+
+    if let Some(ref current) = previous_version {
+        // A pinned release is installed exactly, which also allows rolling back
+        let up_to_date = match candidate {
+            Candidate::Remote { ref version, .. } => version == current,
+            Candidate::Local { ref version, .. } => !is_newer(version, current),
+        };
+        if installed.len() == 1 && up_to_date {
+            return Ok(HudModReport {
+                version: current.clone(),
+                previous_version,
+                updated: false,
+            });
+        }
+    }
+
+    emit_progress(
+        app,
+        &format!("Nyaru HUD {} 설치 중...", candidate.version()),
+        "hud",
+        0,
+        1,
+        99.0,
+    );
+    let data = match candidate {
+        Candidate::Remote {
+            ref url,
+            ref sha1,
+            ref sha512,
+            ..
+        } => {
+            let data = download::download_bytes(client, url).await?;
+            mod_sync::verify_hashes(&data, sha1.as_deref(), sha512.as_deref())
+                .map_err(|e| format!("Nyaru HUD: {}", e))?;
+            data
+        }
+        Candidate::Local { ref path, .. } => {
+            std::fs::read(path).map_err(|e| format!("Read {} failed: {}", path.display(), e))?
+        }
+    };
+
+    // The jar itself is authoritative for the id and version, not the release tag
+    let part = mods_dir.join(format!("{}.jar.part", HUD_MOD_ID));
+    std::fs::write(&part, &data).map_err(|e| format!("Write file failed: {}", e))?;
+    let info = match read_mod_json(&part) {
+        Some(info) if info.id == HUD_MOD_ID => info,
+        _ => {
+            let _ = std::fs::remove_file(&part);
+            return Err(format!("다운로드한 파일이 {} 모드가 아닙니다", HUD_MOD_ID));
+        }
+    };
+
+    for (path, _) in &installed {
+        let _ = std::fs::remove_file(path);
+    }
+    let target = mods_dir.join(format!("{}-{}.jar", HUD_MOD_ID, info.version));
+    std::fs::rename(&part, &target).map_err(|e| format!("Move file failed: {}", e))?;
+
+    Ok(HudModReport {
+        version: info.version,
+        previous_version,
+        updated: true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::remote_config::RemoteConfig;
+
+    #[test]
+    fn bundled_config_pins_hud_release() {
+        let source = RemoteConfig::bundled()
+            .hud_mod
+            .expect("bundled config has a hudMod");
+        assert!(matches!(source, HudModSource::GithubRelease { .. }));
+        source.validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_release_without_hashes() {
+        let source: HudModSource = serde_json::from_str(
+            r#"{"source":"githubRelease","repo":"INIRU/Tinklepaw","version":"1.0.0"}"#,
+        )
+        .unwrap();
+        assert!(source.validate().is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::download;
+
+/// Id of the server instance that lives directly in the game directory.
+pub const MAIN_INSTANCE_ID: &str = "main";
+
+/// What `install` put in a game directory; `launch` starts exactly this.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Instance {
+    /// Display name; the server name for the main instance, the pack name for imports.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub minecraft_version: String,
+    pub loader: Option<InstalledLoader>,
+    /// Loader version the player chose; installs keep it instead of following the latest stable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_loader: Option<String>,
+    /// `pack.toml` URL of a packwiz pack this instance is synced with before every launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packwiz_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledLoader {
+    pub kind: String,
+    pub version: String,
+    /// Profile id under `versions/`, e.g. `fabric-loader-0.18.4-1.21.11` or `neoforge-21.1.77`.
+    pub profile_id: String,
+}
+
+fn instance_path(game_dir: &Path) -> PathBuf {
+    game_dir.join("instance.json")
+}
+
+impl Instance {
+    pub fn load(game_dir: &Path) -> Option<Self> {
+        let data = std::fs::read(instance_path(game_dir)).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn save(&self, game_dir: &Path) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(instance_path(game_dir), json)
+            .map_err(|e| format!("Write instance.json failed: {}", e))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSummary {
+    pub id: String,
+    pub name: String,
+    /// Pass as `gameDir` to `launch_minecraft` to start this instance.
+    pub game_dir: String,
+    pub minecraft_version: String,
+    pub loader: Option<InstalledLoader>,
+}
+
+/// Extra instances (imported packs) each keep their own mods, config and saves here, while
+/// versions, libraries and assets stay shared in the game directory.
+pub fn instances_dir() -> PathBuf {
+    download::get_game_dir().join("instances")
+}
+
+fn summary(id: &str, game_dir: &Path, instance: Instance) -> InstanceSummary {
+    InstanceSummary {
+        id: id.to_string(),
+        name: instance.name.unwrap_or_else(|| id.to_string()),
+        game_dir: game_dir.to_string_lossy().to_string(),
+        minecraft_version: instance.minecraft_version,
+        loader: instance.loader,
+    }
+}
+
+pub fn list() -> Vec<InstanceSummary> {
+    let mut instances = Vec::new();
+    let main_dir = download::get_game_dir();
+    if let Some(main) = Instance::load(&main_dir) {
+        instances.push(summary(MAIN_INSTANCE_ID, &main_dir, main));
+    }
+    let mut extra: Vec<InstanceSummary> = std::fs::read_dir(instances_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let id = entry.file_name().to_string_lossy().to_string();
+            Instance::load(&dir).map(|instance| summary(&id, &dir, instance))
+        })
+        .collect();
+    extra.sort_by_key(|i| i.name.to_lowercase());
+    instances.extend(extra);
+    instances
+}
+
+/// Create an empty directory for a new instance named `name`, returning its id and path.
+pub fn create_dir(name: &str) -> Result<(String, PathBuf), String> {
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() || slug == MAIN_INSTANCE_ID {
+        "instance".to_string()
+    } else {
+        slug
+    };
+
+    let base = instances_dir();
+    let mut id = slug.clone();
+    let mut n = 2;
+    while base.join(&id).exists() {
+        id = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    let dir = base.join(&id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Create instance dir failed: {}", e))?;
+    Ok((id, dir))
+}
+
+/// Id of the instance living in `game_dir`; a directory outside the launcher's is its own id.
+pub fn id_of(game_dir: &Path) -> String {
+    if game_dir == download::get_game_dir() {
+        return MAIN_INSTANCE_ID.to_string();
+    }
+    match (game_dir.parent(), game_dir.file_name()) {
+        (Some(parent), Some(name)) if parent == instances_dir() => {
+            name.to_string_lossy().to_string()
+        }
+        _ => game_dir.to_string_lossy().to_string(),
+    }
+}
+
+/// Game directory of the instance `id`.
+pub fn dir(id: &str) -> Result<PathBuf, String> {
+    if id == MAIN_INSTANCE_ID {
+        return Ok(download::get_game_dir());
+    }
+    let is_plain = Path::new(id).file_name().and_then(|n| n.to_str()) == Some(id);
+    let dir = instances_dir().join(id);
+    if !is_plain || !dir.join("instance.json").exists() {
+        return Err(format!("인스턴스를 찾을 수 없습니다: {}", id));
+    }
+    Ok(dir)
+}
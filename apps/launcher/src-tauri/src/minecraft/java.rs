@@ -1,11 +1,16 @@
 use std::path::PathBuf;
 use std::process::Command;
 
+use super::disk::{self, EstimatePart, InstallError};
+
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// An extracted JDK takes roughly twice its archive, and the archive exists until extraction ends.
+const JDK_EXTRACT_FACTOR: u64 = 3;
+
 /// Detect Java installation. Returns the path to the java binary.
 pub fn detect_java() -> Option<PathBuf> {
     // Check JAVA_HOME first
@@ -105,17 +110,19 @@ pub fn get_java_version(java_path: &PathBuf) -> Option<String> {
     cmd.creation_flags(CREATE_NO_WINDOW);
     let output = cmd.output().ok()?;
 
-    // Java prints version to stderr
+    // Java prints version to stderr, after notes such as "Picked up JAVA_TOOL_OPTIONS: …"
     let stderr = String::from_utf8_lossy(&output.stderr);
-    let first_line = stderr.lines().next()?;
-    Some(first_line.to_string())
+    version_line(&stderr).map(str::to_string)
 }
 
-pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String> {
-    use tauri::Emitter;
+/// The `openjdk version "21.0.2" …` line of `java -version` output.
+fn version_line(stderr: &str) -> Option<&str> {
+    stderr.lines().find(|line| line.contains(" version \""))
+}
 
-    // Determine platform and arch
-    let (os_str, arch_str, ext, inner_dir_suffix) = if cfg!(target_os = "macos") {
+/// Adoptium platform name, architecture, archive extension and the JDK home inside the archive.
+fn platform() -> (&'static str, &'static str, &'static str, &'static str) {
+    if cfg!(target_os = "macos") {
         if cfg!(target_arch = "aarch64") {
             ("mac", "aarch64", "tar.gz", "Contents/Home")
         } else {
@@ -125,62 +132,121 @@ pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String>
         ("windows", "x64", "zip", "")
     } else {
         ("linux", "x64", "tar.gz", "")
-    };
-
-    // Adoptium API to get latest JDK 21
-    let api_url = format!(
-        "https://api.adoptium.net/v3/assets/latest/21/hotspot?os={}&architecture={}&image_type=jdk",
-        os_str, arch_str
-    );
+    }
+}
 
-    // Download destination
+fn managed_java_dir() -> PathBuf {
     let base = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-    let java_dir = base.join("bangul-launcher").join("java");
-    std::fs::create_dir_all(&java_dir).map_err(|e| e.to_string())?;
+    base.join("bangul-launcher").join("java")
+}
 
+/// Java binary previously installed by `install_java_auto`, if any.
+pub fn find_managed_java() -> Option<PathBuf> {
+    let (_, _, _, inner_dir_suffix) = platform();
     let java_bin_name = if cfg!(target_os = "windows") { "java.exe" } else { "java" };
-
-    // Check if already installed
-    if let Ok(entries) = std::fs::read_dir(&java_dir) {
-        for entry in entries.flatten() {
-            let candidate = if inner_dir_suffix.is_empty() {
+    std::fs::read_dir(managed_java_dir())
+        .ok()?
+        .flatten()
+        .map(|entry| {
+            if inner_dir_suffix.is_empty() {
                 entry.path().join("bin").join(java_bin_name)
             } else {
                 entry.path().join(inner_dir_suffix).join("bin").join(java_bin_name)
-            };
-            if candidate.exists() {
-                return Ok(candidate.to_string_lossy().to_string());
             }
-        }
-    }
+        })
+        .find(|candidate| candidate.exists())
+}
 
-    // Emit progress
-    let _ = app.emit("java_install_progress", serde_json::json!({"stage": "Java 21 다운로드 중...", "percent": 0.0}));
+struct JdkPackage {
+    link: String,
+    name: String,
+    size: u64,
+}
 
-    // Fetch metadata
-    let client = reqwest::Client::new();
+/// Latest JDK 21 package for this platform from the Adoptium API.
+async fn fetch_jdk_package() -> Result<JdkPackage, String> {
+    let (os_str, arch_str, _, _) = platform();
+    let api_url = format!(
+        "https://api.adoptium.net/v3/assets/latest/21/hotspot?os={}&architecture={}&image_type=jdk",
+        os_str, arch_str
+    );
+
+    let client = super::http::client();
     let meta: serde_json::Value = client.get(&api_url)
         .send().await.map_err(|e| format!("API request failed: {}", e))?
         .json().await.map_err(|e| format!("API parse failed: {}", e))?;
 
-    let binary = meta.as_array()
+    let package = meta.as_array()
         .and_then(|a| a.first())
         .and_then(|e| e.get("binary"))
+        .and_then(|b| b.get("package"))
         .ok_or("No binary info in response")?;
 
-    let download_url = binary.get("package")
-        .and_then(|p| p.get("link"))
-        .and_then(|l| l.as_str())
-        .ok_or("No download URL")?
-        .to_string();
+    Ok(JdkPackage {
+        link: package.get("link")
+            .and_then(|l| l.as_str())
+            .ok_or("No download URL")?
+            .to_string(),
+        name: package.get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("jdk21.tar.gz")
+            .to_string(),
+        size: package.get("size").and_then(|s| s.as_u64()).unwrap_or(0),
+    })
+}
+
+/// Disk usage of the managed Java install; nothing is needed when a usable Java already exists.
+pub async fn estimate_install(java_available: bool) -> EstimatePart {
+    if java_available || find_managed_java().is_some() {
+        return EstimatePart {
+            name: "Java 21".to_string(),
+            total_bytes: 0,
+            needed_bytes: 0,
+            exact: true,
+        };
+    }
+    match fetch_jdk_package().await {
+        Ok(package) if package.size > 0 => EstimatePart {
+            name: "Java 21".to_string(),
+            total_bytes: package.size * JDK_EXTRACT_FACTOR,
+            needed_bytes: package.size * JDK_EXTRACT_FACTOR,
+            exact: false,
+        },
+        _ => EstimatePart {
+            name: "Java 21".to_string(),
+            total_bytes: 0,
+            needed_bytes: 0,
+            exact: false,
+        },
+    }
+}
+
+pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, InstallError> {
+    use tauri::Emitter;
+
+    let (_, _, ext, inner_dir_suffix) = platform();
+
+    // Download destination
+    let java_dir = managed_java_dir();
+    std::fs::create_dir_all(&java_dir).map_err(|e| e.to_string())?;
+
+    let java_bin_name = if cfg!(target_os = "windows") { "java.exe" } else { "java" };
 
-    let file_name = binary.get("package")
-        .and_then(|p| p.get("name"))
-        .and_then(|n| n.as_str())
-        .unwrap_or("jdk21.tar.gz")
-        .to_string();
+    // Check if already installed
+    if let Some(existing) = find_managed_java() {
+        return Ok(existing.to_string_lossy().to_string());
+    }
 
-    let archive_path = java_dir.join(&file_name);
+    // Emit progress
+    let _ = app.emit("java_install_progress", serde_json::json!({"stage": "Java 21 다운로드 중...", "percent": 0.0}));
+
+    // Fetch metadata
+    let client = super::http::client();
+    let package = fetch_jdk_package().await?;
+    disk::ensure_free_space(&java_dir, package.size * JDK_EXTRACT_FACTOR)?;
+
+    let download_url = package.link;
+    let archive_path = java_dir.join(&package.name);
 
     // Download the archive
     let mut response = client.get(&download_url)
@@ -191,6 +257,7 @@ pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String>
     let mut file_data = Vec::new();
 
     while let Some(chunk) = response.chunk().await.map_err(|e| format!("Chunk error: {}", e))? {
+        super::http::throttle(chunk.len()).await;
         file_data.extend_from_slice(&chunk);
         downloaded += chunk.len() as u64;
         if total > 0 {
@@ -212,7 +279,7 @@ pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String>
         let output = cmd.output()
             .map_err(|e| format!("Extract failed: {}", e))?;
         if !output.status.success() {
-            return Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr)));
+            return Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr)).into());
         }
     } else if ext == "zip" {
         let mut cmd = std::process::Command::new("powershell");
@@ -229,7 +296,7 @@ pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String>
         let output = cmd.output()
             .map_err(|e| format!("Extract failed: {}", e))?;
         if !output.status.success() {
-            return Err(format!("Expand-Archive failed: {}", String::from_utf8_lossy(&output.stderr)));
+            return Err(format!("Expand-Archive failed: {}", String::from_utf8_lossy(&output.stderr)).into());
         }
     }
 
@@ -256,5 +323,26 @@ pub async fn install_java_auto(app: &tauri::AppHandle) -> Result<String, String>
         }
     }
 
-    Err("Java binary not found after extraction".to_string())
+    Err("Java binary not found after extraction".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_version_line_after_notes() {
+        let stderr = "Picked up JAVA_TOOL_OPTIONS: -Dfile.encoding=UTF-8\n\
+            openjdk version \"21.0.2\" 2024-01-16 LTS\n\
+            OpenJDK Runtime Environment Temurin-21.0.2+13 (build 21.0.2+13-LTS)\n";
+        assert_eq!(
+            version_line(stderr),
+            Some("openjdk version \"21.0.2\" 2024-01-16 LTS")
+        );
+        assert_eq!(
+            version_line("java version \"1.8.0_392\"\n"),
+            Some("java version \"1.8.0_392\"")
+        );
+        assert_eq!(version_line("Error: could not find java.dll\n"), None);
+    }
 }
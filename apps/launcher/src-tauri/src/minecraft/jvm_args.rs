@@ -0,0 +1,486 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::java;
+use super::redact;
+
+/// Kept apart from `instance.json`, which installs rewrite.
+const LAUNCH_OPTIONS_FILE: &str = "launch-options.json";
+
+pub const DEFAULT_PRESET: &str = "default";
+
+/// Extra arguments of one instance, as the player typed them.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    /// Id of a `PRESETS` entry; `None` is the default preset.
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Shell-style, e.g. `-XX:+UseLargePages "-Dfoo=a b"`.
+    #[serde(default)]
+    pub jvm_args: String,
+    /// Shell-style, e.g. `--width 1920 --height 1080`.
+    #[serde(default)]
+    pub game_args: String,
+}
+
+fn options_path(game_dir: &Path) -> PathBuf {
+    game_dir.join(LAUNCH_OPTIONS_FILE)
+}
+
+impl LaunchOptions {
+    pub fn load(game_dir: &Path) -> Self {
+        std::fs::read(options_path(game_dir))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, game_dir: &Path) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(options_path(game_dir), json)
+            .map_err(|e| format!("Write {} failed: {}", LAUNCH_OPTIONS_FILE, e))
+    }
+
+    fn preset_id(&self) -> &str {
+        self.preset
+            .as_deref()
+            .filter(|p| !p.is_empty())
+            .unwrap_or(DEFAULT_PRESET)
+    }
+
+    /// Nothing beyond what the launcher passes anyway.
+    pub fn is_empty(&self) -> bool {
+        self.preset_id() == DEFAULT_PRESET
+            && self.jvm_args.trim().is_empty()
+            && self.game_args.trim().is_empty()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub min_java: u32,
+    pub args: &'static [&'static str],
+}
+
+pub const PRESETS: &[JvmPreset] = &[
+    JvmPreset {
+        id: DEFAULT_PRESET,
+        name: "기본",
+        description: "Java 기본 설정으로 실행합니다.",
+        min_java: 8,
+        args: &[],
+    },
+    // Aikar's flags without -XX:+AlwaysPreTouch, which delays a client's start by seconds
+    JvmPreset {
+        id: "aikar",
+        name: "Aikar G1",
+        description: "G1 GC를 짧은 일시정지에 맞춰 조정합니다. 모드가 많은 인스턴스에 적합합니다.",
+        min_java: 8,
+        args: &[
+            "-XX:+UseG1GC",
+            "-XX:+ParallelRefProcEnabled",
+            "-XX:MaxGCPauseMillis=200",
+            "-XX:+UnlockExperimentalVMOptions",
+            "-XX:+DisableExplicitGC",
+            "-XX:G1NewSizePercent=30",
+            "-XX:G1MaxNewSizePercent=40",
+            "-XX:G1HeapRegionSize=8M",
+            "-XX:G1ReservePercent=20",
+            "-XX:G1HeapWastePercent=5",
+            "-XX:G1MixedGCCountTarget=4",
+            "-XX:InitiatingHeapOccupancyPercent=15",
+            "-XX:G1MixedGCLiveThresholdPercent=90",
+            "-XX:SurvivorRatio=32",
+            "-XX:+PerfDisableSharedMem",
+            "-XX:MaxTenuringThreshold=1",
+        ],
+    },
+    JvmPreset {
+        id: "zgc",
+        name: "Generational ZGC",
+        description: "일시정지가 거의 없는 ZGC를 사용합니다. 메모리가 넉넉할 때 적합합니다.",
+        min_java: 21,
+        args: &["-XX:+UseZGC", "-XX:+ZGenerational"],
+    },
+];
+
+/// JVM options only some Java versions accept; others refuse to start with them.
+struct FlagRule {
+    prefix: &'static str,
+    min_java: Option<u32>,
+    /// Last version that still starts with the flag.
+    max_java: Option<u32>,
+}
+
+const FLAG_RULES: &[FlagRule] = &[
+    FlagRule {
+        prefix: "-XX:+ZGenerational",
+        min_java: Some(21),
+        // Deprecated in 23, where ZGC became generational by default, and obsolete after
+        max_java: Some(22),
+    },
+    FlagRule {
+        prefix: "-XX:+UseZGC",
+        min_java: Some(15),
+        max_java: None,
+    },
+    FlagRule {
+        prefix: "-XX:+UseShenandoahGC",
+        min_java: Some(12),
+        max_java: None,
+    },
+    FlagRule {
+        prefix: "--enable-native-access",
+        min_java: Some(17),
+        max_java: None,
+    },
+    FlagRule {
+        prefix: "-XX:+UseParNewGC",
+        min_java: None,
+        max_java: Some(9),
+    },
+];
+
+/// Set by the launcher from natives and classpath; the heap comes from the memory setting.
+const MANAGED_JVM_ARGS: &[&str] = &["-cp", "-classpath", "--class-path", "-Djava.library.path"];
+/// Set by the launcher from the account and install.
+const MANAGED_GAME_ARGS: &[&str] = &[
+    "--username",
+    "--uuid",
+    "--accessToken",
+    "--userType",
+    "--version",
+    "--gameDir",
+    "--assetsDir",
+    "--assetIndex",
+];
+
+/// Arguments to add to the command line, after validation.
+#[derive(Default)]
+pub struct ResolvedArgs {
+    pub jvm: Vec<String>,
+    pub game: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Major version from the version line of `java -version`: `"1.8.0_392"` is 8, `"21.0.2"` 21.
+pub fn java_major(version_line: &str) -> Option<u32> {
+    let version = version_line.split('"').nth(1)?;
+    let mut parts = version.split(['.', '_', '-', '+']);
+    let first: u32 = parts.next()?.parse().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
+
+fn split(text: &str, what: &str) -> Result<Vec<String>, String> {
+    shlex::split(text).ok_or_else(|| {
+        format!(
+            "{}를 해석할 수 없습니다. 따옴표가 닫혔는지 확인해주세요.",
+            what
+        )
+    })
+}
+
+/// Option name of `arg`, without `=value`.
+fn option_name(arg: &str) -> &str {
+    arg.split('=').next().unwrap_or(arg)
+}
+
+fn check_java(arg: &str, java: u32) -> Result<(), String> {
+    for rule in FLAG_RULES {
+        if !arg.starts_with(rule.prefix) {
+            continue;
+        }
+        if let Some(min) = rule.min_java.filter(|min| java < *min) {
+            return Err(format!(
+                "{}는 Java {} 이상이 필요합니다 (선택한 Java: {})",
+                rule.prefix, min, java
+            ));
+        }
+        if let Some(max) = rule.max_java.filter(|max| java > *max) {
+            return Err(format!(
+                "{}는 Java {}부터 지원되지 않습니다 (선택한 Java: {})",
+                rule.prefix,
+                max + 1,
+                java
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The preset's and the player's arguments for a game run by Java `java` (`None` when its
+/// version is unknown), or why the game would not start with them.
+pub fn resolve(options: &LaunchOptions, java: Option<u32>) -> Result<ResolvedArgs, String> {
+    let mut resolved = ResolvedArgs::default();
+    let preset = PRESETS
+        .iter()
+        .find(|p| p.id == options.preset_id())
+        .ok_or_else(|| format!("알 수 없는 JVM 프리셋입니다: {}", options.preset_id()))?;
+    if let Some(java) = java.filter(|java| *java < preset.min_java) {
+        return Err(format!(
+            "{} 프리셋은 Java {} 이상이 필요합니다 (선택한 Java: {})",
+            preset.name, preset.min_java, java
+        ));
+    }
+
+    let custom_jvm = split(&options.jvm_args, "JVM 인수")?;
+    for arg in &custom_jvm {
+        if !arg.starts_with('-') {
+            return Err(format!("JVM 인수는 -로 시작해야 합니다: {}", arg));
+        }
+        if arg.starts_with("-Xmx") || arg.starts_with("-Xms") {
+            return Err("힙 크기는 메모리 할당 설정을 사용해주세요".to_string());
+        }
+        if MANAGED_JVM_ARGS
+            .iter()
+            .any(|managed| arg == managed || arg.starts_with(&format!("{}=", managed)))
+        {
+            return Err(format!("{}는 런처가 설정합니다", option_name(arg)));
+        }
+    }
+    // Preset flags a newer Java no longer needs are left out rather than refused
+    resolved.jvm = preset
+        .args
+        .iter()
+        .filter(|a| java.is_none_or(|java| check_java(a, java).is_ok()))
+        .map(|a| a.to_string())
+        .collect();
+    resolved.jvm.extend(custom_jvm);
+
+    match java {
+        Some(java) => {
+            for arg in &resolved.jvm {
+                check_java(arg, java)?;
+            }
+        }
+        None if !options.is_empty() => resolved
+            .warnings
+            .push("Java 버전을 확인할 수 없어 JVM 인수 호환성을 검사하지 않았습니다".to_string()),
+        None => {}
+    }
+    let collectors: Vec<&str> = resolved
+        .jvm
+        .iter()
+        .filter(|a| a.starts_with("-XX:+Use") && a.ends_with("GC"))
+        .map(|a| a.as_str())
+        .collect();
+    if collectors.len() > 1 {
+        return Err(format!(
+            "가비지 컬렉터는 하나만 지정할 수 있습니다: {}",
+            collectors.join(", ")
+        ));
+    }
+
+    resolved.game = split(&options.game_args, "게임 인수")?;
+    if let Some(arg) = resolved
+        .game
+        .iter()
+        .find(|arg| MANAGED_GAME_ARGS.contains(&option_name(arg)))
+    {
+        return Err(format!("{}는 런처가 설정합니다", option_name(arg)));
+    }
+    Ok(resolved)
+}
+
+/// Major version of the Java at `java_path`; `None` when it does not run or says something
+/// unexpected. Blocks until the JVM has started and exited.
+pub fn detect_java_major(java_path: &Path) -> Option<u32> {
+    java::get_java_version(&java_path.to_path_buf())
+        .as_deref()
+        .and_then(java_major)
+}
+
+/// `resolve` for the Java at `java_path`, which is only asked for its version when there is
+/// something to check. Blocks like `detect_java_major`.
+pub fn resolve_for_java(options: &LaunchOptions, java_path: &Path) -> Result<ResolvedArgs, String> {
+    let java = if options.is_empty() {
+        None
+    } else {
+        detect_java_major(java_path)
+    };
+    resolve(options, java)
+}
+
+/// `args` as one shell-quoted line with secrets masked, for showing to the player.
+pub fn preview_line(program: &Path, args: &[String]) -> String {
+    let mut masked: Vec<String> = Vec::with_capacity(args.len() + 1);
+    masked.push(program.to_string_lossy().to_string());
+    let mut hide_next = false;
+    for arg in args {
+        masked.push(if hide_next {
+            "[REDACTED]".to_string()
+        } else {
+            arg.clone()
+        });
+        hide_next = arg == "--accessToken";
+    }
+    let line = masked
+        .iter()
+        .map(|arg| {
+            shlex::try_quote(arg)
+                .map(|q| q.into_owned())
+                .unwrap_or_else(|_| arg.clone())
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    redact::redact(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(preset: Option<&str>, jvm_args: &str, game_args: &str) -> LaunchOptions {
+        LaunchOptions {
+            preset: preset.map(str::to_string),
+            jvm_args: jvm_args.to_string(),
+            game_args: game_args.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_java_major() {
+        assert_eq!(java_major("java version \"1.8.0_392\""), Some(8));
+        assert_eq!(
+            java_major("openjdk version \"17.0.9\" 2023-10-17"),
+            Some(17)
+        );
+        assert_eq!(
+            java_major("openjdk version \"21.0.2\" 2024-01-16 LTS"),
+            Some(21)
+        );
+        assert_eq!(java_major("openjdk version \"25\" 2025-09-16"), Some(25));
+        assert_eq!(java_major("openjdk version \"22-ea\" 2024-03-19"), Some(22));
+        assert_eq!(java_major("Picked up JAVA_TOOL_OPTIONS: -Xss4M"), None);
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        let resolved = resolve(
+            &options(
+                Some("aikar"),
+                "-XX:+UseLargePages \"-Dfoo=a b\"",
+                "--width 1920 --height 1080",
+            ),
+            Some(17),
+        )
+        .unwrap();
+        assert_eq!(
+            resolved.jvm.first().map(String::as_str),
+            Some("-XX:+UseG1GC")
+        );
+        assert!(resolved
+            .jvm
+            .ends_with(&["-XX:+UseLargePages".to_string(), "-Dfoo=a b".to_string()]));
+        assert_eq!(resolved.game, ["--width", "1920", "--height", "1080"]);
+        assert!(resolved.warnings.is_empty());
+
+        let error = resolve(&options(None, "\"-Dfoo=a b", ""), None)
+            .err()
+            .unwrap();
+        assert!(error.contains("JVM 인수"));
+    }
+
+    #[test]
+    fn rejects_launcher_managed_arguments() {
+        for jvm_args in [
+            "-Xmx8G",
+            "-Xms2G",
+            "-cp mods.jar",
+            "-Djava.library.path=/tmp",
+        ] {
+            assert!(
+                resolve(&options(None, jvm_args, ""), None).is_err(),
+                "{}",
+                jvm_args
+            );
+        }
+        assert_eq!(
+            resolve(&options(None, "-Djava.library.path=/tmp", ""), None).err(),
+            Some("-Djava.library.path는 런처가 설정합니다".to_string())
+        );
+        assert_eq!(
+            resolve(&options(None, "", "--gameDir=/tmp"), None).err(),
+            Some("--gameDir는 런처가 설정합니다".to_string())
+        );
+        assert!(resolve(&options(None, "", "--accessToken abc"), None).is_err());
+        assert!(resolve(&options(None, "nogui", ""), None).is_err());
+        // Other properties that merely start like a managed one are fine
+        assert!(resolve(
+            &options(None, "-cpfoo -Djava.library.path.extra=1", ""),
+            None
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_second_garbage_collector() {
+        let error = resolve(&options(Some("aikar"), "-XX:+UseZGC", ""), Some(21))
+            .err()
+            .unwrap();
+        assert!(error.contains("-XX:+UseG1GC, -XX:+UseZGC"));
+        assert!(resolve(&options(None, "-XX:+UseShenandoahGC", ""), Some(21)).is_ok());
+    }
+
+    #[test]
+    fn checks_flags_against_java() {
+        assert!(resolve(&options(Some("zgc"), "", ""), Some(17))
+            .err()
+            .unwrap()
+            .contains("Java 21"));
+        assert!(resolve(&options(Some("zgc"), "", ""), Some(21)).is_ok());
+        assert!(resolve(&options(None, "-XX:+UseParNewGC", ""), Some(17)).is_err());
+        assert!(resolve(&options(Some("unknown"), "", ""), None).is_err());
+
+        let unchecked = resolve(&options(None, "-XX:+UseZGC", ""), None).unwrap();
+        assert_eq!(unchecked.warnings.len(), 1);
+        assert!(resolve(&options(None, "", ""), None)
+            .unwrap()
+            .warnings
+            .is_empty());
+    }
+
+    #[test]
+    fn zgc_preset_drops_generational_flag_on_new_java() {
+        let java21 = resolve(&options(Some("zgc"), "", ""), Some(21)).unwrap();
+        assert_eq!(java21.jvm, ["-XX:+UseZGC", "-XX:+ZGenerational"]);
+
+        let java25 = resolve(&options(Some("zgc"), "", ""), Some(25)).unwrap();
+        assert_eq!(java25.jvm, ["-XX:+UseZGC"]);
+        assert!(java25.warnings.is_empty());
+
+        // Typed by the player it is still refused
+        assert!(resolve(&options(None, "-XX:+ZGenerational", ""), Some(25))
+            .err()
+            .unwrap()
+            .contains("Java 23"));
+    }
+
+    #[test]
+    fn preview_masks_access_token() {
+        let args: Vec<String> = [
+            "-Dfoo=a b",
+            "--username",
+            "Nyaru",
+            "--accessToken",
+            "secret",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+        let line = preview_line(Path::new("/usr/bin/java"), &args);
+        assert_eq!(
+            line,
+            "/usr/bin/java '-Dfoo=a b' --username Nyaru --accessToken [REDACTED]"
+        );
+    }
+}
@@ -1,98 +1,351 @@
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Command;
+use tokio::task::JoinHandle;
 
+use super::crash;
 use super::download;
-
-const TARGET_VERSION: &str = "1.21.11";
+use super::game_log::{Log4jParser, LogEvent, SessionOutput};
+use super::instance::{self, Instance};
+use super::jvm_args::{self, ResolvedArgs};
+use super::memory::{self, HeapSize};
+use super::mod_validate;
+use super::packwiz;
+use super::process;
+use super::redact;
 
 pub struct LaunchConfig {
+    /// Reserved with `GameRegistry::begin_launch`; tags every game event.
+    pub session_id: String,
     pub java_path: PathBuf,
     pub max_memory_mb: u32,
     pub game_dir: PathBuf,
     pub server_host: Option<String>,
     pub server_port: Option<u16>,
+    /// Multiplayer list entries as (name, address), written to `servers.dat`.
+    pub servers: Vec<(String, String)>,
     pub player_name: String,
     pub player_uuid: String,
     pub access_token: String,
+    /// Start even when the mod check finds problems Fabric would refuse.
+    pub ignore_mod_errors: bool,
 }
 
 pub async fn launch(app: &AppHandle, config: LaunchConfig) -> Result<(), String> {
-    let classpath = download::get_classpath()?;
-    let asset_index = download::get_asset_index_id()?;
-    let assets_dir = config.game_dir.join("assets");
-    let natives_dir = config.game_dir.join("natives").join(TARGET_VERSION);
+    let session_id = config.session_id.as_str();
+    // Minecraft echoes its arguments in some errors; the token must never reach a log
+    redact::register_secret(&config.access_token);
+    // The session log is only started right before spawning, so a launch that fails its checks
+    // never rotates an older log out; messages until then are replayed into it
+    let early = Mutex::new(Vec::new());
+    let log = |message: &str| {
+        let event = process::launcher_event(message);
+        process::emit_event(app, session_id, &event);
+        if let Ok(mut early) = early.lock() {
+            early.push(event);
+        }
+    };
+    let mut instance = load_instance(&config.game_dir)?;
+
+    // A packwiz instance follows its pack; when offline the files from the last sync are used
+    if instance.packwiz_url.is_some() {
+        log("[런처] packwiz 모드팩 동기화 중...");
+        match packwiz::sync_instance(app, &config.game_dir).await {
+            Ok(report) => {
+                let files = &report.files;
+                log(&format!(
+                    "[런처] {} 동기화: 추가 {}, 업데이트 {}, 삭제 {}",
+                    report.pack_name,
+                    files.added.len(),
+                    files.updated.len(),
+                    files.removed.len()
+                ));
+                for failure in &files.failed {
+                    log(&format!(
+                        "[런처] packwiz 경고: {}: {}",
+                        failure.id, failure.error
+                    ));
+                }
+            }
+            Err(e) => log(&format!("[런처] packwiz 동기화 실패: {}", e)),
+        }
+        instance = Instance::load(&config.game_dir).unwrap_or(instance);
+    }
+
+    // Fabric aborts on these at startup with little more than a log line; catch them first.
+    // Forge and NeoForge mods carry no fabric.mod.json, so there is nothing to check there
+    let fabric_like = instance
+        .loader
+        .as_ref()
+        .is_some_and(|l| l.kind == "fabric" || l.kind == "quilt");
+    let launch_options = jvm_args::LaunchOptions::load(&config.game_dir);
+    // `java -version` takes as long as a JVM start, so it only runs when something needs it
+    let java_major = if fabric_like || !launch_options.is_empty() {
+        let java_path = config.java_path.clone();
+        tokio::task::spawn_blocking(move || jvm_args::detect_java_major(&java_path))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))?
+    } else {
+        None
+    };
+    if fabric_like {
+        let (mods_dir, snapshot) = (config.game_dir.join("mods"), instance.clone());
+        let validation = tokio::task::spawn_blocking(move || {
+            mod_validate::validate_dir(&mods_dir, &snapshot, java_major)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+        for issue in validation.warnings.iter().chain(validation.errors.iter()) {
+            log(&format!(
+                "[런처] 모드 경고: {}: {}",
+                issue.filename, issue.message
+            ));
+        }
+        if !validation.errors.is_empty() && !config.ignore_mod_errors {
+            return Err(format!(
+                "모드 문제로 실행할 수 없습니다:\n{}",
+                validation.summary()
+            ));
+        }
+    }
+    let (max_memory_mb, game_dir) = (config.max_memory_mb, config.game_dir.clone());
+    let heap = tokio::task::spawn_blocking(move || memory::tune(max_memory_mb, &game_dir))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    for warning in &heap.warnings {
+        log(&format!("[런처] 메모리 경고: {}", warning));
+    }
+    let extra = jvm_args::resolve(&launch_options, java_major)?;
+    for warning in &extra.warnings {
+        log(&format!("[런처] JVM 인수 경고: {}", warning));
+    }
+    let args = build_args(&config, &instance, &heap, &extra)?;
+
+    // Write servers.dat so servers appear in multiplayer list
+    if !config.servers.is_empty() {
+        let _ = write_servers_dat(&config.game_dir, &config.servers);
+    }
+    log("[런처] 마인크래프트 시작 중...");
+    log(&format!(
+        "[런처] Java: {}",
+        config.java_path.to_string_lossy()
+    ));
+
+    let mut session_log = SessionOutput::create(session_id, &instance::id_of(&config.game_dir));
+    for event in early.into_inner().unwrap_or_default() {
+        session_log.record(&event);
+    }
+    let output = Arc::new(Mutex::new(session_log));
+
+    let spawned_at = SystemTime::now();
+    let mut child = Command::new(&config.java_path)
+        .args(&args)
+        .current_dir(&config.game_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to launch Minecraft: {}", e))?;
+
+    // Without a PID the game could never be stopped, and 0 would signal the launcher itself
+    let Some(pid) = child.id() else {
+        let _ = child.kill().await;
+        return Err("마인크래프트 프로세스 ID를 확인할 수 없습니다".to_string());
+    };
+    app.state::<crate::AppState>()
+        .games
+        .started(session_id, pid);
+    process::emit_started(app, session_id, pid);
+
+    // Stream stdout and stderr into events, the session log and the crash analysis tail
+    let streams: Vec<JoinHandle<()>> = [
+        child
+            .stdout
+            .take()
+            .map(|out| stream_lines(app, session_id, out, "INFO", &output)),
+        child
+            .stderr
+            .take()
+            .map(|err| stream_lines(app, session_id, err, "WARN", &output)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // Wait for process to exit
+    let app_clone = app.clone();
+    let session = session_id.to_string();
+    let game_dir = config.game_dir.clone();
+    tokio::spawn(async move {
+        let status = child.wait().await;
+        // Let the streams drain so the log and the analysis see the last lines
+        for stream in streams {
+            let _ = stream.await;
+        }
+        let log = |message: &str| {
+            record(
+                &app_clone,
+                &session,
+                &output,
+                &process::launcher_event(message),
+            )
+        };
+        let code = match status {
+            Ok(status) => {
+                log(&format!(
+                    "[런처] 마인크래프트 종료 (코드: {:?})",
+                    status.code()
+                ));
+                status.code().unwrap_or(-1)
+            }
+            Err(e) => {
+                log(&format!("[런처] 오류: {}", e));
+                -1
+            }
+        };
+
+        // Drop it from the registry, PID file and directory lock before anyone hears of it
+        let game = app_clone.state::<crate::AppState>().games.remove(&session);
+        let stopped = game.is_some_and(|g| g.stopping);
+        if code != 0 && !stopped {
+            let tail = output.lock().map(|o| o.tail()).unwrap_or_default();
+            let report = crash::analyze(&session, &game_dir, spawned_at, code, &tail);
+            for cause in &report.causes {
+                log(&format!("[런처] 크래시 원인: {}", cause.message));
+            }
+            let _ = app_clone.emit("game-crashed", report);
+        }
+        process::emit_exited(&app_clone, &session, code);
+    });
+
+    Ok(())
+}
+
+/// Command line `launch` would run, with secrets masked, and the warnings it would log.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPreview {
+    pub command: String,
+    pub warnings: Vec<String>,
+}
+
+/// Resolve everything `launch` would for `config` without syncing, checking mods or spawning.
+pub fn preview(config: &LaunchConfig) -> Result<LaunchPreview, String> {
+    let instance = load_instance(&config.game_dir)?;
+    let heap = memory::tune(config.max_memory_mb, &config.game_dir);
+    let extra = jvm_args::resolve_for_java(
+        &jvm_args::LaunchOptions::load(&config.game_dir),
+        &config.java_path,
+    )?;
+    let args = build_args(config, &instance, &heap, &extra)?;
+    Ok(LaunchPreview {
+        command: jvm_args::preview_line(&config.java_path, &args),
+        warnings: heap.warnings.into_iter().chain(extra.warnings).collect(),
+    })
+}
+
+fn load_instance(game_dir: &Path) -> Result<Instance, String> {
+    // Imported instances carry their own instance.json; the main one lives in the root
+    Instance::load(game_dir)
+        .or_else(|| Instance::load(&download::get_game_dir()))
+        .ok_or_else(|| "설치 정보가 없습니다. 다시 설치해주세요.".to_string())
+}
+
+/// Java arguments that start `instance` as `config` describes.
+fn build_args(
+    config: &LaunchConfig,
+    instance: &Instance,
+    heap: &HeapSize,
+    extra: &ResolvedArgs,
+) -> Result<Vec<String>, String> {
+    let root_dir = download::get_game_dir();
+    let version = instance.minecraft_version.as_str();
+    let classpath = download::get_classpath(version)?;
+    let asset_index = download::get_asset_index_id(version)?;
+    let assets_dir = root_dir.join("assets");
+    let natives_dir = root_dir.join("natives").join(version);
     std::fs::create_dir_all(&natives_dir).map_err(|e| e.to_string())?;
 
     let mut args: Vec<String> = Vec::new();
 
     // JVM args
-    args.push(format!("-Xmx{}m", config.max_memory_mb));
-    args.push(format!("-Xms{}m", config.max_memory_mb / 2));
+    args.push(format!("-Xmx{}m", heap.max_mb));
+    args.push(format!("-Xms{}m", heap.initial_mb));
     args.push(format!(
         "-Djava.library.path={}",
         natives_dir.to_string_lossy()
     ));
     args.push("-Dminecraft.launcher.brand=nyaru-launcher".to_string());
-    args.push("-Dminecraft.launcher.version=0.1.1".to_string());
+    args.push(format!(
+        "-Dminecraft.launcher.version={}",
+        env!("CARGO_PKG_VERSION")
+    ));
 
     // macOS specific
     if cfg!(target_os = "macos") {
         args.push("-XstartOnFirstThread".to_string());
     }
 
-    // Fabric support: check for fabric-version.txt and build combined classpath + main class
-    let fabric_version_path = config.game_dir.join("fabric-version.txt");
-    let (final_classpath, main_class) = if fabric_version_path.exists() {
-        match std::fs::read_to_string(&fabric_version_path) {
-            Ok(fabric_id) => {
-                let fabric_id = fabric_id.trim().to_string();
-                let profile_path = config.game_dir
-                    .join("versions")
-                    .join(&fabric_id)
-                    .join(format!("{}.json", fabric_id));
-                match std::fs::read_to_string(&profile_path) {
-                    Ok(profile_json) => {
-                        match serde_json::from_str::<serde_json::Value>(&profile_json) {
-                            Ok(profile) => {
-                                let mc = profile["mainClass"]
-                                    .as_str()
-                                    .unwrap_or("net.minecraft.client.main.Main")
-                                    .to_string();
-                                let sep = if cfg!(windows) { ";" } else { ":" };
-                                let lib_dir = config.game_dir.join("libraries");
-                                let mut fabric_paths: Vec<String> = Vec::new();
-                                if let Some(libs) = profile["libraries"].as_array() {
-                                    for lib in libs {
-                                        if let Some(name) = lib["name"].as_str() {
-                                            let rel = download::maven_name_to_path(name);
-                                            fabric_paths.push(
-                                                lib_dir.join(&rel).to_string_lossy().to_string()
-                                            );
-                                        }
-                                    }
-                                }
-                                let combined = if fabric_paths.is_empty() {
-                                    classpath
-                                } else {
-                                    format!("{}{}{}", fabric_paths.join(sep), sep, classpath)
-                                };
-                                (combined, mc)
-                            }
-                            Err(_) => (classpath, "net.minecraft.client.main.Main".to_string()),
-                        }
-                    }
-                    Err(_) => (classpath, "net.minecraft.client.main.Main".to_string()),
+    // Loader support: merge the installed loader profile's libraries, main class and arguments
+    let (final_classpath, main_class, loader_args) = match instance.loader {
+        Some(ref loader) => {
+            let profile_path = root_dir
+                .join("versions")
+                .join(&loader.profile_id)
+                .join(format!("{}.json", loader.profile_id));
+            match std::fs::read_to_string(&profile_path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            {
+                Some(profile) => {
+                    let mc = profile["mainClass"]
+                        .as_str()
+                        .unwrap_or("net.minecraft.client.main.Main")
+                        .to_string();
+                    let sep = if cfg!(windows) { ";" } else { ":" };
+                    let lib_dir = root_dir.join("libraries");
+                    let loader_paths: Vec<String> = download::profile_library_paths(&profile)
+                        .iter()
+                        .map(|rel| lib_dir.join(rel).to_string_lossy().to_string())
+                        .collect();
+                    let combined = if loader_paths.is_empty() {
+                        classpath
+                    } else {
+                        format!("{}{}{}", loader_paths.join(sep), sep, classpath)
+                    };
+                    let args = LoaderArgs {
+                        jvm: profile_arguments(&profile, "jvm", &lib_dir, &loader.profile_id),
+                        game: profile_arguments(&profile, "game", &lib_dir, &loader.profile_id),
+                    };
+                    (combined, mc, args)
                 }
+                None => (
+                    classpath,
+                    "net.minecraft.client.main.Main".to_string(),
+                    LoaderArgs::default(),
+                ),
             }
-            Err(_) => (classpath, "net.minecraft.client.main.Main".to_string()),
         }
-    } else {
-        (classpath, "net.minecraft.client.main.Main".to_string())
+        None => (
+            classpath,
+            "net.minecraft.client.main.Main".to_string(),
+            LoaderArgs::default(),
+        ),
     };
 
+    args.extend(loader_args.jvm);
+    // The instance's preset and custom options, after the launcher's so they can override them
+    args.extend(extra.jvm.iter().cloned());
+
+    // The game then prints log4j XML events, parsed back into structured `game-log` payloads
+    if let Some(logging) = download::get_logging_argument(version) {
+        args.push(logging);
+    }
+
     args.push("-cp".to_string());
     args.push(final_classpath);
 
@@ -103,7 +356,7 @@ pub async fn launch(app: &AppHandle, config: LaunchConfig) -> Result<(), String>
     args.push("--username".to_string());
     args.push(config.player_name.clone());
     args.push("--version".to_string());
-    args.push(TARGET_VERSION.to_string());
+    args.push(version.to_string());
     args.push("--gameDir".to_string());
     args.push(config.game_dir.to_string_lossy().to_string());
     args.push("--assetsDir".to_string());
@@ -119,81 +372,82 @@ pub async fn launch(app: &AppHandle, config: LaunchConfig) -> Result<(), String>
     args.push("--versionType".to_string());
     args.push("release".to_string());
 
-    // Write servers.dat so server appears in multiplayer list
+    // Forge and NeoForge pass their launch target and versions here
+    args.extend(loader_args.game);
+    args.extend(extra.game.iter().cloned());
+
     if let Some(ref host) = config.server_host {
         let port = config.server_port.unwrap_or(25565);
-        let _ = write_servers_dat(&config.game_dir, "방울냥 서버", &format!("{}:{}", host, port));
         // quickPlayMultiplayer (MC 1.20+)
         args.push("--quickPlayMultiplayer".to_string());
         args.push(format!("{}:{}", host, port));
     }
 
-    let _ = app.emit("game-log", "[런처] 마인크래프트 시작 중...");
-    let _ = app.emit(
-        "game-log",
-        format!("[런처] Java: {}", config.java_path.to_string_lossy()),
-    );
-
-    let mut child = Command::new(&config.java_path)
-        .args(&args)
-        .current_dir(&config.game_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to launch Minecraft: {}", e))?;
-
-    let _ = app.emit("game-started", true);
-
-    // Stream stdout
-    if let Some(stdout) = child.stdout.take() {
-        let app_clone = app.clone();
-        tokio::spawn(async move {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                let _ = app_clone.emit("game-log", &line);
-            }
-        });
-    }
+    Ok(args)
+}
 
-    // Stream stderr
-    if let Some(stderr) = child.stderr.take() {
-        let app_clone = app.clone();
-        tokio::spawn(async move {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                let _ = app_clone.emit("game-log", format!("[WARN] {}", &line));
-            }
-        });
+/// Write `event` to the session log and send it to the UI.
+fn record(app: &AppHandle, session_id: &str, output: &Mutex<SessionOutput>, event: &LogEvent) {
+    if let Ok(mut output) = output.lock() {
+        output.record(event);
     }
+    process::emit_event(app, session_id, event);
+}
 
-    // Wait for process to exit
-    let app_clone = app.clone();
+/// Parse each line of `output` into log events; `plain_level` is for lines outside log4j.
+fn stream_lines(
+    app: &AppHandle,
+    session_id: &str,
+    stream: impl AsyncRead + Unpin + Send + 'static,
+    plain_level: &'static str,
+    output: &Arc<Mutex<SessionOutput>>,
+) -> JoinHandle<()> {
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    let output = Arc::clone(output);
     tokio::spawn(async move {
-        match child.wait().await {
-            Ok(status) => {
-                let _ = app_clone.emit(
-                    "game-log",
-                    format!("[런처] 마인크래프트 종료 (코드: {:?})", status.code()),
-                );
-                let _ = app_clone.emit("game-exited", status.code().unwrap_or(-1));
-            }
-            Err(e) => {
-                let _ = app_clone.emit("game-log", format!("[런처] 오류: {}", e));
-                let _ = app_clone.emit("game-exited", -1);
+        let mut parser = Log4jParser::default();
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            for event in parser.push(&line, plain_level) {
+                record(&app, &session_id, &output, &event);
             }
         }
-        // Reset running flag so user can relaunch
-        if let Ok(mut running) = app_clone.state::<crate::AppState>().minecraft_running.lock() {
-            *running = false;
+        for event in parser.finish(plain_level) {
+            record(&app, &session_id, &output, &event);
         }
-    });
+    })
+}
 
-    Ok(())
+#[derive(Default)]
+struct LoaderArgs {
+    jvm: Vec<String>,
+    game: Vec<String>,
 }
 
-fn write_servers_dat(game_dir: &PathBuf, server_name: &str, server_addr: &str) -> Result<(), String> {
+/// Plain string entries of a profile's `arguments.jvm`/`arguments.game`; entries with rules
+/// are OS-specific tweaks of the vanilla profile and are skipped.
+fn profile_arguments(
+    profile: &serde_json::Value,
+    kind: &str,
+    lib_dir: &Path,
+    version_name: &str,
+) -> Vec<String> {
+    let sep = if cfg!(windows) { ";" } else { ":" };
+    profile["arguments"][kind]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.as_str())
+        .map(|arg| {
+            arg.replace("${library_directory}", &lib_dir.to_string_lossy())
+                .replace("${classpath_separator}", sep)
+                .replace("${version_name}", version_name)
+        })
+        .collect()
+}
+
+fn write_servers_dat(game_dir: &Path, servers: &[(String, String)]) -> Result<(), String> {
     let servers_dat = game_dir.join("servers.dat");
     let mut nbt: Vec<u8> = Vec::new();
 
@@ -201,40 +455,43 @@ fn write_servers_dat(game_dir: &PathBuf, server_name: &str, server_addr: &str) -
     nbt.push(10u8);
     nbt.extend_from_slice(&0u16.to_be_bytes());
 
-    // TAG_List (id=9), name="servers", element type=TAG_Compound(10), count=1
+    // TAG_List (id=9), name="servers", element type=TAG_Compound(10)
     nbt.push(9u8);
     let key = b"servers";
     nbt.extend_from_slice(&(key.len() as u16).to_be_bytes());
     nbt.extend_from_slice(key);
     nbt.push(10u8); // element type: compound
-    nbt.extend_from_slice(&1i32.to_be_bytes()); // count
-
-    // Server entry: TAG_String "name"
-    nbt.push(8u8);
-    let k = b"name";
-    nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
-    nbt.extend_from_slice(k);
-    let v = server_name.as_bytes();
-    nbt.extend_from_slice(&(v.len() as u16).to_be_bytes());
-    nbt.extend_from_slice(v);
-
-    // TAG_String "ip"
-    nbt.push(8u8);
-    let k = b"ip";
-    nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
-    nbt.extend_from_slice(k);
-    let v = server_addr.as_bytes();
-    nbt.extend_from_slice(&(v.len() as u16).to_be_bytes());
-    nbt.extend_from_slice(v);
-
-    // TAG_Byte "acceptTextures" = 1
-    nbt.push(1u8);
-    let k = b"acceptTextures";
-    nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
-    nbt.extend_from_slice(k);
-    nbt.push(1u8);
-
-    nbt.push(0u8); // End of server compound
+    nbt.extend_from_slice(&(servers.len() as i32).to_be_bytes()); // count
+
+    for (server_name, server_addr) in servers {
+        // Server entry: TAG_String "name"
+        nbt.push(8u8);
+        let k = b"name";
+        nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
+        nbt.extend_from_slice(k);
+        let v = server_name.as_bytes();
+        nbt.extend_from_slice(&(v.len() as u16).to_be_bytes());
+        nbt.extend_from_slice(v);
+
+        // TAG_String "ip"
+        nbt.push(8u8);
+        let k = b"ip";
+        nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
+        nbt.extend_from_slice(k);
+        let v = server_addr.as_bytes();
+        nbt.extend_from_slice(&(v.len() as u16).to_be_bytes());
+        nbt.extend_from_slice(v);
+
+        // TAG_Byte "acceptTextures" = 1
+        nbt.push(1u8);
+        let k = b"acceptTextures";
+        nbt.extend_from_slice(&(k.len() as u16).to_be_bytes());
+        nbt.extend_from_slice(k);
+        nbt.push(1u8);
+
+        nbt.push(0u8); // End of server compound
+    }
+
     nbt.push(0u8); // End of root compound
 
     std::fs::write(&servers_dat, &nbt).map_err(|e| format!("servers.dat write failed: {}", e))
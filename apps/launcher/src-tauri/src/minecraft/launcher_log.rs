@@ -0,0 +1,74 @@
+//! The launcher's own log: install stages, launch messages and warnings, kept next to the game
+//! directory so a diagnostics bundle can include it.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::download;
+use super::game_log::LogEvent;
+use super::redact;
+
+/// Rotated to `launcher.log.1` beyond this size, so at most twice this is kept.
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Serialises writers and remembers the last progress stage written.
+static LAST_STAGE: Mutex<String> = Mutex::new(String::new());
+
+pub fn path() -> PathBuf {
+    download::get_launcher_dir().join("launcher.log")
+}
+
+/// The current log and the rotated one before it, oldest first.
+pub fn files() -> Vec<PathBuf> {
+    let current = path();
+    let previous = current.with_extension("log.1");
+    [previous, current]
+        .into_iter()
+        .filter(|p| p.exists())
+        .collect()
+}
+
+fn append(text: &str) {
+    let path = path();
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+        let _ = std::fs::rename(&path, path.with_extension("log.1"));
+    }
+    let _ = std::fs::create_dir_all(download::get_launcher_dir());
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        let _ = writeln!(file, "{}", text);
+    }
+}
+
+pub fn write(level: &str, message: &str) {
+    let _guard = LAST_STAGE.lock();
+    append(&redact::redact(&LogEvent::plain(level, message).to_text()));
+}
+
+/// Log a progress update only when its stage changes or the stage is terminal, so downloading
+/// thousands of assets adds a few lines rather than one per file.
+pub fn progress(stage: &str, message: &str) {
+    let Ok(mut last) = LAST_STAGE.lock() else {
+        return;
+    };
+    let terminal = matches!(stage, "complete" | "incomplete");
+    if !terminal && *last == stage {
+        return;
+    }
+    *last = stage.to_string();
+    let level = if stage == "incomplete" {
+        "WARN"
+    } else {
+        "INFO"
+    };
+    let text = LogEvent {
+        logger: Some(stage.to_string()),
+        ..LogEvent::plain(level, message)
+    }
+    .to_text();
+    append(&redact::redact(&text));
+}
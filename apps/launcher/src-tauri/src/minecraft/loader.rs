@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::download::{self, emit_progress};
+use super::http;
+use super::instance::{self, Instance};
+use super::mod_meta;
+use super::mod_validate;
+use super::remote_config::LoaderSpec;
+
+#[derive(Deserialize)]
+struct LoaderForGame {
+    loader: LoaderEntry,
+}
+
+#[derive(Deserialize)]
+struct LoaderEntry {
+    version: String,
+    /// Absent in Quilt's meta API, where pre-releases carry a `-beta` style suffix.
+    #[serde(default)]
+    stable: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderVersion {
+    pub version: String,
+    pub stable: bool,
+    pub installed: bool,
+    pub pinned: bool,
+    /// Enabled mods whose loader requirement this version does not meet.
+    pub blocked_by: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderChangeReport {
+    pub previous_version: String,
+    pub version: String,
+    pub pinned: bool,
+    /// Profile removed from `versions/` because no instance uses it anymore.
+    pub removed_profile: Option<String>,
+    pub removed_libraries: usize,
+}
+
+/// Mod id under which the loader itself satisfies dependencies.
+fn loader_mod_id(kind: &str) -> &'static str {
+    if kind == "quilt" {
+        "quilt_loader"
+    } else {
+        "fabricloader"
+    }
+}
+
+/// Name and loader predicates of every enabled mod that constrains the loader version.
+fn loader_requirements(game_dir: &Path, kind: &str) -> Vec<(String, Vec<String>)> {
+    let id = loader_mod_id(kind);
+    mod_meta::scan_dir(&game_dir.join("mods"))
+        .into_iter()
+        .filter(|m| m.enabled)
+        .filter_map(|m| {
+            let metadata = m.metadata?;
+            let predicates = metadata.depends.get(id)?.clone();
+            Some((metadata.name, predicates))
+        })
+        .collect()
+}
+
+/// Loader builds published for the instance's Minecraft version, newest first.
+pub async fn list_versions(game_dir: &Path) -> Result<Vec<LoaderVersion>, String> {
+    let instance = Instance::load(game_dir).ok_or("설치 정보가 없습니다")?;
+    let loader = instance
+        .loader
+        .as_ref()
+        .ok_or("모드 로더가 설치되어 있지 않습니다")?;
+    let name = download::loader_name(&loader.kind);
+    let (meta_url, api) = download::fabric_like_meta(&loader.kind)
+        .ok_or_else(|| format!("{}은(는) 버전 선택을 지원하지 않습니다", name))?;
+
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(false);
+    let entries: Vec<LoaderForGame> = fetcher
+        .fetch_json(
+            &format!("{} 버전 목록", name),
+            client.get(format!(
+                "{}/{}/versions/loader/{}",
+                meta_url, api, instance.minecraft_version
+            )),
+        )
+        .await?;
+
+    let requirements = loader_requirements(game_dir, &loader.kind);
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let version = entry.loader.version;
+            let blocked_by = requirements
+                .iter()
+                .filter(|(_, predicates)| {
+                    !predicates.is_empty()
+                        && !predicates
+                            .iter()
+                            .any(|p| mod_validate::matches(p, &version))
+                })
+                .map(|(name, _)| name.clone())
+                .collect();
+            LoaderVersion {
+                stable: entry.loader.stable.unwrap_or(!version.contains('-')),
+                installed: version == loader.version,
+                pinned: instance.pinned_loader.as_deref() == Some(version.as_str()),
+                blocked_by,
+                version,
+            }
+        })
+        .collect())
+}
+
+/// Install `version` of the instance's loader and pin it, or unpin with `None`.
+///
+/// Versions some enabled mod does not accept are refused unless `force` is set. The previous
+/// loader profile and the libraries only it used are removed once no instance needs them.
+pub async fn set_version(
+    app: &AppHandle,
+    game_dir: &Path,
+    version: Option<String>,
+    force: bool,
+) -> Result<LoaderChangeReport, String> {
+    let mut instance = Instance::load(game_dir).ok_or("설치 정보가 없습니다")?;
+    if instance.packwiz_url.is_some() {
+        return Err("packwiz 인스턴스는 팩이 로더 버전을 정합니다".to_string());
+    }
+    let current = instance
+        .loader
+        .clone()
+        .ok_or("모드 로더가 설치되어 있지 않습니다")?;
+
+    let Some(version) = version else {
+        instance.pinned_loader = None;
+        instance.save(game_dir)?;
+        return Ok(LoaderChangeReport {
+            previous_version: current.version.clone(),
+            version: current.version,
+            pinned: false,
+            removed_profile: None,
+            removed_libraries: 0,
+        });
+    };
+
+    let available = list_versions(game_dir).await?;
+    let target = available
+        .iter()
+        .find(|v| v.version == version)
+        .ok_or_else(|| {
+            format!(
+                "Minecraft {}에서 사용할 수 없는 로더 버전입니다: {}",
+                instance.minecraft_version, version
+            )
+        })?;
+    if !target.blocked_by.is_empty() && !force {
+        return Err(format!(
+            "{} 모드가 로더 {}을(를) 지원하지 않습니다",
+            target.blocked_by.join(", "),
+            version
+        ));
+    }
+
+    let mut report = LoaderChangeReport {
+        previous_version: current.version.clone(),
+        version: version.clone(),
+        pinned: true,
+        removed_profile: None,
+        removed_libraries: 0,
+    };
+    if version != current.version {
+        let client = http::client();
+        let mut fetcher = MetaFetcher::new(false);
+        let spec = LoaderSpec {
+            kind: current.kind.clone(),
+            version: Some(version.clone()),
+        };
+        let installed = download::install_loader(
+            app,
+            &client,
+            &mut fetcher,
+            &download::get_game_dir(),
+            &instance.minecraft_version,
+            &spec,
+        )
+        .await?;
+        instance.loader = Some(installed);
+    }
+    instance.pinned_loader = Some(version);
+    instance.save(game_dir)?;
+
+    if current.profile_id != instance.loader.as_ref().map_or("", |l| &l.profile_id) {
+        let removed = cleanup_profile(&current.profile_id)?;
+        if let Some(libraries) = removed {
+            report.removed_profile = Some(current.profile_id);
+            report.removed_libraries = libraries;
+        }
+    }
+    emit_progress(app, "로더 변경 완료!", "complete", 1, 1, 100.0);
+    Ok(report)
+}
+
+fn read_profile_libraries(versions_dir: &Path, id: &str) -> HashSet<String> {
+    std::fs::read(versions_dir.join(id).join(format!("{}.json", id)))
+        .ok()
+        .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+        .map(|profile| {
+            download::profile_library_paths(&profile)
+                .into_iter()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Remove the profile `profile_id` and the libraries no other profile lists, unless some
+/// instance still launches with it (`None`). Returns the number of libraries removed.
+pub fn cleanup_profile(profile_id: &str) -> Result<Option<usize>, String> {
+    let in_use = instance::list().iter().any(|i| {
+        i.loader
+            .as_ref()
+            .is_some_and(|l| l.profile_id == profile_id)
+    });
+    if in_use {
+        return Ok(None);
+    }
+
+    let root = download::get_game_dir();
+    let versions_dir = root.join("versions");
+    let old_libraries = read_profile_libraries(&versions_dir, profile_id);
+    let mut still_used = HashSet::new();
+    for entry in std::fs::read_dir(&versions_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let id = entry.file_name().to_string_lossy().to_string();
+        if id != profile_id {
+            still_used.extend(read_profile_libraries(&versions_dir, &id));
+        }
+    }
+
+    let lib_dir = root.join("libraries");
+    let removed = old_libraries
+        .difference(&still_used)
+        .filter(|rel| {
+            let path = lib_dir.join(rel);
+            let _ = std::fs::remove_file(format!("{}.sha1", path.to_string_lossy()));
+            std::fs::remove_file(path).is_ok()
+        })
+        .count();
+    std::fs::remove_dir_all(versions_dir.join(profile_id))
+        .map_err(|e| format!("Remove {} failed: {}", profile_id, e))?;
+    Ok(Some(removed))
+}
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use super::game_log;
+use super::http;
+use super::redact;
+use crate::settings;
+
+pub const LOG_UPLOAD_SETTINGS_KEY: &str = "log_upload_settings";
+
+pub const DEFAULT_API_BASE: &str = "https://api.mclo.gs";
+
+/// mclo.gs rejects or cuts logs beyond these; the head (versions, mod list) and the tail
+/// (the crash) are what helpers read, so the middle is dropped instead.
+const MAX_LINES: usize = 25_000;
+const MAX_BYTES: usize = 10 * 1024 * 1024;
+const HEAD_LINES: usize = 5_000;
+
+/// Which mclo.gs-compatible API logs are shared with; any server implementing
+/// `POST /1/log` and `GET /1/insights/{id}` works.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogUploadSettings {
+    /// `None` uses mclo.gs.
+    pub api_base: Option<String>,
+}
+
+impl LogUploadSettings {
+    fn api_base(&self) -> &str {
+        self.api_base
+            .as_deref()
+            .map(|b| b.trim().trim_end_matches('/'))
+            .filter(|b| !b.is_empty())
+            .unwrap_or(DEFAULT_API_BASE)
+    }
+}
+
+#[derive(Deserialize)]
+struct PasteResponse {
+    success: bool,
+    id: Option<String>,
+    url: Option<String>,
+    raw: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InsightsResponse {
+    title: Option<String>,
+    #[serde(default)]
+    analysis: InsightsAnalysis,
+}
+
+#[derive(Deserialize, Default)]
+struct InsightsAnalysis {
+    #[serde(default)]
+    problems: Vec<InsightsProblem>,
+    #[serde(default)]
+    information: Vec<InsightsInformation>,
+}
+
+#[derive(Deserialize)]
+struct InsightsProblem {
+    message: String,
+    #[serde(default)]
+    solutions: Vec<InsightsSolution>,
+}
+
+#[derive(Deserialize)]
+struct InsightsSolution {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct InsightsInformation {
+    label: String,
+    value: serde_json::Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogProblem {
+    pub message: String,
+    pub solutions: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogInformation {
+    pub label: String,
+    pub value: String,
+}
+
+/// What the service detected in the log, e.g. "Minecraft 1.21.1 Fabric Client Log".
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogAnalysis {
+    pub title: Option<String>,
+    pub problems: Vec<LogProblem>,
+    pub information: Vec<LogInformation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedLog {
+    pub id: String,
+    pub url: String,
+    pub raw_url: Option<String>,
+    /// Local file that was uploaded.
+    pub file: String,
+    /// `None` when the service has no analysis for the log or it could not be fetched.
+    pub analysis: Option<LogAnalysis>,
+}
+
+/// Log of `session_id`, or the newest one: the running game's or else the last session's.
+fn find_session_log(session_id: Option<&str>) -> Result<PathBuf, String> {
+    let logs = game_log::list_session_logs();
+    let found = match session_id {
+        Some(session_id) => {
+            let short_id: String = session_id.chars().take(8).collect();
+            let suffix = format!("-{}.log", short_id);
+            logs.into_iter()
+                .find(|p| p.to_string_lossy().ends_with(&suffix))
+        }
+        None => logs.into_iter().next(),
+    };
+    found.ok_or_else(|| "업로드할 게임 로그가 없습니다".to_string())
+}
+
+/// The head and tail of `text` within the service's limits.
+fn fit_limits(text: &str) -> String {
+    fit(text, MAX_LINES, HEAD_LINES, MAX_BYTES)
+}
+
+/// Keep up to `head_lines` lines (and a fifth of `max_bytes`) from the start and fill the rest
+/// from the end, replacing whatever is left in the middle with one note.
+fn fit(text: &str, max_lines: usize, head_lines: usize, max_bytes: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= max_lines && text.len() <= max_bytes {
+        return text.to_string();
+    }
+    // Room for the note, whose line count is not known yet
+    let budget = max_bytes.saturating_sub(128);
+
+    let mut head = 0;
+    let mut head_bytes = 0;
+    while head < lines.len().min(head_lines) && head_bytes + lines[head].len() < budget / 5 {
+        head_bytes += lines[head].len() + 1;
+        head += 1;
+    }
+    let mut tail = 0;
+    let mut tail_bytes = 0;
+    while head + tail < lines.len()
+        && head + tail + 1 < max_lines
+        && head_bytes + tail_bytes + lines[lines.len() - tail - 1].len() < budget
+    {
+        tail_bytes += lines[lines.len() - tail - 1].len() + 1;
+        tail += 1;
+    }
+
+    let skipped = format!(
+        "[런처] 로그가 너무 길어 {}줄을 생략했습니다",
+        lines.len() - head - tail
+    );
+    let mut kept = lines[..head].to_vec();
+    kept.push(&skipped);
+    kept.extend_from_slice(&lines[lines.len() - tail..]);
+    kept.join("\n")
+}
+
+async fn fetch_analysis(client: &reqwest::Client, api_base: &str, id: &str) -> Option<LogAnalysis> {
+    let insights: InsightsResponse = client
+        .get(format!("{}/1/insights/{}", api_base, id))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    Some(LogAnalysis {
+        title: insights.title,
+        problems: insights
+            .analysis
+            .problems
+            .into_iter()
+            .map(|p| LogProblem {
+                message: p.message,
+                solutions: p.solutions.into_iter().map(|s| s.message).collect(),
+            })
+            .collect(),
+        information: insights
+            .analysis
+            .information
+            .into_iter()
+            .map(|i| LogInformation {
+                label: i.label,
+                value: match i.value {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                },
+            })
+            .collect(),
+    })
+}
+
+/// Redact the session log of `session_id` (or the newest one) and share it, returning its URL
+/// and the service's analysis.
+pub async fn upload(app: &AppHandle, session_id: Option<&str>) -> Result<UploadedLog, String> {
+    let path = find_session_log(session_id)?;
+    let data = std::fs::read(&path).map_err(|e| format!("로그 읽기 실패: {}", e))?;
+    let content = fit_limits(&redact::redact(&String::from_utf8_lossy(&data)));
+    if content.trim().is_empty() {
+        return Err("게임 로그가 비어 있습니다".to_string());
+    }
+
+    let settings: LogUploadSettings = settings::load(app, LOG_UPLOAD_SETTINGS_KEY);
+    share(
+        &http::client(),
+        settings.api_base(),
+        &content,
+        path.to_string_lossy().to_string(),
+    )
+    .await
+}
+
+/// Post `content` to the service at `api_base` and fetch its analysis.
+async fn share(
+    client: &reqwest::Client,
+    api_base: &str,
+    content: &str,
+    file: String,
+) -> Result<UploadedLog, String> {
+    // Failures come back as `{"success": false, "error": …}` with an error status
+    let response: PasteResponse = client
+        .post(format!("{}/1/log", api_base))
+        .form(&[("content", content)])
+        .send()
+        .await
+        .map_err(|e| format!("로그 업로드 실패: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("로그 업로드 응답 parse failed: {}", e))?;
+    let (true, Some(id), Some(url)) = (response.success, response.id, response.url) else {
+        return Err(format!(
+            "로그 업로드 실패: {}",
+            response.error.as_deref().unwrap_or("알 수 없는 오류")
+        ));
+    };
+
+    let analysis = fetch_analysis(client, api_base, &id).await;
+    Ok(UploadedLog {
+        id,
+        url,
+        raw_url: response.raw,
+        file,
+        analysis,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn numbered(count: usize) -> String {
+        (0..count)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn keeps_small_logs_unchanged() {
+        let text = numbered(10);
+        assert_eq!(fit(&text, 20, 5, 1024), text);
+    }
+
+    #[test]
+    fn drops_middle_lines_over_line_limit() {
+        let output = fit(&numbered(100), 20, 5, 1024 * 1024);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 20);
+        assert_eq!(
+            &lines[..5],
+            ["line 0", "line 1", "line 2", "line 3", "line 4"]
+        );
+        assert!(lines[5].contains("81줄을 생략"));
+        assert_eq!(lines[6], "line 86");
+        assert_eq!(lines[19], "line 99");
+    }
+
+    #[test]
+    fn drops_middle_bytes_over_byte_limit() {
+        let text = numbered(10_000);
+        let output = fit(&text, usize::MAX, usize::MAX, 4096);
+        assert!(output.len() <= 4096);
+        assert!(output.starts_with("line 0\nline 1\n"));
+        assert!(output.ends_with("line 9998\nline 9999"));
+        assert!(output.contains("줄을 생략"));
+    }
+
+    #[test]
+    fn byte_limit_keeps_whole_multibyte_lines() {
+        let text = (0..2000)
+            .map(|_| "한글 로그")
+            .collect::<Vec<_>>()
+            .join("\n");
+        let output = fit(&text, usize::MAX, usize::MAX, 2048);
+        assert!(output.len() <= 2048);
+        assert!(output
+            .lines()
+            .all(|l| l == "한글 로그" || l.contains("줄을 생략")));
+    }
+
+    /// Answers the paste and insights requests like mclo.gs would, returning the uploaded body.
+    async fn stand_in(listener: TcpListener, paste: &'static str) -> String {
+        let mut uploaded = String::new();
+        for response in [
+            paste,
+            r#"{"title":"Fabric Client Log","analysis":{"problems":[{"message":"Mod crashed","solutions":[{"message":"Remove it"}]}],"information":[{"label":"Java","value":21}]}}"#,
+        ] {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text[..end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        if length > 0 {
+                            uploaded = text[end + 4..].to_string();
+                        }
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            stream.write_all(reply.as_bytes()).await.unwrap();
+        }
+        uploaded
+    }
+
+    #[tokio::test]
+    async fn shares_log_and_reads_analysis() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(stand_in(
+            listener,
+            r#"{"success":true,"id":"abc123","url":"https://mclo.gs/abc123","raw":"https://api.mclo.gs/1/raw/abc123"}"#,
+        ));
+
+        let uploaded = share(
+            &reqwest::Client::new(),
+            &api_base,
+            "[main/INFO]: 시작",
+            "latest.log".into(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(uploaded.id, "abc123");
+        assert_eq!(uploaded.url, "https://mclo.gs/abc123");
+        assert_eq!(
+            uploaded.raw_url.as_deref(),
+            Some("https://api.mclo.gs/1/raw/abc123")
+        );
+        let analysis = uploaded.analysis.unwrap();
+        assert_eq!(analysis.title.as_deref(), Some("Fabric Client Log"));
+        assert_eq!(analysis.problems[0].solutions, ["Remove it"]);
+        assert_eq!(analysis.information[0].value, "21");
+
+        let body = server.await.unwrap();
+        assert!(body.starts_with("content="));
+        assert!(body.contains("%5Bmain%2FINFO%5D"));
+    }
+
+    #[tokio::test]
+    async fn reports_service_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(stand_in(
+            listener,
+            r#"{"success":false,"error":"Log is empty"}"#,
+        ));
+
+        let error = share(&reqwest::Client::new(), &api_base, "x", "latest.log".into())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error, "로그 업로드 실패: Log is empty");
+    }
+}
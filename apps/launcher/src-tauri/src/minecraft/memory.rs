@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::instance::Instance;
+use super::system::{self, SystemInfo};
+
+/// Below this even vanilla runs out of heap while loading resources.
+pub const MIN_HEAP_MB: u32 = 1024;
+const VANILLA_HEAP_MB: u32 = 2048;
+const MODDED_HEAP_MB: u32 = 4096;
+const HEAVY_MODPACK_HEAP_MB: u32 = 6144;
+/// Enabled mods from which an instance counts as a heavy modpack.
+const HEAVY_MODPACK_MODS: usize = 100;
+/// Heap sizes are kept to multiples of this.
+const STEP_MB: u32 = 512;
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceProfile {
+    Vanilla,
+    Modded,
+    HeavyModpack,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryRecommendation {
+    pub profile: InstanceProfile,
+    pub mod_count: usize,
+    pub recommended_mb: u32,
+    /// Largest heap that leaves the OS enough; `None` when total memory is unknown.
+    pub max_safe_mb: Option<u32>,
+    pub total_memory_mb: Option<u64>,
+    pub available_memory_mb: Option<u64>,
+}
+
+/// Heap the game is started with, after `tune` checked the requested size.
+pub struct HeapSize {
+    pub max_mb: u32,
+    pub initial_mb: u32,
+    pub warnings: Vec<String>,
+}
+
+fn round_down(mb: u64) -> u32 {
+    let mb = u32::try_from(mb).unwrap_or(u32::MAX);
+    mb / STEP_MB * STEP_MB
+}
+
+/// Memory left to the OS, the launcher and the JVM outside its heap: a quarter of the total,
+/// but never less than 2 GB.
+fn max_safe_mb(total_mb: u64) -> u32 {
+    let reserved = (total_mb / 4).max(2048);
+    round_down(total_mb.saturating_sub(reserved)).max(MIN_HEAP_MB)
+}
+
+fn enabled_mod_count(game_dir: &Path) -> usize {
+    std::fs::read_dir(game_dir.join("mods"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".jar"))
+        .count()
+}
+
+fn recommend_with(game_dir: &Path, system: &SystemInfo) -> MemoryRecommendation {
+    let mod_count = enabled_mod_count(game_dir);
+    let has_loader = Instance::load(game_dir).is_some_and(|i| i.loader.is_some());
+    let (profile, wanted) = if mod_count >= HEAVY_MODPACK_MODS {
+        (InstanceProfile::HeavyModpack, HEAVY_MODPACK_HEAP_MB)
+    } else if has_loader || mod_count > 0 {
+        (InstanceProfile::Modded, MODDED_HEAP_MB)
+    } else {
+        (InstanceProfile::Vanilla, VANILLA_HEAP_MB)
+    };
+    let max_safe = system.total_memory_mb.map(max_safe_mb);
+    MemoryRecommendation {
+        profile,
+        mod_count,
+        recommended_mb: max_safe.map_or(wanted, |max| wanted.min(max)),
+        max_safe_mb: max_safe,
+        total_memory_mb: system.total_memory_mb,
+        available_memory_mb: system.available_memory_mb,
+    }
+}
+
+/// Heap size suited to the instance in `game_dir` on this machine.
+pub fn recommend(game_dir: &Path) -> MemoryRecommendation {
+    recommend_with(game_dir, &system::info())
+}
+
+/// Check the heap the player asked for: raised to the minimum, clamped to what the machine can
+/// spare without swapping, and warned about when it is unlikely to be enough or free right now.
+///
+/// Blocks while the system is queried; async callers run it on a blocking thread.
+pub fn tune(requested_mb: u32, game_dir: &Path) -> HeapSize {
+    tune_with(requested_mb, game_dir, &system::info())
+}
+
+fn tune_with(requested_mb: u32, game_dir: &Path, system: &SystemInfo) -> HeapSize {
+    let recommendation = recommend_with(game_dir, system);
+    let mut warnings = Vec::new();
+    let mut max_mb = requested_mb;
+
+    if max_mb < MIN_HEAP_MB {
+        warnings.push(format!(
+            "할당 메모리 {} MB는 너무 작아 {} MB로 실행합니다",
+            max_mb, MIN_HEAP_MB
+        ));
+        max_mb = MIN_HEAP_MB;
+    }
+    if let (Some(max_safe), Some(total)) = (recommendation.max_safe_mb, system.total_memory_mb) {
+        if max_mb > max_safe {
+            warnings.push(format!(
+                "할당 메모리 {} MB는 시스템 메모리({} MB)에 비해 너무 커서 {} MB로 줄여 실행합니다",
+                max_mb, total, max_safe
+            ));
+            max_mb = max_safe;
+        }
+    }
+    if let Some(available) = system.available_memory_mb {
+        if u64::from(max_mb) > available {
+            warnings.push(format!(
+                "사용 가능한 메모리({} MB)가 할당 메모리보다 적습니다. 다른 프로그램을 종료하지 않으면 느려질 수 있습니다",
+                available
+            ));
+        }
+    }
+    if max_mb < recommendation.recommended_mb {
+        warnings.push(format!(
+            "이 인스턴스에는 {} MB 이상을 권장합니다",
+            recommendation.recommended_mb
+        ));
+    }
+
+    HeapSize {
+        max_mb,
+        initial_mb: max_mb / 2,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn machine(total_mb: Option<u64>, available_mb: Option<u64>) -> SystemInfo {
+        SystemInfo {
+            os: "linux".to_string(),
+            os_version: None,
+            arch: "x86_64".to_string(),
+            cpu_model: None,
+            cpu_threads: 4,
+            total_memory_mb: total_mb,
+            available_memory_mb: available_mb,
+        }
+    }
+
+    /// A game directory with `mods` jars in it, removed on drop.
+    struct GameDir(PathBuf);
+
+    impl GameDir {
+        fn with_mods(mods: usize) -> Self {
+            let dir = std::env::temp_dir().join(format!("memory-test-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(dir.join("mods")).unwrap();
+            for i in 0..mods {
+                std::fs::write(dir.join("mods").join(format!("mod-{}.jar", i)), b"").unwrap();
+            }
+            GameDir(dir)
+        }
+    }
+
+    impl Drop for GameDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn max_safe_leaves_at_least_two_gb() {
+        assert_eq!(max_safe_mb(2048), MIN_HEAP_MB);
+        assert_eq!(max_safe_mb(4096), 2048);
+        assert_eq!(max_safe_mb(8192), 6144);
+        assert_eq!(max_safe_mb(16384), 12288);
+        // Odd totals round down to a whole step
+        assert_eq!(max_safe_mb(7900), 5632);
+    }
+
+    #[test]
+    fn recommends_by_profile() {
+        let system = machine(Some(32768), Some(16384));
+        let vanilla = GameDir::with_mods(0);
+        let recommendation = recommend_with(&vanilla.0, &system);
+        assert!(recommendation.profile == InstanceProfile::Vanilla);
+        assert_eq!(recommendation.recommended_mb, VANILLA_HEAP_MB);
+
+        let modded = GameDir::with_mods(3);
+        let recommendation = recommend_with(&modded.0, &system);
+        assert!(recommendation.profile == InstanceProfile::Modded);
+        assert_eq!(recommendation.mod_count, 3);
+        assert_eq!(recommendation.recommended_mb, MODDED_HEAP_MB);
+
+        let heavy = GameDir::with_mods(HEAVY_MODPACK_MODS);
+        let recommendation = recommend_with(&heavy.0, &system);
+        assert!(recommendation.profile == InstanceProfile::HeavyModpack);
+        assert_eq!(recommendation.recommended_mb, HEAVY_MODPACK_HEAP_MB);
+    }
+
+    #[test]
+    fn recommendation_fits_small_machines() {
+        let heavy = GameDir::with_mods(HEAVY_MODPACK_MODS);
+
+        let recommendation = recommend_with(&heavy.0, &machine(Some(2048), Some(1024)));
+        assert_eq!(recommendation.max_safe_mb, Some(MIN_HEAP_MB));
+        assert_eq!(recommendation.recommended_mb, MIN_HEAP_MB);
+
+        let recommendation = recommend_with(&heavy.0, &machine(Some(4096), Some(2048)));
+        assert_eq!(recommendation.max_safe_mb, Some(2048));
+        assert_eq!(recommendation.recommended_mb, 2048);
+    }
+
+    #[test]
+    fn unknown_total_recommends_profile_size() {
+        let modded = GameDir::with_mods(1);
+        let recommendation = recommend_with(&modded.0, &machine(None, None));
+        assert_eq!(recommendation.max_safe_mb, None);
+        assert_eq!(recommendation.recommended_mb, MODDED_HEAP_MB);
+
+        let heap = tune_with(16384, &modded.0, &machine(None, None));
+        assert_eq!(heap.max_mb, 16384);
+        assert!(heap.warnings.is_empty());
+    }
+
+    #[test]
+    fn tune_clamps_and_warns() {
+        let modded = GameDir::with_mods(1);
+
+        let heap = tune_with(512, &modded.0, &machine(Some(16384), Some(8192)));
+        assert_eq!(heap.max_mb, MIN_HEAP_MB);
+        assert_eq!(heap.initial_mb, MIN_HEAP_MB / 2);
+        assert_eq!(heap.warnings.len(), 2);
+
+        let heap = tune_with(8192, &modded.0, &machine(Some(4096), Some(1024)));
+        assert_eq!(heap.max_mb, 2048);
+        // Clamped and more than is free; the recommendation is capped the same way
+        assert_eq!(heap.warnings.len(), 2);
+
+        let heap = tune_with(4096, &modded.0, &machine(Some(16384), Some(8192)));
+        assert_eq!(heap.max_mb, 4096);
+        assert!(heap.warnings.is_empty());
+    }
+}
@@ -1,3 +1,29 @@
+pub mod cache;
+pub mod crash;
+pub mod curseforge;
+pub mod diagnostics;
+pub mod disk;
 pub mod download;
+pub mod forge;
+pub mod game_log;
+pub mod http;
+pub mod hud_mod;
+pub mod instance;
 pub mod java;
+pub mod jvm_args;
 pub mod launch;
+pub mod launcher_log;
+pub mod loader;
+pub mod mclogs;
+pub mod memory;
+pub mod mod_meta;
+pub mod mod_sync;
+pub mod mod_toggle;
+pub mod mod_validate;
+pub mod modrinth;
+pub mod mrpack;
+pub mod packwiz;
+pub mod process;
+pub mod redact;
+pub mod remote_config;
+pub mod system;
@@ -0,0 +1,559 @@
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+/// Jar-in-jar nesting deeper than this is not followed.
+const MAX_NESTING: u32 = 3;
+
+/// Suffix that keeps a jar in `mods/` without Fabric loading it.
+pub const DISABLED_SUFFIX: &str = ".jar.disabled";
+
+/// Icons above this size are left out instead of being inlined into the list.
+const MAX_ICON_BYTES: u64 = 256 * 1024;
+
+/// `fabric.mod.json`/`quilt.mod.json` past this size are treated as broken.
+const MAX_METADATA_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Bundled jars past this size are not opened for their metadata.
+const MAX_NESTED_JAR_BYTES: u64 = 32 * 1024 * 1024;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModMetadata {
+    pub id: String,
+    pub version: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    /// `fabric` or `quilt`, after the file the metadata came from.
+    pub loader: String,
+    /// The jar also ships `fabric.mod.json`, so Fabric Loader can load it when `loader` is `quilt`.
+    pub fabric_compatible: bool,
+    /// `*`, `client` or `server`.
+    pub environment: String,
+    /// Dependency id to version predicates; any one predicate matching is enough.
+    pub depends: BTreeMap<String, Vec<String>>,
+    pub breaks: BTreeMap<String, Vec<String>>,
+    /// Extra ids this mod satisfies, e.g. an old id after a rename.
+    pub provides: Vec<String>,
+    /// `data:` URL of the icon, only for top-level jars.
+    pub icon: Option<String>,
+    /// Mods bundled inside this jar (jar-in-jar).
+    pub nested: Vec<ModMetadata>,
+    /// `fabric.mod.json` of a jar that ships both files, which is all Fabric Loader reads.
+    #[serde(skip)]
+    pub fabric: Option<Box<ModMetadata>>,
+}
+
+impl ModMetadata {
+    /// The metadata the `loader_kind` loader reads from this jar.
+    pub fn for_loader(&self, loader_kind: Option<&str>) -> &ModMetadata {
+        match self.fabric {
+            Some(ref fabric) if loader_kind == Some("fabric") => fabric,
+            _ => self,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedMod {
+    pub filename: String,
+    pub size: u64,
+    pub enabled: bool,
+    /// Installed and kept up to date by the launcher from the server configuration.
+    pub managed: bool,
+    /// `None` for jars without `fabric.mod.json`/`quilt.mod.json` (libraries, other loaders).
+    pub metadata: Option<ModMetadata>,
+    pub error: Option<String>,
+}
+
+/// Read the mod metadata of every `.jar` and `.jar.disabled` in `mods_dir`, sorted by file name.
+pub fn scan_dir(mods_dir: &Path) -> Vec<ScannedMod> {
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    let mut mods: Vec<ScannedMod> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.to_string_lossy();
+            name.ends_with(".jar") || name.ends_with(DISABLED_SUFFIX)
+        })
+        .map(|p| scan_file(&p))
+        .collect();
+    mods.sort_by_key(|m| m.filename.to_lowercase());
+    mods
+}
+
+pub fn scan_file(path: &Path) -> ScannedMod {
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let (metadata, error) = match read_jar(path) {
+        Ok(metadata) => (metadata, None),
+        Err(e) => (None, Some(e)),
+    };
+    ScannedMod {
+        enabled: !filename.ends_with(DISABLED_SUFFIX),
+        managed: false,
+        filename,
+        size,
+        metadata,
+        error,
+    }
+}
+
+pub fn read_jar(path: &Path) -> Result<Option<ModMetadata>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Open jar failed: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid jar: {}", e))?;
+    read_archive(&mut archive, 0)
+}
+
+fn read_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    depth: u32,
+) -> Result<Option<ModMetadata>, String> {
+    // Quilt jars often ship both files; quilt.mod.json is the richer one, but Fabric Loader
+    // only reads fabric.mod.json
+    let fabric = read_entry_json(archive, "fabric.mod.json")?
+        .map(|json| parse_fabric(&json))
+        .transpose()?;
+    let (mut metadata, nested_paths) = match (read_entry_json(archive, "quilt.mod.json")?, fabric) {
+        (Some(json), fabric) => {
+            let (mut metadata, nested) = parse_quilt(&json)?;
+            metadata.fabric_compatible = fabric.is_some();
+            metadata.fabric = fabric.map(|(fabric, _)| Box::new(fabric));
+            (metadata, nested)
+        }
+        (None, Some(fabric)) => fabric,
+        (None, None) => return Ok(None),
+    };
+
+    if depth == 0 {
+        metadata.icon = metadata
+            .icon
+            .take()
+            .and_then(|path| read_icon(archive, &path));
+    } else {
+        metadata.icon = None;
+    }
+
+    if depth < MAX_NESTING {
+        for path in nested_paths {
+            let Some(bytes) = read_entry(archive, &path, MAX_NESTED_JAR_BYTES) else {
+                continue;
+            };
+            let Ok(mut inner) = zip::ZipArchive::new(Cursor::new(bytes)) else {
+                continue;
+            };
+            if let Ok(Some(nested)) = read_archive(&mut inner, depth + 1) {
+                metadata.nested.push(nested);
+            }
+        }
+    }
+    // Both files list the same bundled jars in practice
+    if let Some(ref mut fabric) = metadata.fabric {
+        fabric.nested = metadata.nested.clone();
+    }
+    Ok(Some(metadata))
+}
+
+/// Read `name` whole, giving up on entries that inflate past `limit` whatever their header says.
+fn read_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+    limit: u64,
+) -> Option<Vec<u8>> {
+    let entry = archive.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    entry.take(limit + 1).read_to_end(&mut bytes).ok()?;
+    (bytes.len() as u64 <= limit).then_some(bytes)
+}
+
+fn read_entry_json<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Value>, String> {
+    if archive.by_name(name).is_err() {
+        return Ok(None);
+    }
+    let bytes = read_entry(archive, name, MAX_METADATA_BYTES)
+        .ok_or_else(|| format!("{}을(를) 읽을 수 없거나 너무 큽니다", name))?;
+    // Some mods ship their metadata with a UTF-8 BOM
+    let text = String::from_utf8_lossy(&bytes);
+    serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map(Some)
+        .map_err(|e| format!("{} parse failed: {}", name, e))
+}
+
+fn read_icon<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, path: &str) -> Option<String> {
+    let path = path.trim_start_matches('/');
+    if archive.by_name(path).ok()?.size() > MAX_ICON_BYTES {
+        return None;
+    }
+    let bytes = read_entry(archive, path, MAX_ICON_BYTES)?;
+    let mime = if path.ends_with(".jpg") || path.ends_with(".jpeg") {
+        "image/jpeg"
+    } else {
+        "image/png"
+    };
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// A version predicate written as a string or an array of strings.
+fn predicates(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => vec!["*".to_string()],
+    }
+}
+
+/// Fabric icons are a path, or a map of pixel size to path; the largest one wins.
+fn fabric_icon(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(sizes) => sizes
+            .iter()
+            .filter_map(|(size, path)| Some((size.parse::<u32>().ok()?, path.as_str()?)))
+            .max_by_key(|(size, _)| *size)
+            .map(|(_, path)| path.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_fabric(json: &Value) -> Result<(ModMetadata, Vec<String>), String> {
+    let id = string(json, "id").ok_or("fabric.mod.json: id missing")?;
+    let relations = |key: &str| -> BTreeMap<String, Vec<String>> {
+        json.get(key)
+            .and_then(Value::as_object)
+            .map(|map| {
+                map.iter()
+                    .map(|(k, v)| (k.clone(), predicates(v)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let authors = json
+        .get("authors")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|a| a.as_str().map(str::to_string).or_else(|| string(a, "name")))
+                .collect()
+        })
+        .unwrap_or_default();
+    let nested = json
+        .get("jars")
+        .and_then(Value::as_array)
+        .map(|jars| jars.iter().filter_map(|j| string(j, "file")).collect())
+        .unwrap_or_default();
+
+    let metadata = ModMetadata {
+        name: string(json, "name").unwrap_or_else(|| id.clone()),
+        version: string(json, "version").unwrap_or_default(),
+        description: string(json, "description"),
+        authors,
+        loader: "fabric".to_string(),
+        fabric_compatible: true,
+        environment: string(json, "environment").unwrap_or_else(|| "*".to_string()),
+        depends: relations("depends"),
+        breaks: relations("breaks"),
+        provides: json.get("provides").map(predicates).unwrap_or_default(),
+        icon: fabric_icon(json.get("icon")),
+        nested: Vec::new(),
+        fabric: None,
+        id,
+    };
+    Ok((metadata, nested))
+}
+
+/// Quilt dependencies are an id, or an object with `id` and `versions`.
+fn quilt_relations(value: Option<&Value>) -> BTreeMap<String, Vec<String>> {
+    let Some(items) = value.and_then(Value::as_array) else {
+        return BTreeMap::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(id) => Some((id.clone(), vec!["*".to_string()])),
+            Value::Object(_) => {
+                if item.get("optional").and_then(Value::as_bool) == Some(true) {
+                    return None;
+                }
+                let id = string(item, "id")?;
+                let id = id.rsplit(':').next().unwrap_or(&id).to_string();
+                let versions = match item.get("versions") {
+                    Some(Value::Object(any)) => any.get("any").map(predicates).unwrap_or_default(),
+                    Some(v) => predicates(v),
+                    None => vec!["*".to_string()],
+                };
+                Some((id, versions))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_quilt(json: &Value) -> Result<(ModMetadata, Vec<String>), String> {
+    let loader = json
+        .get("quilt_loader")
+        .ok_or("quilt.mod.json: quilt_loader missing")?;
+    let id = string(loader, "id").ok_or("quilt.mod.json: id missing")?;
+    let meta = loader.get("metadata").cloned().unwrap_or(Value::Null);
+    let authors = meta
+        .get("contributors")
+        .and_then(Value::as_object)
+        .map(|c| c.keys().cloned().collect())
+        .unwrap_or_default();
+    let environment = match json
+        .get("minecraft")
+        .and_then(|m| string(m, "environment"))
+        .as_deref()
+    {
+        Some("client") => "client",
+        Some("dedicated_server") => "server",
+        _ => "*",
+    };
+    let nested = loader
+        .get("jars")
+        .and_then(Value::as_array)
+        .map(|jars| {
+            jars.iter()
+                .filter_map(|j| j.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let metadata = ModMetadata {
+        name: string(&meta, "name").unwrap_or_else(|| id.clone()),
+        version: string(loader, "version").unwrap_or_default(),
+        description: string(&meta, "description"),
+        authors,
+        loader: "quilt".to_string(),
+        fabric_compatible: false,
+        environment: environment.to_string(),
+        depends: quilt_relations(loader.get("depends")),
+        breaks: quilt_relations(loader.get("breaks")),
+        provides: loader
+            .get("provides")
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string).or_else(|| string(p, "id")))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        icon: fabric_icon(meta.get("icon")),
+        nested: Vec::new(),
+        fabric: None,
+        id,
+    };
+    Ok((metadata, nested))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// An in-memory jar holding `entries`.
+    fn jar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, data) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn read(bytes: Vec<u8>) -> Result<Option<ModMetadata>, String> {
+        read_archive(&mut zip::ZipArchive::new(Cursor::new(bytes)).unwrap(), 0)
+    }
+
+    #[test]
+    fn parses_fabric_mod_json() {
+        let json = br#"{
+            "schemaVersion": 1,
+            "id": "sodium",
+            "version": "0.7.0+mc1.21.11",
+            "name": "Sodium",
+            "description": "Rendering engine",
+            "authors": ["JellySquid", {"name": "IMS"}],
+            "environment": "client",
+            "icon": {"16": "assets/sodium/small.png", "128": "assets/sodium/icon.png"},
+            "depends": {"fabricloader": ">=0.16.0", "minecraft": ["1.21.10", "1.21.11"]},
+            "breaks": {"optifabric": "*"},
+            "provides": ["rubidium"]
+        }"#;
+        let metadata = read(jar(&[
+            ("fabric.mod.json", json),
+            ("assets/sodium/icon.png", b"png"),
+        ]))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(metadata.id, "sodium");
+        assert_eq!(metadata.version, "0.7.0+mc1.21.11");
+        assert_eq!(metadata.name, "Sodium");
+        assert_eq!(metadata.description.as_deref(), Some("Rendering engine"));
+        assert_eq!(metadata.authors, ["JellySquid", "IMS"]);
+        assert_eq!(metadata.loader, "fabric");
+        assert!(metadata.fabric_compatible);
+        assert_eq!(metadata.environment, "client");
+        assert_eq!(metadata.depends["fabricloader"], [">=0.16.0"]);
+        assert_eq!(metadata.depends["minecraft"], ["1.21.10", "1.21.11"]);
+        assert_eq!(metadata.breaks["optifabric"], ["*"]);
+        assert_eq!(metadata.provides, ["rubidium"]);
+        // The largest icon, inlined
+        assert_eq!(metadata.icon.as_deref(), Some("data:image/png;base64,cG5n"));
+    }
+
+    #[test]
+    fn parses_quilt_mod_json() {
+        let quilt = br#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "id": "qsl",
+                "version": "10.0.0",
+                "metadata": {
+                    "name": "Quilt Standard Libraries",
+                    "contributors": {"QuiltMC": "Owner"}
+                },
+                "depends": [
+                    "quilt_loader",
+                    {"id": "org.quiltmc:minecraft", "versions": {"any": ["1.21.11"]}},
+                    {"id": "modmenu", "optional": true}
+                ],
+                "breaks": [{"id": "fabric-api", "versions": "<0.100.0"}],
+                "provides": ["quilted_fabric_api", {"id": "fabric"}]
+            },
+            "minecraft": {"environment": "dedicated_server"}
+        }"#;
+        let fabric = br#"{"id": "qsl-fabric", "version": "10.0.0"}"#;
+        let metadata = read(jar(&[
+            ("quilt.mod.json", quilt),
+            ("fabric.mod.json", fabric),
+        ]))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(metadata.id, "qsl");
+        assert_eq!(metadata.name, "Quilt Standard Libraries");
+        assert_eq!(metadata.authors, ["QuiltMC"]);
+        assert_eq!(metadata.loader, "quilt");
+        assert_eq!(metadata.environment, "server");
+        assert_eq!(metadata.depends["quilt_loader"], ["*"]);
+        assert_eq!(metadata.depends["minecraft"], ["1.21.11"]);
+        assert!(!metadata.depends.contains_key("modmenu"));
+        assert_eq!(metadata.breaks["fabric-api"], ["<0.100.0"]);
+        assert_eq!(metadata.provides, ["quilted_fabric_api", "fabric"]);
+        // Fabric Loader only sees the fabric.mod.json of the same jar
+        assert!(metadata.fabric_compatible);
+        assert_eq!(metadata.for_loader(Some("fabric")).id, "qsl-fabric");
+        assert_eq!(metadata.for_loader(Some("quilt")).id, "qsl");
+    }
+
+    #[test]
+    fn follows_nested_jars_up_to_the_limit() {
+        // outer -> level-1 -> level-2 -> level-3 -> level-4
+        let mut inner = jar(&[("fabric.mod.json", br#"{"id": "level-4"}"#)]);
+        for level in (1..=3).rev() {
+            let json = format!(
+                r#"{{"id": "level-{}", "icon": "icon.png", "jars": [{{"file": "META-INF/jars/inner.jar"}}]}}"#,
+                level
+            );
+            inner = jar(&[
+                ("fabric.mod.json", json.as_bytes()),
+                ("icon.png", b"png"),
+                ("META-INF/jars/inner.jar", &inner),
+            ]);
+        }
+        let outer = jar(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "outer", "jars": [{"file": "META-INF/jars/inner.jar"}, {"file": "META-INF/jars/missing.jar"}]}"#,
+            ),
+            ("META-INF/jars/inner.jar", &inner),
+        ]);
+        let metadata = read(outer).unwrap().unwrap();
+
+        let level1 = &metadata.nested[0];
+        let level2 = &level1.nested[0];
+        let level3 = &level2.nested[0];
+        assert_eq!(metadata.nested.len(), 1);
+        assert_eq!(
+            [&level1.id, &level2.id, &level3.id],
+            ["level-1", "level-2", "level-3"]
+        );
+        // Read at the limit, but its own bundled jars are not opened
+        assert!(level3.nested.is_empty());
+        // Icons are only inlined for the top-level jar
+        assert!(level1.icon.is_none());
+    }
+
+    #[test]
+    fn strips_byte_order_mark() {
+        let json = "\u{feff}{\"id\": \"bom\", \"version\": \"1.0.0\"}";
+        let metadata = read(jar(&[("fabric.mod.json", json.as_bytes())]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(metadata.id, "bom");
+        assert_eq!(metadata.name, "bom");
+    }
+
+    #[test]
+    fn reports_malformed_metadata() {
+        let err = read(jar(&[("fabric.mod.json", b"{\"id\": \"broken\",")]))
+            .err()
+            .unwrap();
+        assert!(err.contains("fabric.mod.json parse failed"), "{}", err);
+        assert!(read(jar(&[("fabric.mod.json", br#"{"version": "1.0.0"}"#)])).is_err());
+        assert!(read(jar(&[("quilt.mod.json", br#"{"schema_version": 1}"#)])).is_err());
+        // A library without metadata is not an error
+        assert!(read(jar(&[("META-INF/MANIFEST.MF", b"")]))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn scan_reports_broken_jars() {
+        let dir = std::env::temp_dir().join(format!("mod-meta-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("b.jar.disabled"),
+            jar(&[("fabric.mod.json", br#"{"id": "b"}"#)]),
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.jar"), b"not a zip").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"").unwrap();
+
+        let mods = scan_dir(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(mods.len(), 2);
+        assert!(mods[0].enabled && mods[0].error.is_some());
+        assert!(!mods[1].enabled);
+        assert_eq!(mods[1].metadata.as_ref().unwrap().id, "b");
+    }
+}
@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use sha1::Digest;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::download::{self, emit_progress};
+use super::launcher_log;
+use super::modrinth;
+use super::remote_config::ConfigMod;
+
+/// Files the launcher put in `mods/`; anything not listed here belongs to the player.
+const MANIFEST_FILE: &str = ".nyaru-managed.json";
+
+/// Jars earlier launcher versions wrote without a manifest, by configured mod id and file prefix.
+const LEGACY_JARS: &[(&str, &str)] = &[("fabric-api", "fabric-api-")];
+
+/// A configured mod resolved to one exact file.
+pub struct LockedFile {
+    pub version_id: Option<String>,
+    pub url: String,
+    pub filename: String,
+    /// 0 when unknown (direct URLs).
+    pub size: u64,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedFile {
+    pub id: String,
+    pub filename: String,
+    pub version_id: Option<String>,
+    pub sha512: Option<String>,
+    pub sha1: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedManifest {
+    pub files: Vec<ManagedFile>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSyncReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub failed: Vec<ModSyncFailure>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSyncFailure {
+    pub id: String,
+    pub error: String,
+}
+
+impl ManagedManifest {
+    pub fn load(mods_dir: &Path) -> Self {
+        std::fs::read(mods_dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, mods_dir: &Path) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(mods_dir.join(MANIFEST_FILE), json)
+            .map_err(|e| format!("Write {} failed: {}", MANIFEST_FILE, e))
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ManagedFile> {
+        self.files.iter().find(|f| f.id == id)
+    }
+
+    pub fn is_managed(&self, filename: &str) -> bool {
+        self.files.iter().any(|f| f.filename == filename)
+    }
+}
+
+/// Resolve a configured mod to a concrete file: a direct URL or a pinned Modrinth version.
+/// Entries without a pinned version or hash are rejected so every launcher installs the same file.
+///
+/// Hashes from the configuration win; a Modrinth file that disagrees with them is rejected.
+pub async fn resolve(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    m: &ConfigMod,
+    mc_version: &str,
+) -> Result<LockedFile, String> {
+    m.validate()?;
+    if let Some(ref url) = m.url {
+        let filename = m
+            .filename
+            .clone()
+            .or_else(|| url.rsplit('/').next().map(|s| s.to_string()))
+            .ok_or_else(|| format!("{}: filename missing", m.id))?;
+        return Ok(LockedFile {
+            version_id: m.version_id.clone(),
+            url: url.clone(),
+            filename,
+            size: 0,
+            sha1: m.sha1.clone(),
+            sha512: m.sha512.clone(),
+        });
+    }
+
+    let version_id = m.version_id.as_deref().unwrap_or_default();
+    let version = match m.project_id.as_deref() {
+        Some(project_id) => {
+            modrinth::get_project_version(client, fetcher, project_id, version_id).await?
+        }
+        None => modrinth::get_version(client, fetcher, version_id).await?,
+    };
+    if !version.game_versions.iter().any(|v| v == mc_version) {
+        return Err(format!(
+            "{}: 고정된 버전 {}은(는) MC {}를 지원하지 않습니다",
+            m.id, version.version_number, mc_version
+        ));
+    }
+    let file = version
+        .primary_file()
+        .ok_or_else(|| format!("{}: no files in version {}", m.id, version.id))?;
+
+    for (name, pinned, published) in [
+        ("sha1", &m.sha1, &file.hashes.sha1),
+        ("sha512", &m.sha512, &file.hashes.sha512),
+    ] {
+        if let (Some(pinned), Some(published)) = (pinned, published) {
+            if !pinned.eq_ignore_ascii_case(published) {
+                return Err(format!(
+                    "{}: 설정의 {}와 Modrinth 파일이 다릅니다",
+                    m.id, name
+                ));
+            }
+        }
+    }
+
+    Ok(LockedFile {
+        version_id: Some(version.id.clone()),
+        url: file.url.clone(),
+        filename: m.filename.clone().unwrap_or_else(|| file.filename.clone()),
+        size: file.size,
+        sha1: m.sha1.clone().or_else(|| file.hashes.sha1.clone()),
+        sha512: m.sha512.clone().or_else(|| file.hashes.sha512.clone()),
+    })
+}
+
+/// Check `data` against every hash that is known; at least one must be.
+pub fn verify_hashes(data: &[u8], sha1: Option<&str>, sha512: Option<&str>) -> Result<(), String> {
+    if sha1.is_none() && sha512.is_none() {
+        return Err("해시 정보가 없습니다".to_string());
+    }
+    if let Some(expected) = sha512 {
+        let actual = hex::encode(sha2::Sha512::digest(data));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "sha512 불일치 (기대 {}, 실제 {})",
+                expected, actual
+            ));
+        }
+    }
+    if let Some(expected) = sha1 {
+        let actual = hex::encode(sha1::Sha1::digest(data));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!("sha1 불일치 (기대 {}, 실제 {})", expected, actual));
+        }
+    }
+    Ok(())
+}
+
+fn file_matches(path: &Path, file: &LockedFile) -> bool {
+    std::fs::read(path)
+        .map(|data| verify_hashes(&data, file.sha1.as_deref(), file.sha512.as_deref()).is_ok())
+        .unwrap_or(false)
+}
+
+/// Download into a temporary file and only move it into `mods/` once the hashes match.
+pub async fn download_verified(
+    client: &reqwest::Client,
+    file: &LockedFile,
+    path: &Path,
+) -> Result<(), String> {
+    let data = download::download_bytes(client, &file.url).await?;
+    verify_hashes(&data, file.sha1.as_deref(), file.sha512.as_deref())
+        .map_err(|e| format!("{}: {}", file.filename, e))?;
+    let part = PathBuf::from(format!("{}.part", path.to_string_lossy()));
+    std::fs::write(&part, &data).map_err(|e| format!("Write file failed: {}", e))?;
+    std::fs::rename(&part, path).map_err(|e| format!("Move file failed: {}", e))
+}
+
+/// Move a managed jar the player force-disabled back to its enabled name, so it is checked
+/// and updated in place instead of being downloaded next to the disabled copy.
+pub fn reenable_disabled(mods_dir: &Path, filename: &str) -> Result<(), String> {
+    let path = mods_dir.join(filename);
+    let disabled = mods_dir.join(format!("{}.disabled", filename));
+    if path.exists() || !disabled.exists() {
+        return Ok(());
+    }
+    std::fs::rename(&disabled, &path).map_err(|e| format!("Rename {} failed: {}", filename, e))
+}
+
+/// Remove a managed jar in either its enabled or its disabled form.
+fn remove_managed(mods_dir: &Path, filename: &str) -> Result<(), String> {
+    for name in [filename.to_string(), format!("{}.disabled", filename)] {
+        let path = mods_dir.join(&name);
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("Remove {} failed: {}", name, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove copies of `id` an older launcher installed before there was a manifest; left in
+/// place they would load next to the managed jar and Fabric refuses duplicate mod ids.
+fn remove_legacy(mods_dir: &Path, id: &str, keep: &str) {
+    let Some((_, prefix)) = LEGACY_JARS.iter().find(|(legacy, _)| *legacy == id) else {
+        return;
+    };
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name != keep && name.starts_with(prefix) && name.ends_with(".jar") {
+            launcher_log::write("INFO", &format!("이전 런처가 설치한 {} 제거", name));
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Make `mods/` contain exactly the required mods of the configuration.
+///
+/// Player-added jars are never touched; only files recorded in the managed manifest are
+/// replaced or removed.
+pub async fn sync(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    mc_version: &str,
+    mods: &[ConfigMod],
+) -> Result<ModSyncReport, String> {
+    let mods_dir = game_dir.join("mods");
+    std::fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+
+    let first_sync = !mods_dir.join(MANIFEST_FILE).exists();
+    let previous = ManagedManifest::load(&mods_dir);
+    let mut manifest = ManagedManifest::default();
+    let mut report = ModSyncReport::default();
+
+    let required: Vec<&ConfigMod> = mods.iter().filter(|m| m.required).collect();
+    let total = required.len() as u64;
+    for (i, m) in required.iter().enumerate() {
+        emit_progress(
+            app,
+            &format!("{} 확인 중...", m.name),
+            "mods",
+            i as u64,
+            total,
+            98.0,
+        );
+        let old = previous.get(&m.id);
+        let file = match resolve(client, fetcher, m, mc_version).await {
+            Ok(file) => file,
+            Err(error) => {
+                // Keep the previous file so a failed lookup never uninstalls a working mod
+                if let Some(old) = old {
+                    manifest.files.push(old.clone());
+                }
+                report.failed.push(ModSyncFailure {
+                    id: m.id.clone(),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        if first_sync {
+            remove_legacy(&mods_dir, &m.id, &file.filename);
+        }
+        let path = mods_dir.join(&file.filename);
+        if let Some(old) = old {
+            let _ = reenable_disabled(&mods_dir, &old.filename);
+        }
+        let present = file_matches(&path, &file);
+        if !present {
+            emit_progress(
+                app,
+                &format!("{} 다운로드 중...", m.name),
+                "mods",
+                i as u64,
+                total,
+                98.0,
+            );
+            if let Err(error) = download_verified(client, &file, &path).await {
+                if let Some(old) = old {
+                    manifest.files.push(old.clone());
+                }
+                report.failed.push(ModSyncFailure {
+                    id: m.id.clone(),
+                    error,
+                });
+                continue;
+            }
+        }
+        match old {
+            None => report.added.push(m.id.clone()),
+            Some(o) if present && o.filename == file.filename => {
+                report.unchanged.push(m.id.clone())
+            }
+            Some(_) => report.updated.push(m.id.clone()),
+        }
+
+        if let Some(old) = old {
+            if old.filename != file.filename {
+                let _ = remove_managed(&mods_dir, &old.filename);
+            }
+        }
+        manifest.files.push(ManagedFile {
+            id: m.id.clone(),
+            filename: file.filename,
+            version_id: file.version_id,
+            sha512: file.sha512,
+            sha1: file.sha1,
+        });
+    }
+
+    // Managed mods the configuration dropped
+    let kept: HashSet<&str> = manifest.files.iter().map(|f| f.id.as_str()).collect();
+    for old in previous
+        .files
+        .iter()
+        .filter(|f| !kept.contains(f.id.as_str()))
+    {
+        if !manifest.is_managed(&old.filename) {
+            remove_managed(&mods_dir, &old.filename)?;
+        }
+        report.removed.push(old.id.clone());
+    }
+
+    manifest.save(&mods_dir)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::remote_config::RemoteConfig;
+
+    fn config_mod(version_id: Option<&str>, sha512: Option<&str>) -> ConfigMod {
+        ConfigMod {
+            id: "example".to_string(),
+            name: "Example".to_string(),
+            project_id: Some("AANobbMI".to_string()),
+            version_id: version_id.map(str::to_string),
+            filename: None,
+            url: None,
+            sha1: None,
+            sha512: sha512.map(str::to_string),
+            required: true,
+        }
+    }
+
+    #[test]
+    fn bundled_config_pins_every_mod() {
+        let config = RemoteConfig::bundled();
+        assert!(!config.mods.is_empty());
+        for m in &config.mods {
+            m.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_unpinned_mods() {
+        assert!(config_mod(Some("1.0.0"), Some("ab")).validate().is_ok());
+        assert!(config_mod(None, Some("ab")).validate().is_err());
+        assert!(config_mod(Some("1.0.0"), None).validate().is_err());
+    }
+
+    #[test]
+    fn verifies_known_hashes() {
+        let data = b"nyaru";
+        let sha1 = hex::encode(sha1::Sha1::digest(data));
+        let sha512 = hex::encode(sha2::Sha512::digest(data));
+        assert!(verify_hashes(data, Some(&sha1), Some(&sha512)).is_ok());
+        assert!(verify_hashes(data, Some(&sha1.to_uppercase()), None).is_ok());
+        assert!(verify_hashes(b"other", Some(&sha1), None).is_err());
+        assert!(verify_hashes(data, None, None).is_err());
+    }
+}
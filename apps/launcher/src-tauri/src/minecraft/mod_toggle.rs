@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::hud_mod;
+use super::mod_meta::{self, ScannedMod, DISABLED_SUFFIX};
+use super::mod_sync::ManagedManifest;
+
+/// Jars disabled by safe mode, so `restore` brings back exactly the previous set.
+const SAFE_MODE_FILE: &str = ".nyaru-safe-mode.json";
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SafeModeState {
+    disabled: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeReport {
+    pub active: bool,
+    /// Jar names (enabled form) that safe mode turned off.
+    pub disabled: Vec<String>,
+}
+
+/// File names of the server-required mods, which must stay enabled.
+fn protected_files(game_dir: &Path) -> Vec<String> {
+    let mods_dir = game_dir.join("mods");
+    let mut files: Vec<String> = ManagedManifest::load(&mods_dir)
+        .files
+        .into_iter()
+        .map(|f| f.filename)
+        .collect();
+    files.extend(hud_mod::status(game_dir).filename);
+    files
+}
+
+fn enabled_name(filename: &str) -> &str {
+    filename
+        .strip_suffix(".disabled")
+        .filter(|name| name.ends_with(".jar"))
+        .unwrap_or(filename)
+}
+
+/// Reject anything that is not a plain jar name inside `mods/`.
+fn mod_path(mods_dir: &Path, filename: &str) -> Result<PathBuf, String> {
+    let is_plain = Path::new(filename).file_name().and_then(|n| n.to_str()) == Some(filename);
+    if !is_plain || !(filename.ends_with(".jar") || filename.ends_with(DISABLED_SUFFIX)) {
+        return Err(format!("잘못된 모드 파일 이름입니다: {}", filename));
+    }
+    Ok(mods_dir.join(filename))
+}
+
+/// Scanned mods with the server-managed ones marked.
+pub fn list(game_dir: &Path) -> Vec<ScannedMod> {
+    let protected = protected_files(game_dir);
+    let mut mods = mod_meta::scan_dir(&game_dir.join("mods"));
+    for m in &mut mods {
+        m.managed = protected.iter().any(|f| f == enabled_name(&m.filename));
+    }
+    mods
+}
+
+/// Rename `filename` to its enabled or disabled form and return the new name.
+///
+/// Managed mods can only be disabled with `force`; the next mod sync re-enables them anyway.
+pub fn set_enabled(
+    game_dir: &Path,
+    filename: &str,
+    enabled: bool,
+    force: bool,
+) -> Result<String, String> {
+    let mods_dir = game_dir.join("mods");
+    let path = mod_path(&mods_dir, filename)?;
+    if !path.exists() {
+        return Err(format!("모드 파일이 없습니다: {}", filename));
+    }
+
+    let jar_name = enabled_name(filename).to_string();
+    if !enabled && !force && protected_files(game_dir).contains(&jar_name) {
+        return Err(format!(
+            "{}은(는) 서버 필수 모드라 끌 수 없습니다",
+            jar_name
+        ));
+    }
+
+    let target_name = if enabled {
+        jar_name
+    } else {
+        format!("{}.disabled", jar_name)
+    };
+    if target_name == filename {
+        return Ok(target_name);
+    }
+    let target = mods_dir.join(&target_name);
+    if target.exists() {
+        return Err(format!("{}이(가) 이미 있습니다", target_name));
+    }
+    std::fs::rename(&path, &target).map_err(|e| format!("Rename {} failed: {}", filename, e))?;
+    Ok(target_name)
+}
+
+fn load_state(mods_dir: &Path) -> Option<SafeModeState> {
+    let data = std::fs::read(mods_dir.join(SAFE_MODE_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+pub fn safe_mode_status(game_dir: &Path) -> SafeModeReport {
+    match load_state(&game_dir.join("mods")) {
+        Some(state) => SafeModeReport {
+            active: true,
+            disabled: state.disabled,
+        },
+        None => SafeModeReport {
+            active: false,
+            disabled: Vec::new(),
+        },
+    }
+}
+
+/// Disable every enabled mod except the server-required ones, remembering which.
+pub fn enter_safe_mode(game_dir: &Path) -> Result<SafeModeReport, String> {
+    let mods_dir = game_dir.join("mods");
+    if load_state(&mods_dir).is_some() {
+        return Err("이미 안전 모드입니다".to_string());
+    }
+
+    let mut state = SafeModeState::default();
+    for m in list(game_dir)
+        .into_iter()
+        .filter(|m| m.enabled && !m.managed)
+    {
+        set_enabled(game_dir, &m.filename, false, false)?;
+        state.disabled.push(m.filename);
+        // Record progress as we go so a failure halfway can still be restored
+        let json = serde_json::to_vec_pretty(&state).map_err(|e| e.to_string())?;
+        std::fs::write(mods_dir.join(SAFE_MODE_FILE), json)
+            .map_err(|e| format!("Write {} failed: {}", SAFE_MODE_FILE, e))?;
+    }
+    if state.disabled.is_empty() {
+        return Err("끌 수 있는 모드가 없습니다".to_string());
+    }
+
+    Ok(SafeModeReport {
+        active: true,
+        disabled: state.disabled,
+    })
+}
+
+/// Re-enable the mods safe mode turned off; mods the player toggled since are left alone.
+pub fn restore(game_dir: &Path) -> Result<SafeModeReport, String> {
+    let mods_dir = game_dir.join("mods");
+    let state = load_state(&mods_dir).ok_or("복원할 모드 목록이 없습니다")?;
+
+    let mut failed = Vec::new();
+    for filename in &state.disabled {
+        let disabled = format!("{}.disabled", filename);
+        if mods_dir.join(&disabled).exists() {
+            if let Err(e) = set_enabled(game_dir, &disabled, true, false) {
+                failed.push(e);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        return Err(failed.join("\n"));
+    }
+
+    std::fs::remove_file(mods_dir.join(SAFE_MODE_FILE))
+        .map_err(|e| format!("Remove {} failed: {}", SAFE_MODE_FILE, e))?;
+    Ok(SafeModeReport {
+        active: false,
+        disabled: state.disabled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::mod_sync::{self, ManagedFile};
+
+    /// A game directory with empty jars in `mods/`, removed on drop.
+    struct GameDir(PathBuf);
+
+    impl GameDir {
+        fn with(managed: &[&str], player: &[&str]) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("mod-toggle-test-{}", uuid::Uuid::new_v4()));
+            let mods_dir = dir.join("mods");
+            std::fs::create_dir_all(&mods_dir).unwrap();
+            for name in managed.iter().chain(player) {
+                std::fs::write(mods_dir.join(name), b"").unwrap();
+            }
+            let manifest = ManagedManifest {
+                files: managed
+                    .iter()
+                    .map(|name| ManagedFile {
+                        id: name.trim_end_matches(".jar").to_string(),
+                        filename: name.to_string(),
+                        version_id: None,
+                        sha512: None,
+                        sha1: None,
+                    })
+                    .collect(),
+            };
+            manifest.save(&mods_dir).unwrap();
+            GameDir(dir)
+        }
+
+        fn has(&self, name: &str) -> bool {
+            self.0.join("mods").join(name).exists()
+        }
+    }
+
+    impl Drop for GameDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn toggles_player_mods() {
+        let game = GameDir::with(&[], &["sodium.jar"]);
+        assert_eq!(
+            set_enabled(&game.0, "sodium.jar", false, false).unwrap(),
+            "sodium.jar.disabled"
+        );
+        assert!(!game.has("sodium.jar"));
+        assert!(!list(&game.0)[0].enabled);
+
+        assert_eq!(
+            set_enabled(&game.0, "sodium.jar.disabled", true, false).unwrap(),
+            "sodium.jar"
+        );
+        assert!(game.has("sodium.jar") && !game.has("sodium.jar.disabled"));
+        // Already in the requested state
+        assert_eq!(
+            set_enabled(&game.0, "sodium.jar", true, false).unwrap(),
+            "sodium.jar"
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_mods() {
+        let game = GameDir::with(&[], &["sodium.jar"]);
+        for name in ["../sodium.jar", "sub/sodium.jar", "options.txt"] {
+            assert!(
+                set_enabled(&game.0, name, false, false).is_err(),
+                "{}",
+                name
+            );
+        }
+        assert!(set_enabled(&game.0, "missing.jar", false, false).is_err());
+    }
+
+    #[test]
+    fn managed_mods_need_force_and_come_back_on_sync() {
+        let game = GameDir::with(&["fabric-api.jar"], &[]);
+        assert!(list(&game.0)[0].managed);
+        assert!(set_enabled(&game.0, "fabric-api.jar", false, false).is_err());
+        assert!(game.has("fabric-api.jar"));
+
+        set_enabled(&game.0, "fabric-api.jar", false, true).unwrap();
+        let listed = list(&game.0);
+        assert!(listed[0].managed && !listed[0].enabled);
+
+        // Sync takes the disabled copy back instead of downloading a second one
+        mod_sync::reenable_disabled(&game.0.join("mods"), "fabric-api.jar").unwrap();
+        assert!(game.has("fabric-api.jar") && !game.has("fabric-api.jar.disabled"));
+        mod_sync::reenable_disabled(&game.0.join("mods"), "fabric-api.jar").unwrap();
+        assert!(game.has("fabric-api.jar"));
+    }
+
+    #[test]
+    fn safe_mode_round_trip() {
+        let game = GameDir::with(
+            &["fabric-api.jar"],
+            &["sodium.jar", "iris.jar", "old.jar.disabled"],
+        );
+        assert!(!safe_mode_status(&game.0).active);
+
+        let report = enter_safe_mode(&game.0).unwrap();
+        assert_eq!(report.disabled, ["iris.jar", "sodium.jar"]);
+        assert!(game.has("fabric-api.jar"));
+        assert!(game.has("sodium.jar.disabled") && game.has("iris.jar.disabled"));
+        assert!(safe_mode_status(&game.0).active);
+        assert!(enter_safe_mode(&game.0).is_err());
+
+        // Turned back on by hand in the meantime; restore leaves it be
+        set_enabled(&game.0, "iris.jar.disabled", true, false).unwrap();
+
+        let report = restore(&game.0).unwrap();
+        assert!(!report.active);
+        assert!(game.has("sodium.jar") && game.has("iris.jar"));
+        // Disabled before safe mode, so it stays that way
+        assert!(game.has("old.jar.disabled"));
+        assert!(!safe_mode_status(&game.0).active);
+        assert!(restore(&game.0).is_err());
+    }
+
+    #[test]
+    fn safe_mode_needs_player_mods() {
+        let game = GameDir::with(&["fabric-api.jar"], &[]);
+        assert!(enter_safe_mode(&game.0).is_err());
+        assert!(!safe_mode_status(&game.0).active);
+    }
+}
@@ -0,0 +1,629 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::instance::Instance;
+use super::mod_meta::{self, ModMetadata, ScannedMod};
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    MissingDependency,
+    VersionMismatch,
+    Breaks,
+    Duplicate,
+    WrongLoader,
+    Environment,
+    InvalidJar,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModIssue {
+    pub kind: IssueKind,
+    pub filename: String,
+    pub mod_id: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// Problems Fabric refuses to start with.
+    pub errors: Vec<ModIssue>,
+    /// Problems that load but are probably not what the player wants.
+    pub warnings: Vec<ModIssue>,
+}
+
+impl ValidationReport {
+    pub fn summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|i| format!("{}: {}", i.filename, i.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A version as Fabric Loader reads it: any number of numeric components, an optional
+/// pre-release after `-`, and build metadata after `+` which is ignored.
+#[derive(PartialEq, Eq)]
+struct FabricVersion {
+    core: Vec<u64>,
+    pre: Option<Vec<String>>,
+}
+
+impl FabricVersion {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.split('+').next().unwrap_or(s).trim();
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (s, None),
+        };
+        let core = core
+            .split('.')
+            .map(|c| c.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Self {
+            core,
+            pre: pre.map(|p| p.split('.').map(str::to_string).collect()),
+        })
+    }
+
+    fn component(&self, i: usize) -> u64 {
+        self.core.get(i).copied().unwrap_or(0)
+    }
+
+    /// The smallest version above every version that shares the first `len` components.
+    fn bump(&self, len: usize) -> Self {
+        let len = len.max(1);
+        let mut core: Vec<u64> = (0..len).map(|i| self.component(i)).collect();
+        core[len - 1] += 1;
+        Self { core, pre: None }
+    }
+}
+
+impl PartialOrd for FabricVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FabricVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.core.len().max(other.core.len());
+        for i in 0..len {
+            match self.component(i).cmp(&other.component(i)) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => {
+                for (x, y) in a.iter().zip(b.iter()) {
+                    let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    };
+                    if ord != Ordering::Equal {
+                        return ord;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+        }
+    }
+}
+
+/// Evaluate one Fabric version predicate such as `~1.21.11`, `>=0.18.4`, `1.21.x` or
+/// `>=1.0 <2.0` (space-separated terms must all hold).
+pub fn matches(predicate: &str, version: &str) -> bool {
+    let predicate = predicate.trim();
+    if predicate.is_empty() || predicate == "*" {
+        return true;
+    }
+    predicate
+        .split_whitespace()
+        .all(|term| matches_term(term, version))
+}
+
+fn matches_term(term: &str, version: &str) -> bool {
+    let (op, target) = ["<=", ">=", "<", ">", "=", "~", "^"]
+        .iter()
+        .find_map(|op| term.strip_prefix(op).map(|rest| (*op, rest.trim())))
+        .unwrap_or(("=", term));
+
+    // `1.21.x`: every component before the wildcard has to match
+    let components: Vec<&str> = target.split('.').collect();
+    if let Some(wildcard) = components
+        .iter()
+        .position(|c| matches!(*c, "x" | "X" | "*"))
+    {
+        let prefix = components[..wildcard].join(".");
+        let (Some(v), Some(p)) = (FabricVersion::parse(version), FabricVersion::parse(&prefix))
+        else {
+            return wildcard == 0;
+        };
+        return (0..wildcard).all(|i| v.component(i) == p.component(i));
+    }
+
+    let (Some(v), Some(t)) = (FabricVersion::parse(version), FabricVersion::parse(target)) else {
+        // Non-numeric versions (snapshots, odd mod versions) can only be compared for equality
+        return op == "=" && version == target;
+    };
+    match op {
+        ">=" => v >= t,
+        "<=" => v <= t,
+        ">" => v > t,
+        "<" => v < t,
+        "~" => v >= t && v < t.bump(t.core.len().min(2)),
+        "^" => v >= t && v < t.bump(1),
+        _ => v == t,
+    }
+}
+
+fn matches_any(predicates: &[String], version: &str) -> bool {
+    predicates.is_empty() || predicates.iter().any(|p| matches(p, version))
+}
+
+/// Libraries the loader bundles as jar-in-jar, from the loader version that added them.
+/// Their exact versions are not known here.
+const LOADER_BUNDLED: &[(&str, &str, &str)] = &[
+    ("fabric", ">=0.15.0", "mixinextras"),
+    ("quilt", ">=0.23.0", "mixinextras"),
+];
+
+/// Every mod id the game will see, with its version and the jar it comes from.
+/// `java` is only present when the runtime's major version is known.
+fn provided_ids<'a>(
+    mods: &'a [ScannedMod],
+    instance: &'a Instance,
+    java_major: Option<u32>,
+) -> HashMap<String, Vec<(String, &'a str)>> {
+    let mut provided: HashMap<String, Vec<(String, &str)>> = HashMap::new();
+    provided
+        .entry("minecraft".to_string())
+        .or_default()
+        .push((instance.minecraft_version.clone(), "minecraft"));
+    if let Some(major) = java_major {
+        provided
+            .entry("java".to_string())
+            .or_default()
+            .push((major.to_string(), "java"));
+    }
+    if let Some(ref loader) = instance.loader {
+        let ids: &[&str] = match loader.kind.as_str() {
+            "quilt" => &["quilt_loader", "fabricloader"],
+            "fabric" => &["fabricloader"],
+            _ => &[],
+        };
+        for id in ids {
+            provided
+                .entry(id.to_string())
+                .or_default()
+                .push((loader.version.clone(), "loader"));
+        }
+    }
+
+    fn add<'a>(
+        provided: &mut HashMap<String, Vec<(String, &'a str)>>,
+        m: &ModMetadata,
+        filename: &'a str,
+        loader_kind: Option<&str>,
+    ) {
+        let m = m.for_loader(loader_kind);
+        for id in std::iter::once(&m.id).chain(m.provides.iter()) {
+            provided
+                .entry(id.clone())
+                .or_default()
+                .push((m.version.clone(), filename));
+        }
+        for nested in &m.nested {
+            add(provided, nested, filename, loader_kind);
+        }
+    }
+    let loader_kind = instance.loader.as_ref().map(|l| l.kind.as_str());
+    for scanned in mods {
+        if let Some(ref m) = scanned.metadata {
+            add(&mut provided, m, &scanned.filename, loader_kind);
+        }
+    }
+    provided
+}
+
+/// Ids the loader supplies at a version this check cannot see.
+fn bundled_ids(instance: &Instance) -> Vec<&'static str> {
+    let Some(ref loader) = instance.loader else {
+        return Vec::new();
+    };
+    LOADER_BUNDLED
+        .iter()
+        .filter(|(kind, since, _)| *kind == loader.kind && matches(since, &loader.version))
+        .map(|(_, _, id)| *id)
+        .collect()
+}
+
+fn check_relations(
+    report: &mut ValidationReport,
+    m: &ModMetadata,
+    filename: &str,
+    provided: &HashMap<String, Vec<(String, &str)>>,
+    bundled: &[&str],
+    loader_kind: Option<&str>,
+) {
+    let m = m.for_loader(loader_kind);
+    for (dep, predicates) in &m.depends {
+        let issue = match provided.get(dep) {
+            // The runtime version is unknown here; Fabric checks it itself
+            None if dep == "java" => None,
+            None if bundled.contains(&dep.as_str()) => {
+                let any_version = predicates.is_empty()
+                    || predicates.iter().any(|p| matches!(p.trim(), "" | "*"));
+                (!any_version).then(|| {
+                    (
+                        IssueKind::VersionMismatch,
+                        format!(
+                            "{}은(는) {} {}이(가) 필요하지만 로더에 포함된 버전을 확인할 수 없습니다",
+                            m.name,
+                            dep,
+                            predicates.join(" 또는 ")
+                        ),
+                        true,
+                    )
+                })
+            }
+            None => Some((
+                IssueKind::MissingDependency,
+                format!("{}에 필요한 {} 모드가 없습니다", m.name, dep),
+                false,
+            )),
+            Some(found) if !found.iter().any(|(v, _)| matches_any(predicates, v)) => Some((
+                IssueKind::VersionMismatch,
+                format!(
+                    "{}은(는) {} {}이(가) 필요하지만 {}이(가) 설치되어 있습니다",
+                    m.name,
+                    dep,
+                    predicates.join(" 또는 "),
+                    found
+                        .iter()
+                        .map(|(v, _)| v.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                false,
+            )),
+            Some(_) => None,
+        };
+        if let Some((kind, message, warning)) = issue {
+            let issue = ModIssue {
+                kind,
+                filename: filename.to_string(),
+                mod_id: Some(m.id.clone()),
+                message,
+            };
+            if warning {
+                report.warnings.push(issue);
+            } else {
+                report.errors.push(issue);
+            }
+        }
+    }
+
+    for (other, predicates) in &m.breaks {
+        let Some(found) = provided.get(other) else {
+            continue;
+        };
+        for (version, source) in found {
+            if *source != filename && matches_any(predicates, version) {
+                report.errors.push(ModIssue {
+                    kind: IssueKind::Breaks,
+                    filename: filename.to_string(),
+                    mod_id: Some(m.id.clone()),
+                    message: format!(
+                        "{}은(는) {} {}({})과(와) 함께 사용할 수 없습니다",
+                        m.name, other, version, source
+                    ),
+                });
+            }
+        }
+    }
+
+    for nested in &m.nested {
+        check_relations(report, nested, filename, provided, bundled, loader_kind);
+    }
+}
+
+/// `java_major` is the runtime the game will use, when known.
+pub fn validate(
+    mods: &[ScannedMod],
+    instance: &Instance,
+    java_major: Option<u32>,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let provided = provided_ids(mods, instance, java_major);
+    let bundled = bundled_ids(instance);
+    let loader_kind = instance.loader.as_ref().map(|l| l.kind.as_str());
+
+    let mut by_id: HashMap<&str, Vec<&str>> = HashMap::new();
+    for scanned in mods {
+        let Some(m) = scanned.metadata.as_ref().map(|m| m.for_loader(loader_kind)) else {
+            if let Some(ref error) = scanned.error {
+                report.warnings.push(ModIssue {
+                    kind: IssueKind::InvalidJar,
+                    filename: scanned.filename.clone(),
+                    mod_id: None,
+                    message: format!("모드 정보를 읽을 수 없습니다: {}", error),
+                });
+            }
+            continue;
+        };
+        by_id.entry(&m.id).or_default().push(&scanned.filename);
+
+        // Fabric Loader just skips jars it has no metadata for, so this never stops the game
+        if m.loader == "quilt" && !m.fabric_compatible && loader_kind == Some("fabric") {
+            report.warnings.push(ModIssue {
+                kind: IssueKind::WrongLoader,
+                filename: scanned.filename.clone(),
+                mod_id: Some(m.id.clone()),
+                message: format!("{}은(는) Quilt 전용 모드라 로드되지 않습니다", m.name),
+            });
+            continue;
+        }
+        if m.environment == "server" {
+            report.warnings.push(ModIssue {
+                kind: IssueKind::Environment,
+                filename: scanned.filename.clone(),
+                mod_id: Some(m.id.clone()),
+                message: format!(
+                    "{}은(는) 서버 전용 모드라 클라이언트에서 로드되지 않습니다",
+                    m.name
+                ),
+            });
+            continue;
+        }
+        check_relations(
+            &mut report,
+            m,
+            &scanned.filename,
+            &provided,
+            &bundled,
+            loader_kind,
+        );
+    }
+
+    let mut duplicates: Vec<(&str, Vec<&str>)> = by_id
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .collect();
+    duplicates.sort();
+    for (id, files) in duplicates {
+        report.errors.push(ModIssue {
+            kind: IssueKind::Duplicate,
+            filename: files.join(", "),
+            mod_id: Some(id.to_string()),
+            message: format!(
+                "{} 모드가 여러 개 설치되어 있습니다: {}",
+                id,
+                files.join(", ")
+            ),
+        });
+    }
+    report
+}
+
+/// Scan `mods_dir` and validate it against the installed instance.
+pub fn validate_dir(
+    mods_dir: &Path,
+    instance: &Instance,
+    java_major: Option<u32>,
+) -> ValidationReport {
+    let enabled: Vec<ScannedMod> = mod_meta::scan_dir(mods_dir)
+        .into_iter()
+        .filter(|m| m.enabled)
+        .collect();
+    validate(&enabled, instance, java_major)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::instance::InstalledLoader;
+    use std::collections::BTreeMap;
+
+    fn instance(kind: &str, version: &str) -> Instance {
+        Instance {
+            name: None,
+            minecraft_version: "1.21.11".to_string(),
+            loader: Some(InstalledLoader {
+                kind: kind.to_string(),
+                version: version.to_string(),
+                profile_id: format!("{}-loader-{}-1.21.11", kind, version),
+            }),
+            pinned_loader: None,
+            packwiz_url: None,
+        }
+    }
+
+    fn jar(id: &str, loader: &str, depends: &[(&str, &str)]) -> ScannedMod {
+        ScannedMod {
+            filename: format!("{}.jar", id),
+            size: 0,
+            enabled: true,
+            managed: false,
+            metadata: Some(ModMetadata {
+                id: id.to_string(),
+                version: "1.0.0".to_string(),
+                name: id.to_string(),
+                description: None,
+                authors: Vec::new(),
+                loader: loader.to_string(),
+                fabric_compatible: loader == "fabric",
+                environment: "*".to_string(),
+                depends: depends
+                    .iter()
+                    .map(|(id, p)| (id.to_string(), vec![p.to_string()]))
+                    .collect(),
+                breaks: BTreeMap::new(),
+                provides: Vec::new(),
+                icon: None,
+                nested: Vec::new(),
+                fabric: None,
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn comparison_operators() {
+        assert!(matches(">=0.15.0", "0.18.4"));
+        assert!(!matches(">=0.15.0", "0.14.25"));
+        assert!(matches("<=1.21.11", "1.21.11"));
+        assert!(matches(">1.21", "1.21.1"));
+        assert!(!matches("<1.21", "1.21"));
+        assert!(matches("=1.21.11", "1.21.11"));
+        assert!(matches("1.21.11", "1.21.11"));
+        assert!(!matches("1.21.11", "1.21.10"));
+    }
+
+    #[test]
+    fn tilde_allows_patch_updates_only() {
+        assert!(matches("~1.21.11", "1.21.11"));
+        assert!(matches("~1.21.11", "1.21.15"));
+        assert!(!matches("~1.21.11", "1.21.10"));
+        assert!(!matches("~1.21.11", "1.22.0"));
+    }
+
+    #[test]
+    fn caret_allows_minor_updates() {
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "1.2.2"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+    }
+
+    #[test]
+    fn x_ranges_and_wildcards() {
+        assert!(matches("1.21.x", "1.21.11"));
+        assert!(matches("1.21.X", "1.21"));
+        assert!(!matches("1.21.x", "1.20.1"));
+        assert!(matches("1.x", "1.21.11"));
+        assert!(matches("*", "anything"));
+        assert!(matches("", "1.0"));
+    }
+
+    #[test]
+    fn space_separated_terms_must_all_hold() {
+        assert!(matches(">=1.0 <2.0", "1.5"));
+        assert!(!matches(">=1.0 <2.0", "2.0"));
+        assert!(!matches(">=1.0 <2.0", "0.9"));
+    }
+
+    #[test]
+    fn pre_releases_and_build_metadata() {
+        assert!(!matches(">=0.15.0", "0.15.0-beta.1"));
+        assert!(matches(">=0.15.0-beta.1", "0.15.0-beta.2"));
+        assert!(matches(">=0.136.0", "0.136.2+1.21.11"));
+    }
+
+    #[test]
+    fn non_numeric_versions_compare_for_equality() {
+        assert!(matches("25w14craftmine", "25w14craftmine"));
+        assert!(!matches(">=25w14craftmine", "25w14craftmine"));
+    }
+
+    #[test]
+    fn any_predicate_in_a_list_is_enough() {
+        let list = vec!["1.20.x".to_string(), "1.21.x".to_string()];
+        assert!(matches_any(&list, "1.21.11"));
+        assert!(!matches_any(&list, "1.19.4"));
+        assert!(matches_any(&[], "1.19.4"));
+    }
+
+    #[test]
+    fn quilt_jar_with_fabric_metadata_loads_on_fabric() {
+        let mut both = jar("both", "quilt", &[]);
+        both.metadata.as_mut().unwrap().fabric_compatible = true;
+        let only = jar("only", "quilt", &[]);
+        let report = validate(&[both, only], &instance("fabric", "0.18.4"), None);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].mod_id.as_deref(), Some("only"));
+    }
+
+    #[test]
+    fn loader_bundled_ids_are_not_missing() {
+        let m = jar("uses-mixinextras", "fabric", &[("mixinextras", "*")]);
+        let report = validate(&[m], &instance("fabric", "0.18.4"), None);
+        assert!(report.errors.is_empty() && report.warnings.is_empty());
+
+        let m = jar(
+            "needs-new-mixinextras",
+            "fabric",
+            &[("mixinextras", ">=0.5.0")],
+        );
+        let report = validate(&[m], &instance("fabric", "0.18.4"), None);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+
+        let m = jar("uses-mixinextras", "fabric", &[("mixinextras", "*")]);
+        let report = validate(&[m], &instance("fabric", "0.14.25"), None);
+        assert_eq!(report.errors.len(), 1);
+    }
+
+    #[test]
+    fn java_is_checked_only_when_known() {
+        let m = jar("modern", "fabric", &[("java", ">=21")]);
+        let fabric = instance("fabric", "0.18.4");
+        assert!(validate(std::slice::from_ref(&m), &fabric, None)
+            .errors
+            .is_empty());
+        assert!(validate(std::slice::from_ref(&m), &fabric, Some(21))
+            .errors
+            .is_empty());
+        assert_eq!(validate(&[m], &fabric, Some(17)).errors.len(), 1);
+    }
+
+    #[test]
+    fn fabric_reads_fabric_metadata_of_dual_jars() {
+        let path = std::env::temp_dir().join(format!("dual-{}.jar", uuid::Uuid::new_v4()));
+        {
+            use std::io::Write;
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("quilt.mod.json", options).unwrap();
+            zip.write_all(
+                br#"{"quilt_loader":{"id":"dual","version":"1.0.0","depends":["quilt_loader","quilted_fabric_api"]}}"#,
+            )
+            .unwrap();
+            zip.start_file("fabric.mod.json", options).unwrap();
+            zip.write_all(
+                br#"{"id":"dual","version":"1.0.0","depends":{"fabricloader":">=0.15.0"}}"#,
+            )
+            .unwrap();
+            zip.finish().unwrap();
+        }
+        let scanned = mod_meta::scan_file(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(scanned.metadata.as_ref().unwrap().loader, "quilt");
+
+        let report = validate(
+            std::slice::from_ref(&scanned),
+            &instance("fabric", "0.18.4"),
+            None,
+        );
+        assert!(report.errors.is_empty() && report.warnings.is_empty());
+
+        // Quilt still checks its own metadata
+        let report = validate(&[scanned], &instance("quilt", "0.29.0"), None);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(
+            report.errors[0].message,
+            "dual에 필요한 quilted_fabric_api 모드가 없습니다"
+        );
+    }
+}
@@ -0,0 +1,819 @@
+use serde::{Deserialize, Serialize};
+use sha1::Digest;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::download::{emit_progress, get_game_dir};
+use super::http;
+use super::hud_mod;
+use super::instance::Instance;
+use super::mod_sync::{self, LockedFile, ManagedManifest};
+
+pub const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModrinthVersionFile {
+    pub url: String,
+    pub primary: bool,
+    pub filename: String,
+    pub size: u64,
+    #[serde(default)]
+    pub hashes: ModrinthHashes,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ModrinthHashes {
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModrinthDependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    /// `required`, `optional`, `incompatible` or `embedded`.
+    pub dependency_type: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModrinthVersion {
+    pub id: String,
+    #[serde(default)]
+    pub project_id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version_number: String,
+    #[serde(default)]
+    pub version_type: String,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub date_published: String,
+    #[serde(default)]
+    pub dependencies: Vec<ModrinthDependency>,
+    pub files: Vec<ModrinthVersionFile>,
+}
+
+impl ModrinthVersion {
+    pub fn primary_file(&self) -> Option<&ModrinthVersionFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| self.files.first())
+    }
+
+    fn locked_file(&self) -> Result<LockedFile, String> {
+        let file = self
+            .primary_file()
+            .ok_or_else(|| format!("No files in mod version {}", self.id))?;
+        // The name becomes a path under mods/, so it must not climb out of it
+        let is_plain = Path::new(&file.filename)
+            .file_name()
+            .and_then(|n| n.to_str())
+            == Some(file.filename.as_str());
+        if !is_plain {
+            return Err(format!("잘못된 파일 이름입니다: {}", file.filename));
+        }
+        Ok(LockedFile {
+            version_id: Some(self.id.clone()),
+            url: file.url.clone(),
+            filename: file.filename.clone(),
+            size: file.size,
+            sha1: file.hashes.sha1.clone(),
+            sha512: file.hashes.sha512.clone(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<ModSearchHit>,
+    total_hits: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModSearchHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub icon_url: Option<String>,
+    pub downloads: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSearchResult {
+    pub hits: Vec<ModSearchHit>,
+    pub total_hits: u64,
+    pub minecraft_version: String,
+    pub loader: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledModFile {
+    pub project_id: String,
+    pub version_id: String,
+    pub filename: String,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModInstallReport {
+    pub installed: Vec<InstalledModFile>,
+    /// Required dependencies that were already present.
+    pub already_installed: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModUpdate {
+    pub project_id: String,
+    pub from_file: String,
+    pub to_file: String,
+    pub to_version: String,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModUpdateReport {
+    pub updated: Vec<ModUpdate>,
+    pub up_to_date: Vec<String>,
+    /// Jars Modrinth does not know, plus server-managed ones which are never touched here.
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// Where `install_version` looks up dependency versions.
+trait VersionSource {
+    async fn version(&mut self, version_id: &str) -> Result<ModrinthVersion, String>;
+    /// Newest version of `project_id` for the installed game version and loader.
+    async fn latest(&mut self, project_id: &str) -> Result<ModrinthVersion, String>;
+}
+
+struct Api<'a> {
+    client: &'a reqwest::Client,
+    fetcher: &'a mut MetaFetcher,
+    mc_version: &'a str,
+    loader: &'a str,
+}
+
+impl VersionSource for Api<'_> {
+    async fn version(&mut self, version_id: &str) -> Result<ModrinthVersion, String> {
+        get_version(self.client, self.fetcher, version_id).await
+    }
+
+    async fn latest(&mut self, project_id: &str) -> Result<ModrinthVersion, String> {
+        latest_version(
+            self.client,
+            self.fetcher,
+            project_id,
+            self.mc_version,
+            self.loader,
+        )
+        .await
+    }
+}
+
+/// Installed mods by project id: the jar in mods/ and the version it is.
+type InstalledMods = HashMap<String, (PathBuf, ModrinthVersion)>;
+
+/// Game version and loader the browser filters by.
+fn target(game_dir: &Path) -> Result<(String, String), String> {
+    let instance = Instance::load(game_dir).ok_or("설치된 버전이 없습니다")?;
+    let loader = instance
+        .loader
+        .map(|l| l.kind)
+        .ok_or("모드 로더가 설치되어 있지 않습니다")?;
+    Ok((instance.minecraft_version, loader))
+}
+
+pub async fn search(query: &str, offset: u32, limit: u32) -> Result<ModSearchResult, String> {
+    let (mc_version, loader) = target(&get_game_dir())?;
+    let facets = format!(
+        "[[\"project_type:mod\"],[\"categories:{}\"],[\"versions:{}\"]]",
+        loader, mc_version
+    );
+    let response: SearchResponse = http::client()
+        .get(format!("{}/search", MODRINTH_API))
+        .query(&[
+            ("query", query),
+            ("facets", &facets),
+            ("offset", &offset.to_string()),
+            ("limit", &limit.min(100).to_string()),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Modrinth search failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Modrinth search parse failed: {}", e))?;
+    Ok(ModSearchResult {
+        hits: response.hits,
+        total_hits: response.total_hits,
+        minecraft_version: mc_version,
+        loader,
+    })
+}
+
+/// Versions of a project that run on the installed game version and loader, newest first.
+pub async fn compatible_versions(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    project_id: &str,
+    mc_version: &str,
+    loader: &str,
+) -> Result<Vec<ModrinthVersion>, String> {
+    let request = client
+        .get(format!("{}/project/{}/version", MODRINTH_API, project_id))
+        .query(&[
+            ("loaders", format!("[\"{}\"]", loader)),
+            ("game_versions", format!("[\"{}\"]", mc_version)),
+        ]);
+    fetcher
+        .fetch_json(&format!("Modrinth 모드 조회 ({})", project_id), request)
+        .await
+}
+
+pub async fn latest_version(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    project_id: &str,
+    mc_version: &str,
+    loader: &str,
+) -> Result<ModrinthVersion, String> {
+    compatible_versions(client, fetcher, project_id, mc_version, loader)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            format!(
+                "No {} version of {} for MC {}",
+                loader, project_id, mc_version
+            )
+        })
+}
+
+pub async fn get_version(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    version_id: &str,
+) -> Result<ModrinthVersion, String> {
+    fetcher
+        .fetch_json(
+            &format!("Modrinth 버전 조회 ({})", version_id),
+            client.get(format!("{}/version/{}", MODRINTH_API, version_id)),
+        )
+        .await
+}
+
+/// Look up a version of `project_id` by id or by version number (e.g. `0.136.2+1.21.11`).
+pub async fn get_project_version(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    project_id: &str,
+    version: &str,
+) -> Result<ModrinthVersion, String> {
+    // `+` in a version number would otherwise be read back as a space
+    let url = format!(
+        "{}/project/{}/version/{}",
+        MODRINTH_API,
+        project_id,
+        version.replace('+', "%2B")
+    );
+    fetcher
+        .fetch_json(
+            &format!("Modrinth 버전 조회 ({} {})", project_id, version),
+            client.get(url),
+        )
+        .await
+}
+
+pub async fn list_versions(project_id: &str) -> Result<Vec<ModrinthVersion>, String> {
+    let (mc_version, loader) = target(&get_game_dir())?;
+    let mut fetcher = MetaFetcher::new(false);
+    compatible_versions(
+        &http::client(),
+        &mut fetcher,
+        project_id,
+        &mc_version,
+        &loader,
+    )
+    .await
+}
+
+pub fn sha512_file(path: &Path) -> Option<String> {
+    let data = std::fs::read(path).ok()?;
+    Some(hex::encode(sha2::Sha512::digest(&data)))
+}
+
+pub fn installed_jars(mods_dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jar"))
+        .filter_map(|p| sha512_file(&p).map(|hash| (p, hash)))
+        .collect()
+}
+
+/// Identify installed jars by hash; jars Modrinth does not know are absent from the map.
+pub async fn identify(
+    client: &reqwest::Client,
+    jars: &[(PathBuf, String)],
+) -> Result<HashMap<String, ModrinthVersion>, String> {
+    if jars.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let hashes: Vec<&str> = jars.iter().map(|(_, h)| h.as_str()).collect();
+    client
+        .post(format!("{}/version_files", MODRINTH_API))
+        .json(&serde_json::json!({ "hashes": hashes, "algorithm": "sha512" }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Modrinth hash lookup failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Modrinth hash lookup parse failed: {}", e))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Reject a version built for another game version or loader. Quilt also loads Fabric mods.
+fn check_compatible(
+    version: &ModrinthVersion,
+    mc_version: &str,
+    loader: &str,
+) -> Result<(), String> {
+    if !version.game_versions.iter().any(|v| v == mc_version) {
+        return Err(format!(
+            "{} {}은(는) MC {}를 지원하지 않습니다",
+            version.name, version.version_number, mc_version
+        ));
+    }
+    let loader_ok = version
+        .loaders
+        .iter()
+        .any(|l| l == loader || (loader == "quilt" && l == "fabric"));
+    if !loader_ok {
+        return Err(format!(
+            "{} {}은(는) {} 로더용이 아닙니다",
+            version.name, version.version_number, loader
+        ));
+    }
+    Ok(())
+}
+
+/// Install `version_id` and, recursively, its required dependencies.
+///
+/// Nothing is downloaded when any mod in the plan is marked incompatible with an installed
+/// mod or with another mod of the plan.
+pub async fn install_version(
+    app: &AppHandle,
+    version_id: &str,
+) -> Result<ModInstallReport, String> {
+    let game_dir = get_game_dir();
+    let (mc_version, loader) = target(&game_dir)?;
+    let mods_dir = game_dir.join("mods");
+    std::fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(false);
+    let managed = ManagedManifest::load(&mods_dir);
+
+    emit_progress(app, "설치된 모드 확인 중...", "mods", 0, 1, 0.0);
+    let jars = installed_jars(&mods_dir);
+    let known = identify(&client, &jars).await?;
+    let installed: InstalledMods = jars
+        .iter()
+        .filter_map(|(path, hash)| {
+            let version = known.get(hash)?;
+            Some((version.project_id.clone(), (path.clone(), version.clone())))
+        })
+        .collect();
+
+    let root = get_version(&client, &mut fetcher, version_id).await?;
+    if let Some((path, current)) = installed.get(&root.project_id) {
+        if managed.is_managed(&file_name(path)) {
+            return Err(format!(
+                "{}은(는) 서버에서 관리하는 모드입니다",
+                file_name(path)
+            ));
+        }
+        if current.id == root.id {
+            return Ok(ModInstallReport::default());
+        }
+    }
+
+    let mut report = ModInstallReport::default();
+    let mut source = Api {
+        client: &client,
+        fetcher: &mut fetcher,
+        mc_version: &mc_version,
+        loader: &loader,
+    };
+    let plan = plan_install(&mut source, root.clone(), &installed, &mut report).await?;
+
+    for version in &plan {
+        check_compatible(version, &mc_version, &loader)?;
+    }
+
+    let conflicts = find_conflicts(&plan, &installed, &root.project_id);
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "호환되지 않는 모드가 있습니다: {}",
+            conflicts.join(", ")
+        ));
+    }
+
+    let total = plan.len() as u64;
+    for (i, version) in plan.iter().enumerate() {
+        let file = version.locked_file()?;
+        emit_progress(
+            app,
+            &format!("{} 다운로드 중...", file.filename),
+            "mods",
+            i as u64,
+            total,
+            100.0 * i as f64 / total as f64,
+        );
+        mod_sync::download_verified(&client, &file, &mods_dir.join(&file.filename)).await?;
+        if let Some((old, _)) = installed.get(&version.project_id) {
+            if file_name(old) != file.filename {
+                let _ = std::fs::remove_file(old);
+            }
+        }
+        report.installed.push(InstalledModFile {
+            project_id: version.project_id.clone(),
+            version_id: version.id.clone(),
+            filename: file.filename,
+        });
+    }
+    emit_progress(app, "모드 설치 완료!", "mods", total, total, 100.0);
+    Ok(report)
+}
+
+/// `root` and, recursively, its required dependencies that are not installed yet.
+///
+/// Dependencies that are already installed are listed in `report.already_installed`.
+async fn plan_install(
+    source: &mut impl VersionSource,
+    root: ModrinthVersion,
+    installed: &InstalledMods,
+    report: &mut ModInstallReport,
+) -> Result<Vec<ModrinthVersion>, String> {
+    let mut plan: Vec<ModrinthVersion> = Vec::new();
+    let mut queue = vec![root];
+    while let Some(version) = queue.pop() {
+        if plan.iter().any(|p| p.project_id == version.project_id) {
+            continue;
+        }
+        for dep in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "required")
+        {
+            // Some versions name a dependency only by version id; its project comes from the lookup
+            let (project_id, pinned) = match (&dep.project_id, &dep.version_id) {
+                (Some(project_id), _) => (project_id.clone(), None),
+                (None, Some(id)) => {
+                    let version = source.version(id).await?;
+                    (version.project_id.clone(), Some(version))
+                }
+                (None, None) => continue,
+            };
+            if installed.contains_key(&project_id) {
+                if !report.already_installed.contains(&project_id) {
+                    report.already_installed.push(project_id);
+                }
+                continue;
+            }
+            if plan
+                .iter()
+                .chain(queue.iter())
+                .any(|p| p.project_id == project_id)
+            {
+                continue;
+            }
+            let dep_version = match (pinned, &dep.version_id) {
+                (Some(version), _) => version,
+                (None, Some(id)) => source.version(id).await?,
+                (None, None) => source.latest(&project_id).await?,
+            };
+            queue.push(dep_version);
+        }
+        plan.push(version);
+    }
+
+    Ok(plan)
+}
+
+/// Pairs of mods in `plan` or already installed that are marked incompatible with each other.
+fn find_conflicts(
+    plan: &[ModrinthVersion],
+    installed: &InstalledMods,
+    root_project: &str,
+) -> Vec<String> {
+    let planned: HashSet<&str> = plan.iter().map(|v| v.project_id.as_str()).collect();
+    let mut conflicts = Vec::new();
+    for version in plan {
+        for dep in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "incompatible")
+        {
+            let Some(ref project_id) = dep.project_id else {
+                continue;
+            };
+            if let Some((path, _)) = installed.get(project_id) {
+                conflicts.push(format!("{} ↔ {}", version.name, file_name(path)));
+            } else if planned.contains(project_id.as_str()) {
+                conflicts.push(format!("{} ↔ {}", version.name, project_id));
+            }
+        }
+    }
+    for (path, version) in installed.values() {
+        // The root's old version is replaced, so its own incompatibilities no longer apply
+        if version.project_id == root_project {
+            continue;
+        }
+        for dep in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "incompatible")
+        {
+            if dep
+                .project_id
+                .as_deref()
+                .is_some_and(|p| planned.contains(p))
+            {
+                conflicts.push(format!(
+                    "{} ↔ {}",
+                    file_name(path),
+                    dep.project_id.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+    }
+    conflicts
+}
+
+/// Move every installed Modrinth mod to its newest compatible version.
+///
+/// Server-managed jars and the HUD mod follow the server configuration instead.
+pub async fn update_all(app: &AppHandle) -> Result<ModUpdateReport, String> {
+    let game_dir = get_game_dir();
+    let (mc_version, loader) = target(&game_dir)?;
+    let mods_dir = game_dir.join("mods");
+    let client = http::client();
+    let managed = ManagedManifest::load(&mods_dir);
+    let hud = hud_mod::status(&game_dir).filename;
+
+    let mut report = ModUpdateReport::default();
+    let mut jars = Vec::new();
+    for (path, hash) in installed_jars(&mods_dir) {
+        let name = file_name(&path);
+        if managed.is_managed(&name) || hud.as_deref() == Some(name.as_str()) {
+            report.skipped.push(name);
+        } else {
+            jars.push((path, hash));
+        }
+    }
+    if jars.is_empty() {
+        return Ok(report);
+    }
+
+    emit_progress(app, "모드 업데이트 확인 중...", "mods", 0, 1, 0.0);
+    let hashes: Vec<&str> = jars.iter().map(|(_, h)| h.as_str()).collect();
+    let latest: HashMap<String, ModrinthVersion> = client
+        .post(format!("{}/version_files/update", MODRINTH_API))
+        .json(&serde_json::json!({
+            "hashes": hashes,
+            "algorithm": "sha512",
+            "loaders": [loader],
+            "game_versions": [mc_version],
+        }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Modrinth update lookup failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Modrinth update lookup parse failed: {}", e))?;
+
+    let total = jars.len() as u64;
+    for (i, (path, hash)) in jars.iter().enumerate() {
+        let name = file_name(path);
+        let Some(version) = latest.get(hash) else {
+            report.skipped.push(name);
+            continue;
+        };
+        let is_current = version.files.iter().any(|f| {
+            f.hashes
+                .sha512
+                .as_deref()
+                .is_some_and(|h| h.eq_ignore_ascii_case(hash))
+        });
+        if is_current {
+            report.up_to_date.push(name);
+            continue;
+        }
+
+        emit_progress(
+            app,
+            &format!("{} 업데이트 중...", name),
+            "mods",
+            i as u64,
+            total,
+            100.0 * i as f64 / total as f64,
+        );
+        let result = async {
+            let file = version.locked_file()?;
+            mod_sync::download_verified(&client, &file, &mods_dir.join(&file.filename)).await?;
+            if file.filename != name {
+                std::fs::remove_file(path).map_err(|e| format!("Remove {} failed: {}", name, e))?;
+            }
+            Ok::<_, String>(file.filename)
+        }
+        .await;
+        match result {
+            Ok(to_file) => report.updated.push(ModUpdate {
+                project_id: version.project_id.clone(),
+                from_file: name,
+                to_file,
+                to_version: version.version_number.clone(),
+            }),
+            Err(e) => report.failed.push(format!("{}: {}", name, e)),
+        }
+    }
+    emit_progress(app, "모드 업데이트 완료!", "mods", total, total, 100.0);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed `GET /version/{id}` responses.
+    const VERSIONS: &str = r#"[
+        {"id": "v-extra-2", "project_id": "extra", "name": "Sodium Extra", "version_number": "0.6.2",
+         "dependencies": [
+            {"version_id": null, "project_id": "sodium", "dependency_type": "required"},
+            {"version_id": "v-api-1", "project_id": null, "dependency_type": "required"},
+            {"version_id": null, "project_id": "cloth", "dependency_type": "required"},
+            {"version_id": null, "project_id": "modmenu", "dependency_type": "optional"},
+            {"version_id": null, "project_id": "optifabric", "dependency_type": "incompatible"}
+         ],
+         "files": [{"url": "https://cdn.modrinth.com/extra.jar", "primary": true, "filename": "sodium-extra-0.6.2.jar", "size": 1}]},
+        {"id": "v-extra-1", "project_id": "extra", "name": "Sodium Extra", "version_number": "0.6.1",
+         "dependencies": [{"version_id": null, "project_id": "fabric-api", "dependency_type": "incompatible"}],
+         "files": [{"url": "https://cdn.modrinth.com/extra-old.jar", "primary": true, "filename": "sodium-extra-0.6.1.jar", "size": 1}]},
+        {"id": "v-sodium", "project_id": "sodium", "name": "Sodium", "version_number": "0.7.0",
+         "dependencies": [{"version_id": null, "project_id": "extra", "dependency_type": "required"}],
+         "files": [{"url": "https://cdn.modrinth.com/sodium.jar", "primary": true, "filename": "sodium-0.7.0.jar", "size": 1}]},
+        {"id": "v-api-1", "project_id": "fabric-api", "name": "Fabric API", "version_number": "0.136.2",
+         "files": [{"url": "https://cdn.modrinth.com/api.jar", "primary": true, "filename": "fabric-api-0.136.2.jar", "size": 1}]},
+        {"id": "v-cloth", "project_id": "cloth", "name": "Cloth Config", "version_number": "20.0.0",
+         "dependencies": [{"version_id": null, "project_id": "sodium", "dependency_type": "incompatible"}],
+         "files": [{"url": "https://cdn.modrinth.com/cloth.jar", "primary": true, "filename": "cloth-config-20.0.0.jar", "size": 1}]},
+        {"id": "v-optifabric", "project_id": "optifabric", "name": "OptiFabric", "version_number": "1.14.3",
+         "files": [{"url": "https://cdn.modrinth.com/optifabric.jar", "primary": true, "filename": "optifabric-1.14.3.jar", "size": 1}]}
+    ]"#;
+
+    struct Fixtures(Vec<ModrinthVersion>);
+
+    impl Fixtures {
+        fn load() -> Self {
+            Fixtures(serde_json::from_str(VERSIONS).unwrap())
+        }
+
+        fn get(&self, id: &str) -> ModrinthVersion {
+            self.0.iter().find(|v| v.id == id).unwrap().clone()
+        }
+    }
+
+    impl VersionSource for Fixtures {
+        async fn version(&mut self, version_id: &str) -> Result<ModrinthVersion, String> {
+            self.0
+                .iter()
+                .find(|v| v.id == version_id)
+                .cloned()
+                .ok_or_else(|| format!("unknown version {}", version_id))
+        }
+
+        async fn latest(&mut self, project_id: &str) -> Result<ModrinthVersion, String> {
+            self.0
+                .iter()
+                .find(|v| v.project_id == project_id)
+                .cloned()
+                .ok_or_else(|| format!("unknown project {}", project_id))
+        }
+    }
+
+    fn installed(fixtures: &Fixtures, ids: &[&str]) -> InstalledMods {
+        ids.iter()
+            .map(|id| {
+                let version = fixtures.get(id);
+                let path = PathBuf::from("mods").join(&version.primary_file().unwrap().filename);
+                (version.project_id.clone(), (path, version))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn resolves_required_dependencies() {
+        let mut fixtures = Fixtures::load();
+        let root = fixtures.get("v-extra-2");
+        let installed = installed(&fixtures, &["v-cloth"]);
+        let mut report = ModInstallReport::default();
+
+        let plan = plan_install(&mut fixtures, root, &installed, &mut report)
+            .await
+            .unwrap();
+        let mut ids: Vec<&str> = plan.iter().map(|v| v.id.as_str()).collect();
+        ids.sort();
+        // Sodium's dependency back on the root does not queue it twice; optional ones are left out
+        assert_eq!(ids, ["v-api-1", "v-extra-2", "v-sodium"]);
+        assert_eq!(report.already_installed, ["cloth"]);
+    }
+
+    #[tokio::test]
+    async fn fails_on_unknown_dependency() {
+        let mut fixtures = Fixtures::load();
+        let mut root = fixtures.get("v-api-1");
+        root.dependencies.push(ModrinthDependency {
+            version_id: Some("v-missing".into()),
+            project_id: None,
+            dependency_type: "required".into(),
+        });
+        let result = plan_install(
+            &mut fixtures,
+            root,
+            &InstalledMods::new(),
+            &mut ModInstallReport::default(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn finds_incompatible_mods() {
+        let fixtures = Fixtures::load();
+        let plan = vec![
+            fixtures.get("v-extra-2"),
+            fixtures.get("v-sodium"),
+            fixtures.get("v-api-1"),
+        ];
+        let installed = installed(&fixtures, &["v-cloth", "v-optifabric", "v-extra-1"]);
+
+        let mut conflicts = find_conflicts(&plan, &installed, "extra");
+        conflicts.sort();
+        // The old Sodium Extra being replaced does not count against Fabric API
+        assert_eq!(
+            conflicts,
+            [
+                "Sodium Extra ↔ optifabric-1.14.3.jar",
+                "cloth-config-20.0.0.jar ↔ sodium",
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_incompatible_mods_within_plan() {
+        let fixtures = Fixtures::load();
+        let plan = vec![fixtures.get("v-cloth"), fixtures.get("v-sodium")];
+        let conflicts = find_conflicts(&plan, &InstalledMods::new(), "cloth");
+        assert_eq!(conflicts, ["Cloth Config ↔ sodium"]);
+        assert!(find_conflicts(&plan[1..], &InstalledMods::new(), "sodium").is_empty());
+    }
+
+    #[test]
+    fn rejects_unsafe_filenames() {
+        let fixtures = Fixtures::load();
+        assert!(fixtures.get("v-sodium").locked_file().is_ok());
+        for name in ["../sodium.jar", "sub/sodium.jar", "/tmp/sodium.jar", ""] {
+            let mut version = fixtures.get("v-sodium");
+            version.files[0].filename = name.into();
+            assert!(version.locked_file().is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::disk::InstallError;
+use super::download::{self, emit_progress};
+use super::http;
+use super::instance::{self, Instance};
+use super::mod_sync;
+use super::modrinth;
+use super::remote_config::LoaderSpec;
+
+const INDEX_FILE: &str = "modrinth.index.json";
+
+/// Largest pack manifest read into memory; thousands of file entries stay well below.
+const MAX_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+
+/// `dependencies` key of each loader in `modrinth.index.json`.
+const LOADER_KEYS: [(&str, &str); 4] = [
+    ("fabric-loader", "fabric"),
+    ("quilt-loader", "quilt"),
+    ("neoforge", "neoforge"),
+    ("forge", "forge"),
+];
+
+/// Directories whose files are looked up on Modrinth when exporting.
+const REFERENCED_DIRS: [&str; 3] = ["mods", "resourcepacks", "shaderpacks"];
+
+/// Directories copied into `overrides/` as they are when exporting.
+const OVERRIDE_DIRS: [&str; 1] = ["config"];
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MrpackIndex {
+    format_version: u32,
+    game: String,
+    version_id: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    files: Vec<MrpackFile>,
+    dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MrpackFile {
+    path: String,
+    hashes: MrpackHashes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    env: Option<MrpackEnv>,
+    downloads: Vec<String>,
+    #[serde(default)]
+    file_size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MrpackHashes {
+    sha1: Option<String>,
+    sha512: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MrpackEnv {
+    client: String,
+    server: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackImportReport {
+    pub instance_id: String,
+    pub name: String,
+    pub game_dir: String,
+    pub minecraft_version: String,
+    pub loader: Option<String>,
+    pub downloaded: usize,
+    pub overrides: usize,
+    /// Files the pack marks as unsupported on the client.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackExportReport {
+    pub path: String,
+    /// Files referenced by their Modrinth download URL.
+    pub referenced: usize,
+    /// Files stored inside the pack under `overrides/`.
+    pub overrides: usize,
+}
+
+/// `path` as a relative path that cannot leave the directory it is joined to.
+pub fn safe_relative(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let safe = !path.as_os_str().is_empty()
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    safe.then(|| path.to_path_buf())
+}
+
+pub fn open_pack(pack_path: &Path) -> Result<zip::ZipArchive<std::fs::File>, String> {
+    let file = std::fs::File::open(pack_path).map_err(|e| format!("Open pack failed: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("Invalid mrpack: {}", e))
+}
+
+/// Read the pack's manifest `name` whole, giving up on entries that inflate past
+/// `MAX_MANIFEST_BYTES` whatever their header says.
+pub fn read_manifest(pack_path: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let mut archive = open_pack(pack_path)?;
+    let entry = archive
+        .by_name(name)
+        .map_err(|_| format!("{}이(가) 없습니다", name))?;
+    let mut data = Vec::new();
+    entry
+        .take(MAX_MANIFEST_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Read {} failed: {}", name, e))?;
+    if data.len() as u64 > MAX_MANIFEST_BYTES {
+        return Err(format!("{}이(가) 너무 큽니다", name));
+    }
+    Ok(data)
+}
+
+fn read_index(pack_path: &Path) -> Result<MrpackIndex, String> {
+    let json = read_manifest(pack_path, INDEX_FILE)?;
+    serde_json::from_slice(&json).map_err(|e| format!("{} parse failed: {}", INDEX_FILE, e))
+}
+
+fn loader_from_dependencies(dependencies: &BTreeMap<String, String>) -> Result<LoaderSpec, String> {
+    LOADER_KEYS
+        .iter()
+        .find_map(|(key, kind)| {
+            dependencies.get(*key).map(|version| LoaderSpec {
+                kind: kind.to_string(),
+                version: Some(version.clone()),
+            })
+        })
+        .ok_or_else(|| "모드팩에 로더 정보가 없습니다".to_string())
+}
+
+/// Extract the entries under each of `prefixes` into `dir`, later prefixes overwriting earlier ones.
+pub fn extract_overrides(pack_path: &Path, prefixes: &[&str], dir: &Path) -> Result<usize, String> {
+    let mut archive = open_pack(pack_path)?;
+    let mut count = 0;
+    for prefix in prefixes {
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            let Some(relative) = entry.name().strip_prefix(*prefix).and_then(safe_relative) else {
+                continue;
+            };
+            let target = dir.join(relative);
+            if entry.is_dir() {
+                std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let mut out = std::fs::File::create(&target)
+                .map_err(|e| format!("Write {} failed: {}", target.display(), e))?;
+            std::io::copy(&mut entry, &mut out)
+                .map_err(|e| format!("Write {} failed: {}", target.display(), e))?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Download one pack file, trying each mirror until one matches the pack's hashes.
+async fn download_pack_file(
+    client: &reqwest::Client,
+    file: &MrpackFile,
+    target: &Path,
+) -> Result<(), String> {
+    let mut last_error = format!("{}: 다운로드 주소가 없습니다", file.path);
+    for url in &file.downloads {
+        let data = match download::download_bytes(client, url).await {
+            Ok(data) => data,
+            Err(e) => {
+                last_error = e;
+                continue;
+            }
+        };
+        match mod_sync::verify_hashes(
+            &data,
+            file.hashes.sha1.as_deref(),
+            file.hashes.sha512.as_deref(),
+        ) {
+            Ok(()) => {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                return std::fs::write(target, &data)
+                    .map_err(|e| format!("Write file failed: {}", e));
+            }
+            Err(e) => last_error = format!("{}: {}", file.path, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// Import a `.mrpack` into a new instance, installing its Minecraft and loader versions.
+///
+/// The instance directory is removed again when anything fails.
+pub async fn import(
+    app: &AppHandle,
+    pack_path: &Path,
+    name: Option<String>,
+) -> Result<MrpackImportReport, InstallError> {
+    let index = read_index(pack_path)?;
+    if index.game != "minecraft" {
+        return Err(format!("지원하지 않는 게임입니다: {}", index.game).into());
+    }
+    let mc_version = index
+        .dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or("모드팩에 Minecraft 버전 정보가 없습니다")?;
+    let loader = loader_from_dependencies(&index.dependencies)?;
+
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| index.name.clone());
+    let (instance_id, dir) = instance::create_dir(&name)?;
+
+    let result = import_into(app, pack_path, &index, &mc_version, &loader, &dir).await;
+    let (mut instance, downloaded, overrides, skipped) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    instance.name = Some(name.clone());
+    instance.save(&dir)?;
+
+    emit_progress(app, "모드팩 가져오기 완료!", "complete", 1, 1, 100.0);
+    Ok(MrpackImportReport {
+        instance_id,
+        name,
+        game_dir: dir.to_string_lossy().to_string(),
+        minecraft_version: mc_version,
+        loader: instance.loader.map(|l| format!("{} {}", l.kind, l.version)),
+        downloaded,
+        overrides,
+        skipped,
+    })
+}
+
+async fn import_into(
+    app: &AppHandle,
+    pack_path: &Path,
+    index: &MrpackIndex,
+    mc_version: &str,
+    loader: &LoaderSpec,
+    dir: &Path,
+) -> Result<(Instance, usize, usize, Vec<String>), InstallError> {
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(false);
+
+    let mut skipped = Vec::new();
+    let mut files = Vec::new();
+    for file in &index.files {
+        let relative = safe_relative(&file.path)
+            .ok_or_else(|| format!("잘못된 파일 경로입니다: {}", file.path))?;
+        if file
+            .env
+            .as_ref()
+            .is_some_and(|env| env.client == "unsupported")
+        {
+            skipped.push(file.path.clone());
+        } else {
+            files.push((file, dir.join(relative)));
+        }
+    }
+    let pack_bytes: u64 = files.iter().map(|(f, _)| f.file_size).sum();
+
+    let instance =
+        download::install_game(app, &client, &mut fetcher, mc_version, loader, pack_bytes).await?;
+
+    let total = files.len() as u64;
+    for (i, (file, target)) in files.iter().enumerate() {
+        emit_progress(
+            app,
+            &format!("모드팩 파일: {}", file.path),
+            "mrpack",
+            i as u64,
+            total,
+            100.0 * i as f64 / total.max(1) as f64,
+        );
+        download_pack_file(&client, file, target).await?;
+    }
+
+    emit_progress(
+        app,
+        "덮어쓰기 파일 적용 중...",
+        "mrpack",
+        total,
+        total,
+        100.0,
+    );
+    let overrides = extract_overrides(pack_path, &["overrides/", "client-overrides/"], dir)?;
+    Ok((instance, files.len(), overrides, skipped))
+}
+
+fn is_launcher_file(name: &str) -> bool {
+    name.starts_with(".nyaru-") || name.ends_with(".part")
+}
+
+/// Every regular file under `dir`, relative to `base`, skipping launcher bookkeeping.
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if !is_launcher_file(&entry.file_name().to_string_lossy()) {
+            if let Ok(relative) = path.strip_prefix(base) {
+                out.push(relative.to_path_buf());
+            }
+        }
+    }
+}
+
+/// Modrinth-style path with forward slashes.
+pub fn pack_path_of(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Export an instance as `.mrpack`: files Modrinth knows are referenced by URL, everything
+/// else (and `config/`) goes into `overrides/`. Disabled mods are left out.
+pub async fn export(
+    app: &AppHandle,
+    game_dir: &Path,
+    output: &Path,
+    name: &str,
+    version_id: &str,
+) -> Result<MrpackExportReport, String> {
+    let instance = Instance::load(game_dir).ok_or("설치 정보가 없습니다")?;
+    let mut dependencies = BTreeMap::new();
+    dependencies.insert("minecraft".to_string(), instance.minecraft_version.clone());
+    let loader = instance
+        .loader
+        .as_ref()
+        .ok_or("모드 로더가 설치되어 있지 않습니다")?;
+    let key = LOADER_KEYS
+        .iter()
+        .find(|(_, kind)| *kind == loader.kind)
+        .map(|(key, _)| *key)
+        .ok_or_else(|| format!("지원하지 않는 로더입니다: {}", loader.kind))?;
+    dependencies.insert(key.to_string(), loader.version.clone());
+
+    let mut candidates = Vec::new();
+    for dir in REFERENCED_DIRS {
+        let Ok(entries) = std::fs::read_dir(game_dir.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            let wanted = if dir == "mods" {
+                file_name.ends_with(".jar")
+            } else {
+                file_name.ends_with(".zip")
+            };
+            if !wanted || !path.is_file() {
+                continue;
+            }
+            if let Some(hash) = modrinth::sha512_file(&path) {
+                candidates.push((Path::new(dir).join(&file_name), hash));
+            }
+        }
+    }
+
+    emit_progress(app, "Modrinth에서 파일 확인 중...", "mrpack", 0, 1, 0.0);
+    let known = modrinth::identify(&http::client(), &candidates).await?;
+
+    let mut files = Vec::new();
+    let mut overrides = Vec::new();
+    for (relative, hash) in candidates {
+        let remote = known.get(&hash).and_then(|version| {
+            version.files.iter().find(|f| {
+                f.hashes
+                    .sha512
+                    .as_deref()
+                    .is_some_and(|h| h.eq_ignore_ascii_case(&hash))
+            })
+        });
+        match remote {
+            Some(file) => files.push(MrpackFile {
+                path: pack_path_of(&relative),
+                hashes: MrpackHashes {
+                    sha1: file.hashes.sha1.clone(),
+                    sha512: Some(hash),
+                },
+                env: None,
+                downloads: vec![file.url.clone()],
+                file_size: file.size,
+            }),
+            None => overrides.push(relative),
+        }
+    }
+    for dir in OVERRIDE_DIRS {
+        collect_files(game_dir, &game_dir.join(dir), &mut overrides);
+    }
+
+    let index = MrpackIndex {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: version_id.to_string(),
+        name: name.to_string(),
+        summary: None,
+        files,
+        dependencies,
+    };
+    emit_progress(app, "모드팩 파일 쓰는 중...", "mrpack", 1, 1, 90.0);
+    write_pack(output, &index, game_dir, &overrides)?;
+
+    emit_progress(app, "모드팩 내보내기 완료!", "complete", 1, 1, 100.0);
+    Ok(MrpackExportReport {
+        path: output.to_string_lossy().to_string(),
+        referenced: index.files.len(),
+        overrides: overrides.len(),
+    })
+}
+
+fn write_pack(
+    output: &Path,
+    index: &MrpackIndex,
+    game_dir: &Path,
+    overrides: &[PathBuf],
+) -> Result<(), String> {
+    let file = std::fs::File::create(output).map_err(|e| format!("Create pack failed: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let json = serde_json::to_vec_pretty(index).map_err(|e| e.to_string())?;
+    zip.start_file(INDEX_FILE, options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+
+    for relative in overrides {
+        let data = std::fs::read(game_dir.join(relative))
+            .map_err(|e| format!("Read {} failed: {}", relative.display(), e))?;
+        zip.start_file(format!("overrides/{}", pack_path_of(relative)), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&data).map_err(|e| e.to_string())?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Write pack failed: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,486 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha1::Digest;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::AppHandle;
+use url::Url;
+
+use super::cache::MetaFetcher;
+use super::disk::InstallError;
+use super::download::{self, emit_progress};
+use super::http;
+use super::instance::{self, Instance};
+use super::mod_sync::{ModSyncFailure, ModSyncReport};
+use super::modrinth;
+use super::mrpack;
+use super::remote_config::LoaderSpec;
+
+/// What the last sync wrote, so unchanged files are skipped and dropped ones removed.
+const STATE_FILE: &str = ".nyaru-packwiz.json";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PackToml {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    index: IndexRef,
+    versions: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IndexRef {
+    file: String,
+    hash_format: String,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IndexToml {
+    hash_format: String,
+    #[serde(default)]
+    files: Vec<IndexFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct IndexFile {
+    file: String,
+    hash: String,
+    #[serde(default)]
+    hash_format: Option<String>,
+    /// A `.pw.toml` describing where to download the real file.
+    #[serde(default)]
+    metafile: bool,
+    /// Written once and then left to the player, e.g. option files.
+    #[serde(default)]
+    preserve: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct MetaFile {
+    name: String,
+    filename: String,
+    #[serde(default = "default_side")]
+    side: String,
+    download: MetaDownload,
+    #[serde(default)]
+    update: Option<MetaUpdate>,
+    #[serde(default)]
+    option: Option<MetaOption>,
+}
+
+fn default_side() -> String {
+    "both".to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct MetaDownload {
+    #[serde(default)]
+    url: Option<String>,
+    hash_format: String,
+    hash: String,
+    #[serde(default)]
+    mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MetaUpdate {
+    modrinth: Option<MetaModrinth>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct MetaModrinth {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct MetaOption {
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    default: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PackwizState {
+    index_hash: Option<String>,
+    /// Index path to what was written for it.
+    files: BTreeMap<String, SyncedFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SyncedFile {
+    hash: String,
+    /// Path relative to the instance directory.
+    target: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackwizSyncReport {
+    pub pack_name: String,
+    pub pack_version: Option<String>,
+    pub files: ModSyncReport,
+    /// Server-only and opt-in files that were not installed.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackwizInstanceReport {
+    pub instance_id: String,
+    pub game_dir: String,
+    pub sync: PackwizSyncReport,
+}
+
+/// Check `data` against a packwiz hash; packwiz writes sha256 by default.
+fn verify(data: &[u8], format: &str, expected: &str) -> Result<(), String> {
+    let actual = match format {
+        "sha1" => hex::encode(sha1::Sha1::digest(data)),
+        "sha256" => hex::encode(sha2::Sha256::digest(data)),
+        "sha512" => hex::encode(sha2::Sha512::digest(data)),
+        other => return Err(format!("지원하지 않는 해시 형식입니다: {}", other)),
+    };
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(format!("{} 해시가 일치하지 않습니다", format));
+    }
+    Ok(())
+}
+
+fn parse_toml<T: DeserializeOwned>(data: &[u8], what: &str) -> Result<T, String> {
+    let text = std::str::from_utf8(data).map_err(|e| format!("{} parse failed: {}", what, e))?;
+    toml::from_str(text).map_err(|e| format!("{} parse failed: {}", what, e))
+}
+
+fn join(base: &Url, path: &str) -> Result<Url, String> {
+    base.join(path)
+        .map_err(|e| format!("Invalid URL {}: {}", path, e))
+}
+
+async fn fetch_pack(client: &reqwest::Client, pack_url: &str) -> Result<(Url, PackToml), String> {
+    let url = Url::parse(pack_url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let data = download::download_bytes(client, url.as_str()).await?;
+    Ok((url, parse_toml(&data, "pack.toml")?))
+}
+
+/// `[versions]` → loader to install; packs without a loader are not supported.
+fn loader_spec(pack: &PackToml) -> Result<LoaderSpec, String> {
+    ["fabric", "quilt", "neoforge", "forge"]
+        .iter()
+        .find_map(|kind| {
+            pack.versions.get(*kind).map(|version| LoaderSpec {
+                kind: kind.to_string(),
+                version: Some(version.clone()),
+            })
+        })
+        .ok_or_else(|| "pack.toml에 로더 정보가 없습니다".to_string())
+}
+
+fn load_state(game_dir: &Path) -> PackwizState {
+    std::fs::read(game_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(game_dir: &Path, state: &PackwizState) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(state).map_err(|e| e.to_string())?;
+    std::fs::write(game_dir.join(STATE_FILE), json)
+        .map_err(|e| format!("Write {} failed: {}", STATE_FILE, e))
+}
+
+fn write_file(target: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(target, data).map_err(|e| format!("Write file failed: {}", e))
+}
+
+enum Outcome {
+    Written(SyncedFile),
+    Skipped,
+}
+
+/// Sync one index entry; the returned target is relative to `game_dir`.
+async fn sync_entry(
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    index_url: &Url,
+    index_format: &str,
+    entry: &IndexFile,
+    relative: &Path,
+    game_dir: &Path,
+) -> Result<Outcome, String> {
+    let format = entry.hash_format.as_deref().unwrap_or(index_format);
+    let data = download::download_bytes(client, join(index_url, &entry.file)?.as_str()).await?;
+    verify(&data, format, &entry.hash)?;
+
+    if !entry.metafile {
+        let target = game_dir.join(relative);
+        if !(entry.preserve && target.exists()) {
+            write_file(&target, &data)?;
+        }
+        return Ok(Outcome::Written(SyncedFile {
+            hash: entry.hash.clone(),
+            target: entry.file.clone(),
+        }));
+    }
+
+    let meta: MetaFile = parse_toml(&data, &entry.file)?;
+    let opted_out = meta
+        .option
+        .as_ref()
+        .is_some_and(|o| o.optional && !o.default);
+    if meta.side == "server" || opted_out {
+        return Ok(Outcome::Skipped);
+    }
+
+    // The real file sits next to its metafile
+    let target_relative = relative
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(&meta.filename);
+    if mrpack::safe_relative(&target_relative.to_string_lossy()).is_none()
+        || Path::new(&meta.filename)
+            .file_name()
+            .and_then(|n| n.to_str())
+            != Some(meta.filename.as_str())
+    {
+        return Err(format!("잘못된 파일 이름입니다: {}", meta.filename));
+    }
+
+    let url = match (meta.download.url, meta.download.mode.as_deref()) {
+        (Some(url), None | Some("url")) => url,
+        (None, None | Some("url")) => {
+            let version = meta
+                .update
+                .and_then(|u| u.modrinth)
+                .ok_or_else(|| format!("{}: 다운로드 주소가 없습니다", meta.name))?
+                .version;
+            modrinth::get_version(client, fetcher, &version)
+                .await?
+                .primary_file()
+                .map(|f| f.url.clone())
+                .ok_or_else(|| format!("{}: Modrinth 파일이 없습니다", meta.name))?
+        }
+        (_, Some(mode)) => {
+            return Err(format!(
+                "{}: 지원하지 않는 다운로드 방식입니다 ({})",
+                meta.name, mode
+            ))
+        }
+    };
+    let data = download::download_bytes(client, &url).await?;
+    verify(&data, &meta.download.hash_format, &meta.download.hash)
+        .map_err(|e| format!("{}: {}", meta.name, e))?;
+    write_file(&game_dir.join(&target_relative), &data)?;
+    Ok(Outcome::Written(SyncedFile {
+        hash: entry.hash.clone(),
+        target: mrpack::pack_path_of(&target_relative),
+    }))
+}
+
+/// Bring `game_dir` in line with the pack's index, downloading only entries whose hash
+/// changed since the last sync. Files the pack never wrote are left alone.
+async fn sync_files(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    fetcher: &mut MetaFetcher,
+    game_dir: &Path,
+    pack_url: &Url,
+    pack: &PackToml,
+) -> Result<PackwizSyncReport, String> {
+    let index_url = join(pack_url, &pack.index.file)?;
+    let data = download::download_bytes(client, index_url.as_str()).await?;
+    verify(&data, &pack.index.hash_format, &pack.index.hash)
+        .map_err(|e| format!("index.toml: {}", e))?;
+    let index: IndexToml = parse_toml(&data, "index.toml")?;
+
+    let old = load_state(game_dir);
+    let mut state = PackwizState {
+        index_hash: Some(pack.index.hash.clone()),
+        files: BTreeMap::new(),
+    };
+    let mut report = PackwizSyncReport {
+        pack_name: pack.name.clone(),
+        pack_version: pack.version.clone(),
+        files: ModSyncReport::default(),
+        skipped: Vec::new(),
+    };
+
+    let total = index.files.len() as u64;
+    for (i, entry) in index.files.iter().enumerate() {
+        let Some(relative) = mrpack::safe_relative(&entry.file) else {
+            report.files.failed.push(ModSyncFailure {
+                id: entry.file.clone(),
+                error: "잘못된 파일 경로입니다".to_string(),
+            });
+            continue;
+        };
+        let previous = old.files.get(&entry.file);
+        if let Some(previous) = previous {
+            if previous.hash == entry.hash && game_dir.join(&previous.target).exists() {
+                report.files.unchanged.push(previous.target.clone());
+                state.files.insert(entry.file.clone(), previous.clone());
+                continue;
+            }
+        }
+
+        emit_progress(
+            app,
+            &format!("packwiz: {}", entry.file),
+            "packwiz",
+            i as u64,
+            total,
+            100.0 * i as f64 / total.max(1) as f64,
+        );
+        match sync_entry(
+            client,
+            fetcher,
+            &index_url,
+            &index.hash_format,
+            entry,
+            &relative,
+            game_dir,
+        )
+        .await
+        {
+            Ok(Outcome::Written(synced)) => {
+                if previous.is_some() {
+                    report.files.updated.push(synced.target.clone());
+                } else {
+                    report.files.added.push(synced.target.clone());
+                }
+                state.files.insert(entry.file.clone(), synced);
+            }
+            Ok(Outcome::Skipped) => report.skipped.push(entry.file.clone()),
+            Err(error) => {
+                // Keep the old file and retry on the next sync
+                if let Some(previous) = previous {
+                    state.files.insert(entry.file.clone(), previous.clone());
+                }
+                report.files.failed.push(ModSyncFailure {
+                    id: entry.file.clone(),
+                    error,
+                });
+            }
+        }
+    }
+
+    // Whatever the last sync wrote and this one did not keep is gone from the pack
+    let kept: Vec<&str> = state.files.values().map(|f| f.target.as_str()).collect();
+    for previous in old.files.values() {
+        if kept.contains(&previous.target.as_str()) {
+            continue;
+        }
+        let path = game_dir.join(&previous.target);
+        if path.exists() && std::fs::remove_file(&path).is_ok() {
+            report.files.removed.push(previous.target.clone());
+        }
+    }
+
+    save_state(game_dir, &state)?;
+    Ok(report)
+}
+
+/// Fetch the instance's pack, reinstall Minecraft or the loader when the pack moved to other
+/// versions, and sync its files.
+pub async fn sync_instance(
+    app: &AppHandle,
+    game_dir: &Path,
+) -> Result<PackwizSyncReport, InstallError> {
+    let mut instance = Instance::load(game_dir).ok_or("설치 정보가 없습니다")?;
+    let pack_url = instance
+        .packwiz_url
+        .clone()
+        .ok_or("packwiz 인스턴스가 아닙니다")?;
+    let client = http::client();
+    let mut fetcher = MetaFetcher::new(false);
+    let (url, pack) = fetch_pack(&client, &pack_url).await?;
+
+    let mc_version = pack
+        .versions
+        .get("minecraft")
+        .ok_or("pack.toml에 Minecraft 버전 정보가 없습니다")?;
+    let loader = loader_spec(&pack)?;
+    let up_to_date = instance.minecraft_version == *mc_version
+        && instance
+            .loader
+            .as_ref()
+            .is_some_and(|l| l.kind == loader.kind && Some(&l.version) == loader.version.as_ref());
+    if !up_to_date {
+        let installed =
+            download::install_game(app, &client, &mut fetcher, mc_version, &loader, 0).await?;
+        instance.minecraft_version = installed.minecraft_version;
+        instance.loader = installed.loader;
+        instance.save(game_dir)?;
+    }
+
+    Ok(sync_files(app, &client, &mut fetcher, game_dir, &url, &pack).await?)
+}
+
+/// Create an instance that follows the packwiz pack at `pack_url` (its `pack.toml`).
+pub async fn create(
+    app: &AppHandle,
+    pack_url: &str,
+    name: Option<String>,
+) -> Result<PackwizInstanceReport, InstallError> {
+    let (_, pack) = fetch_pack(&http::client(), pack_url).await?;
+    let mc_version = pack
+        .versions
+        .get("minecraft")
+        .ok_or("pack.toml에 Minecraft 버전 정보가 없습니다")?;
+    loader_spec(&pack)?;
+
+    let name = name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| pack.name.clone());
+    let (instance_id, dir) = instance::create_dir(&name)?;
+    // Saved first so sync_instance sees a version mismatch and installs the game
+    let placeholder = Instance {
+        name: Some(name),
+        minecraft_version: mc_version.clone(),
+        loader: None,
+        pinned_loader: None,
+        packwiz_url: Some(pack_url.to_string()),
+    };
+
+    let result = async {
+        placeholder.save(&dir)?;
+        let sync = sync_instance(app, &dir).await?;
+        if !sync.files.failed.is_empty() {
+            let failed: Vec<String> = sync
+                .files
+                .failed
+                .iter()
+                .map(|f| format!("{}: {}", f.id, f.error))
+                .collect();
+            return Err(InstallError::from(failed.join("\n")));
+        }
+        Ok(sync)
+    }
+    .await;
+    match result {
+        Ok(sync) => {
+            emit_progress(app, "packwiz 인스턴스 준비 완료!", "complete", 1, 1, 100.0);
+            Ok(PackwizInstanceReport {
+                instance_id,
+                game_dir: dir.to_string_lossy().to_string(),
+                sync,
+            })
+        }
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,652 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::process::Command;
+
+use super::download;
+use super::game_log::LogEvent;
+use super::launcher_log;
+use super::redact;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How long `stop` gives the game to save and quit before killing it.
+const GRACEFUL_TIMEOUT: Duration = Duration::from_secs(15);
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How often a game re-attached after a launcher restart is checked for exit.
+const ATTACHED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Held in each game directory while a game runs there, so no two processes share it.
+const LOCK_FILE: &str = ".nyaru-session.lock";
+/// A lock whose game was never spawned (the launcher died mid-launch) expires after this.
+const LAUNCH_LOCK_TIMEOUT_SECS: u64 = 10 * 60;
+
+pub const SESSION_POLICY_KEY: &str = "session_policy";
+
+/// How many games may run side by side.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionPolicy {
+    /// One game at a time.
+    #[default]
+    Single,
+    /// One game per instance and per account.
+    PerInstance,
+    /// No limit beyond the game directory lock.
+    Unlimited,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningGame {
+    pub session_id: String,
+    /// 0 while the game is still being launched.
+    pub pid: u32,
+    /// Unix seconds.
+    pub started_at: u64,
+    pub instance_id: String,
+    pub game_dir: String,
+    pub player_name: String,
+    pub player_uuid: String,
+    /// Started by an earlier launcher run, so its output and exit code are not available.
+    #[serde(default)]
+    pub attached: bool,
+    /// `stop` was asked for, so a non-zero exit is not a crash.
+    #[serde(skip)]
+    pub stopping: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopReport {
+    pub session_id: String,
+    /// The game did not quit within the grace period (or `force` was asked) and was killed.
+    pub forced: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GameLogEvent<'a> {
+    session_id: &'a str,
+    #[serde(flatten)]
+    event: &'a LogEvent,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GameStartedEvent<'a> {
+    session_id: &'a str,
+    pid: u32,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GameExitedEvent<'a> {
+    session_id: &'a str,
+    code: i32,
+}
+
+pub fn emit_event(app: &AppHandle, session_id: &str, event: &LogEvent) {
+    let event = &redact::redact_event(event);
+    let _ = app.emit("game-log", GameLogEvent { session_id, event });
+}
+
+/// A message of the launcher itself about the session.
+pub fn launcher_event(message: &str) -> LogEvent {
+    LogEvent {
+        logger: Some("launcher".to_string()),
+        ..LogEvent::plain("INFO", message)
+    }
+}
+
+pub fn emit_log(app: &AppHandle, session_id: &str, message: &str) {
+    launcher_log::write("INFO", &format!("[{}] {}", session_id, message));
+    emit_event(app, session_id, &launcher_event(message));
+}
+
+pub fn emit_started(app: &AppHandle, session_id: &str, pid: u32) {
+    let _ = app.emit("game-started", GameStartedEvent { session_id, pid });
+}
+
+pub fn emit_exited(app: &AppHandle, session_id: &str, code: i32) {
+    let _ = app.emit("game-exited", GameExitedEvent { session_id, code });
+}
+
+#[derive(Default)]
+struct Registry {
+    games: Vec<RunningGame>,
+    /// Reserved between the policy check and the spawn, so two launches cannot race.
+    launching: Vec<RunningGame>,
+    /// Where `games` is mirrored; `None` keeps them in memory only.
+    pid_file: Option<PathBuf>,
+}
+
+impl Registry {
+    fn save(&self) {
+        let Some(ref path) = self.pid_file else {
+            return;
+        };
+        if self.games.is_empty() {
+            let _ = std::fs::remove_file(path);
+        } else if let Ok(json) = serde_json::to_vec_pretty(&self.games) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// Games the launcher started, mirrored to a PID file so a restarted launcher finds them.
+pub struct GameRegistry(Mutex<Registry>);
+
+impl Default for GameRegistry {
+    fn default() -> Self {
+        GameRegistry(Mutex::new(Registry {
+            pid_file: Some(pid_file()),
+            ..Registry::default()
+        }))
+    }
+}
+
+impl GameRegistry {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Registry> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Reserve a session for `game` if `policy` allows it next to the running ones, and lock
+    /// its game directory.
+    pub async fn begin_launch(
+        &self,
+        policy: SessionPolicy,
+        game: RunningGame,
+    ) -> Result<(), String> {
+        self.reserve(policy, game.clone())?;
+        // Outside the registry lock: checking a stale lock's PID spawns a process
+        if let Err(e) = acquire_lock(Path::new(&game.game_dir), &game.session_id, 0).await {
+            self.lock()
+                .launching
+                .retain(|g| g.session_id != game.session_id);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn reserve(&self, policy: SessionPolicy, game: RunningGame) -> Result<(), String> {
+        let mut registry = self.lock();
+        let mut others = registry.games.iter().chain(registry.launching.iter());
+        let conflict = match policy {
+            SessionPolicy::Single => others
+                .next()
+                .map(|_| "마인크래프트가 이미 실행 중입니다.".to_string()),
+            SessionPolicy::PerInstance => others.find_map(|other| {
+                if other.instance_id == game.instance_id {
+                    Some(format!(
+                        "{} 인스턴스가 이미 실행 중입니다.",
+                        game.instance_id
+                    ))
+                } else if other.player_uuid == game.player_uuid {
+                    Some(format!("{} 계정으로 이미 실행 중입니다.", game.player_name))
+                } else {
+                    None
+                }
+            }),
+            SessionPolicy::Unlimited => None,
+        };
+        if let Some(conflict) = conflict {
+            return Err(conflict);
+        }
+        registry.launching.push(game);
+        Ok(())
+    }
+
+    /// Release a reserved session after its launch failed before spawning anything.
+    pub fn cancel_launch(&self, session_id: &str) {
+        let mut registry = self.lock();
+        if let Some(i) = registry
+            .launching
+            .iter()
+            .position(|g| g.session_id == session_id)
+        {
+            let game = registry.launching.remove(i);
+            release_lock(Path::new(&game.game_dir), session_id);
+        }
+    }
+
+    /// Record that the reserved session's game was spawned as `pid`.
+    pub fn started(&self, session_id: &str, pid: u32) {
+        let mut registry = self.lock();
+        let Some(i) = registry
+            .launching
+            .iter()
+            .position(|g| g.session_id == session_id)
+        else {
+            return;
+        };
+        let mut game = registry.launching.remove(i);
+        game.pid = pid;
+        game.started_at = now_secs();
+        let _ = write_lock(Path::new(&game.game_dir), session_id, pid);
+        registry.games.push(game);
+        registry.save();
+    }
+
+    pub fn remove(&self, session_id: &str) -> Option<RunningGame> {
+        let mut registry = self.lock();
+        let i = registry
+            .games
+            .iter()
+            .position(|g| g.session_id == session_id)?;
+        let game = registry.games.remove(i);
+        release_lock(Path::new(&game.game_dir), session_id);
+        registry.save();
+        Some(game)
+    }
+
+    pub fn list(&self) -> Vec<RunningGame> {
+        self.lock().games.clone()
+    }
+
+    /// Mark the session as being stopped and return its PID.
+    fn begin_stop(&self, session_id: &str) -> Option<u32> {
+        let mut registry = self.lock();
+        let game = registry
+            .games
+            .iter_mut()
+            .find(|g| g.session_id == session_id)?;
+        game.stopping = true;
+        Some(game.pid)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionLock {
+    session_id: String,
+    /// 0 while the game is being launched.
+    pid: u32,
+    /// Unix seconds.
+    locked_at: u64,
+}
+
+fn read_lock(game_dir: &Path) -> Option<SessionLock> {
+    let data = std::fs::read(game_dir.join(LOCK_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn write_lock(game_dir: &Path, session_id: &str, pid: u32) -> Result<(), String> {
+    let lock = SessionLock {
+        session_id: session_id.to_string(),
+        pid,
+        locked_at: now_secs(),
+    };
+    let json = serde_json::to_vec(&lock).map_err(|e| e.to_string())?;
+    std::fs::write(game_dir.join(LOCK_FILE), json)
+        .map_err(|e| format!("Write session lock failed: {}", e))
+}
+
+/// Take the game directory lock, replacing one left by a game that is no longer running.
+async fn acquire_lock(game_dir: &Path, session_id: &str, pid: u32) -> Result<(), String> {
+    let path = game_dir.join(LOCK_FILE);
+    if let Some(held) = read_lock(game_dir) {
+        let live = if held.pid == 0 {
+            now_secs().saturating_sub(held.locked_at) < LAUNCH_LOCK_TIMEOUT_SECS
+        } else {
+            is_alive(held.pid).await
+        };
+        if live {
+            return Err(format!(
+                "다른 게임이 이 폴더를 사용 중입니다: {}",
+                game_dir.to_string_lossy()
+            ));
+        }
+        let _ = std::fs::remove_file(&path);
+    } else if path.exists() {
+        // Unreadable, most likely half-written; nothing can be holding it
+        let _ = std::fs::remove_file(&path);
+    }
+
+    // create_new so two launchers racing for the same directory cannot both win
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|_| {
+            format!(
+                "다른 게임이 이 폴더를 사용 중입니다: {}",
+                game_dir.to_string_lossy()
+            )
+        })?;
+    write_lock(game_dir, session_id, pid)
+}
+
+/// Remove the game directory lock if `session_id` still holds it.
+fn release_lock(game_dir: &Path, session_id: &str) {
+    if read_lock(game_dir).is_some_and(|held| held.session_id == session_id) {
+        let _ = std::fs::remove_file(game_dir.join(LOCK_FILE));
+    }
+}
+
+fn pid_file() -> PathBuf {
+    download::get_game_dir().join("running-games.json")
+}
+
+pub fn now_secs() -> u64 {
+    now_millis() / 1000
+}
+
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Whether `pid` is still a running Java process; a reused PID of another program is not.
+async fn is_alive(pid: u32) -> bool {
+    if pid == 0 {
+        return false;
+    }
+    #[cfg(target_os = "windows")]
+    let output = {
+        let mut cmd = Command::new("tasklist");
+        cmd.args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"]);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.output().await
+    };
+    #[cfg(not(target_os = "windows"))]
+    let output = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .await;
+
+    output.is_ok_and(|output| {
+        output.status.success()
+            && String::from_utf8_lossy(&output.stdout)
+                .to_lowercase()
+                .contains("java")
+    })
+}
+
+/// Ask the game to quit (window close / SIGTERM), or kill it outright with `force`.
+async fn signal(pid: u32, force: bool) -> Result<(), String> {
+    // `kill 0` would signal the launcher's whole process group
+    if pid == 0 {
+        return Err("게임 PID를 알 수 없습니다".to_string());
+    }
+    let pid_arg = pid.to_string();
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("taskkill");
+        cmd.args(["/PID", &pid_arg, "/T"]);
+        if force {
+            cmd.arg("/F");
+        }
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("kill");
+        cmd.args([if force { "-KILL" } else { "-TERM" }, &pid_arg]);
+        cmd
+    };
+
+    let status = cmd
+        .status()
+        .await
+        .map_err(|e| format!("게임 종료 실패: {}", e))?;
+    if !status.success() && is_alive(pid).await {
+        return Err(format!("게임 종료 실패 (PID {})", pid));
+    }
+    Ok(())
+}
+
+/// Stop the game of `session_id`: ask it to quit and kill it if it is still up after the
+/// grace period.
+///
+/// The registry entry is dropped by whoever watches the process exit, not here.
+pub async fn stop(
+    registry: &GameRegistry,
+    session_id: &str,
+    force: bool,
+) -> Result<StopReport, String> {
+    let pid = registry
+        .begin_stop(session_id)
+        .ok_or_else(|| format!("실행 중인 게임이 아닙니다: {}", session_id))?;
+    let report = |forced| StopReport {
+        session_id: session_id.to_string(),
+        forced,
+    };
+    if !force {
+        signal(pid, false).await?;
+        let deadline = Instant::now() + GRACEFUL_TIMEOUT;
+        while Instant::now() < deadline {
+            if !is_alive(pid).await {
+                return Ok(report(false));
+            }
+            tokio::time::sleep(STOP_POLL_INTERVAL).await;
+        }
+    }
+    signal(pid, true).await?;
+    Ok(report(true))
+}
+
+/// Pick up games a previous launcher run left running, according to the PID file.
+///
+/// Runs during setup, before any launch can be requested, so it may block on the checks.
+pub fn reattach(app: &AppHandle) {
+    let saved: Vec<RunningGame> = std::fs::read(pid_file())
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default();
+    let (alive, exited): (Vec<_>, Vec<_>) = tauri::async_runtime::block_on(async {
+        let mut alive = Vec::new();
+        let mut exited = Vec::new();
+        for game in saved {
+            if is_alive(game.pid).await {
+                alive.push(game);
+            } else {
+                exited.push(game);
+            }
+        }
+        (alive, exited)
+    });
+    for game in exited {
+        release_lock(Path::new(&game.game_dir), &game.session_id);
+    }
+
+    let registry = &app.state::<crate::AppState>().games;
+    let mut watched = Vec::new();
+    {
+        let mut inner = registry.lock();
+        for mut game in alive {
+            game.attached = true;
+            watched.push((game.session_id.clone(), game.pid));
+            inner.games.push(game);
+        }
+        // Also drops entries of games that exited while the launcher was closed
+        inner.save();
+    }
+
+    for (session_id, pid) in watched {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while is_alive(pid).await {
+                tokio::time::sleep(ATTACHED_POLL_INTERVAL).await;
+            }
+            app.state::<crate::AppState>().games.remove(&session_id);
+            emit_log(&app, &session_id, "[런처] 마인크래프트 종료");
+            emit_exited(&app, &session_id, -1);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Game directories for the sessions of a test, removed on drop.
+    struct GameDirs(PathBuf);
+
+    impl GameDirs {
+        fn new() -> Self {
+            GameDirs(std::env::temp_dir().join(format!("process-test-{}", uuid::Uuid::new_v4())))
+        }
+
+        fn game(&self, session_id: &str, instance_id: &str, player: &str) -> RunningGame {
+            let game_dir = self.0.join(instance_id);
+            std::fs::create_dir_all(&game_dir).unwrap();
+            RunningGame {
+                session_id: session_id.to_string(),
+                pid: 0,
+                started_at: 0,
+                instance_id: instance_id.to_string(),
+                game_dir: game_dir.to_string_lossy().to_string(),
+                player_name: player.to_string(),
+                player_uuid: format!("uuid-{}", player),
+                attached: false,
+                stopping: false,
+            }
+        }
+
+        fn lock(&self, instance_id: &str) -> Option<SessionLock> {
+            read_lock(&self.0.join(instance_id))
+        }
+    }
+
+    impl Drop for GameDirs {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A registry that is not mirrored to the PID file.
+    fn registry() -> GameRegistry {
+        GameRegistry(Mutex::new(Registry::default()))
+    }
+
+    #[test]
+    fn single_policy_allows_one_game() {
+        let dirs = GameDirs::new();
+        let games = registry();
+        games
+            .reserve(SessionPolicy::Single, dirs.game("a", "main", "Steve"))
+            .unwrap();
+        let err = games
+            .reserve(SessionPolicy::Single, dirs.game("b", "other", "Alex"))
+            .err()
+            .unwrap();
+        assert!(err.contains("이미 실행 중"), "{}", err);
+        // The policy is checked per launch, so a looser one lets the next game through
+        games
+            .reserve(SessionPolicy::Unlimited, dirs.game("c", "main", "Steve"))
+            .unwrap();
+    }
+
+    #[test]
+    fn per_instance_policy_separates_instances_and_accounts() {
+        let dirs = GameDirs::new();
+        let games = registry();
+        let policy = SessionPolicy::PerInstance;
+        games
+            .reserve(policy, dirs.game("a", "main", "Steve"))
+            .unwrap();
+
+        let err = games
+            .reserve(policy, dirs.game("b", "main", "Alex"))
+            .err()
+            .unwrap();
+        assert!(err.contains("main 인스턴스"), "{}", err);
+        let err = games
+            .reserve(policy, dirs.game("c", "other", "Steve"))
+            .err()
+            .unwrap();
+        assert!(err.contains("Steve 계정"), "{}", err);
+        games
+            .reserve(policy, dirs.game("d", "other", "Alex"))
+            .unwrap();
+    }
+
+    #[test]
+    fn tracks_reservations_until_removed() {
+        let dirs = GameDirs::new();
+        let games = registry();
+        games
+            .reserve(SessionPolicy::Single, dirs.game("a", "main", "Steve"))
+            .unwrap();
+        // Reserved but not spawned yet: not listed, but it still counts
+        assert!(games.list().is_empty());
+        assert!(games
+            .reserve(SessionPolicy::Single, dirs.game("b", "main", "Steve"))
+            .is_err());
+
+        games.started("a", 4242);
+        let listed = games.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].pid, 4242);
+        assert!(listed[0].started_at > 0);
+        assert_eq!(dirs.lock("main").unwrap().pid, 4242);
+        assert!(games
+            .reserve(SessionPolicy::Single, dirs.game("b", "main", "Steve"))
+            .is_err());
+
+        assert!(games.remove("missing").is_none());
+        assert_eq!(games.remove("a").unwrap().session_id, "a");
+        assert!(games.list().is_empty());
+        assert!(dirs.lock("main").is_none());
+        games
+            .reserve(SessionPolicy::Single, dirs.game("b", "main", "Steve"))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn begin_launch_locks_the_game_directory() {
+        let dirs = GameDirs::new();
+        let games = registry();
+        let policy = SessionPolicy::Unlimited;
+        games
+            .begin_launch(policy, dirs.game("a", "main", "Steve"))
+            .await
+            .unwrap();
+        assert_eq!(dirs.lock("main").unwrap().session_id, "a");
+
+        let err = games
+            .begin_launch(policy, dirs.game("b", "main", "Alex"))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("폴더를 사용 중"), "{}", err);
+        // The refused launch gives its reservation back
+        assert_eq!(games.lock().launching.len(), 1);
+
+        games.cancel_launch("a");
+        assert!(games.lock().launching.is_empty());
+        assert!(dirs.lock("main").is_none());
+        games
+            .begin_launch(policy, dirs.game("b", "main", "Alex"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn replaces_abandoned_launch_lock() {
+        let dirs = GameDirs::new();
+        let game = dirs.game("a", "main", "Steve");
+        let stale = SessionLock {
+            session_id: "crashed".to_string(),
+            pid: 0,
+            locked_at: now_secs() - LAUNCH_LOCK_TIMEOUT_SECS - 1,
+        };
+        std::fs::write(
+            Path::new(&game.game_dir).join(LOCK_FILE),
+            serde_json::to_vec(&stale).unwrap(),
+        )
+        .unwrap();
+
+        registry()
+            .begin_launch(SessionPolicy::Single, game)
+            .await
+            .unwrap();
+        assert_eq!(dirs.lock("main").unwrap().session_id, "a");
+    }
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::cache::MetaFetcher;
+use super::http;
+use super::hud_mod::HudModSource;
+use crate::settings;
+
+/// Signed and published by `release-launcher-config.yml` under a fixed tag; overridable with
+/// the `remote_config_url` setting.
+pub const DEFAULT_CONFIG_URL: &str =
+    "https://github.com/INIRU/Tinklepaw/releases/download/launcher-config/launcher-config.json";
+
+/// minisign public key (base64 line) for `launcher-config.json.minisig`, injected at build time.
+/// Without it only the bundled configuration is used.
+const CONFIG_PUBLIC_KEY: Option<&str> = option_env!("NYARU_CONFIG_PUBKEY");
+
+/// Configuration compiled into the binary, used until a signed remote one is available.
+const BUNDLED_CONFIG: &str = include_str!("../../launcher-config.json");
+
+pub const CONFIG_URL_KEY: &str = "remote_config_url";
+
+/// Highest `serial` of a remote configuration this launcher accepted.
+const CONFIG_SERIAL_KEY: &str = "remote_config_serial";
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteConfig {
+    pub schema_version: u32,
+    /// Grows with every published configuration; a lower one than already seen is an old,
+    /// validly signed configuration being replayed and is refused.
+    #[serde(default)]
+    pub serial: u64,
+    pub server_name: String,
+    pub minecraft_version: String,
+    pub loader: LoaderSpec,
+    #[serde(default)]
+    pub mods: Vec<ConfigMod>,
+    /// Source of the `nyaru-hud` client mod; `None` leaves it alone.
+    #[serde(default)]
+    pub hud_mod: Option<HudModSource>,
+    #[serde(default)]
+    pub servers: Vec<ConfigServer>,
+    pub min_launcher_version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderSpec {
+    /// `fabric`, `quilt`, `forge` or `neoforge`.
+    pub kind: String,
+    /// Exact loader version; `None` takes the latest stable one.
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigMod {
+    pub id: String,
+    pub name: String,
+    pub project_id: Option<String>,
+    /// Modrinth version id; with `project_id` set, a version number works as well.
+    pub version_id: Option<String>,
+    pub filename: Option<String>,
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigServer {
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigSource {
+    Remote,
+    Bundled,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedConfig {
+    pub config: RemoteConfig,
+    pub source: ConfigSource,
+    /// Why the bundled configuration was used instead of the remote one.
+    pub warning: Option<String>,
+}
+
+impl ConfigMod {
+    /// Every mod must be pinned to one file: a version and at least one hash of it.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.url.is_none() && self.version_id.is_none() {
+            return Err(format!(
+                "{}: versionId 또는 url로 버전을 고정해야 합니다",
+                self.id
+            ));
+        }
+        if self.sha1.is_none() && self.sha512.is_none() {
+            return Err(format!("{}: 설정에 sha1 또는 sha512가 필요합니다", self.id));
+        }
+        Ok(())
+    }
+}
+
+fn default_required() -> bool {
+    true
+}
+
+fn default_port() -> u16 {
+    25565
+}
+
+impl RemoteConfig {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_CONFIG).expect("bundled launcher-config.json is valid")
+    }
+
+    /// Display name for `host`, falling back to the server name of the configuration.
+    pub fn server_name_for(&self, host: &str) -> &str {
+        self.servers
+            .iter()
+            .find(|s| s.host.eq_ignore_ascii_case(host))
+            .map(|s| s.name.as_str())
+            .unwrap_or(&self.server_name)
+    }
+
+    /// Refuse configurations that need a newer launcher than this one.
+    pub fn check_launcher_version(&self) -> Result<(), String> {
+        let Some(ref min) = self.min_launcher_version else {
+            return Ok(());
+        };
+        let min = semver::Version::parse(min)
+            .map_err(|e| format!("Invalid minLauncherVersion {}: {}", min, e))?;
+        let current =
+            semver::Version::parse(env!("CARGO_PKG_VERSION")).map_err(|e| e.to_string())?;
+        if current < min {
+            return Err(format!(
+                "런처 업데이트가 필요합니다 (현재 {}, 필요 {} 이상)",
+                current, min
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub fn config_url(app: &AppHandle) -> String {
+    let url: Option<String> = settings::load(app, CONFIG_URL_KEY);
+    url.filter(|u| !u.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CONFIG_URL.to_string())
+}
+
+/// Fetch and verify the server team's configuration, falling back to the bundled one.
+///
+/// A configuration whose signature does not verify is never used, not even from the cache.
+pub async fn load(app: &AppHandle, fetcher: &mut MetaFetcher) -> Result<LoadedConfig, String> {
+    let seen: u64 = settings::load(app, CONFIG_SERIAL_KEY);
+    let min_serial = seen.max(RemoteConfig::bundled().serial);
+    let fetched = fetch_verified(&config_url(app), fetcher, min_serial).await;
+    if let Ok(ref config) = fetched {
+        if config.serial > seen {
+            let _ = settings::save(app, CONFIG_SERIAL_KEY, &config.serial);
+        }
+    }
+    let loaded = or_bundled(fetched);
+    loaded.config.check_launcher_version()?;
+    Ok(loaded)
+}
+
+/// The fetched configuration, or the bundled one with the reason it was not usable.
+fn or_bundled(fetched: Result<RemoteConfig, String>) -> LoadedConfig {
+    match fetched {
+        Ok(config) => LoadedConfig {
+            config,
+            source: ConfigSource::Remote,
+            warning: None,
+        },
+        Err(e) => LoadedConfig {
+            config: RemoteConfig::bundled(),
+            source: ConfigSource::Bundled,
+            warning: Some(e),
+        },
+    }
+}
+
+async fn fetch_verified(
+    url: &str,
+    fetcher: &mut MetaFetcher,
+    min_serial: u64,
+) -> Result<RemoteConfig, String> {
+    let public_key = public_key(CONFIG_PUBLIC_KEY)?;
+
+    let client = http::client();
+    let body = fetcher.fetch("런처 설정", client.get(url)).await?;
+    let signature = fetcher
+        .fetch("런처 설정 서명", client.get(format!("{}.minisig", url)))
+        .await?;
+    verify(&body, &signature, &public_key, min_serial)
+}
+
+fn public_key(configured: Option<&str>) -> Result<minisign_verify::PublicKey, String> {
+    let key = configured
+        .filter(|k| !k.is_empty())
+        .ok_or("이 빌드에는 설정 서명 키가 없습니다")?;
+    minisign_verify::PublicKey::from_base64(key)
+        .map_err(|e| format!("Invalid config public key: {}", e))
+}
+
+/// Parse `body` once its minisign `signature` verifies against `public_key` and its serial
+/// is at least `min_serial`.
+fn verify(
+    body: &[u8],
+    signature: &[u8],
+    public_key: &minisign_verify::PublicKey,
+    min_serial: u64,
+) -> Result<RemoteConfig, String> {
+    let signature = std::str::from_utf8(signature)
+        .map_err(|e| e.to_string())
+        .and_then(|s| minisign_verify::Signature::decode(s).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Invalid config signature: {}", e))?;
+    public_key
+        .verify(body, &signature, false)
+        .map_err(|e| format!("런처 설정 서명 검증 실패: {}", e))?;
+
+    let config: RemoteConfig =
+        serde_json::from_slice(body).map_err(|e| format!("런처 설정 parse failed: {}", e))?;
+    if config.serial < min_serial {
+        return Err(format!(
+            "이전 런처 설정입니다 (serial {}, 필요 {} 이상)",
+            config.serial, min_serial
+        ));
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generated with a throwaway key; minisign's prehashed (`ED`) format.
+    const PUBLIC_KEY: &str = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+    const BODY: &str = r#"{"schemaVersion":1,"serial":5,"serverName":"Nyaru","minecraftVersion":"1.21.11","loader":{"kind":"fabric","version":null}}"#;
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQBAgMEBQYHCBK7Ivq3HAzxtZFEllLqM8yJLWhG55WX22nTFBCd1wZURq+xWB0zVjlqRdXMixKEKErVFPWsi9xcwqjMnlUVXgM=
+trusted comment: timestamp:1760000000\tfile:launcher-config.json\tprehashed
+5BTAhgf54Qzup/2qaShP5UwgpUUB0qqw2wx9qYFzcEdQBAV47d/IUlTfKxGNXvnQRrjdOw7bbY4laJvJSSLRDw==
+";
+
+    fn key() -> minisign_verify::PublicKey {
+        public_key(Some(PUBLIC_KEY)).unwrap()
+    }
+
+    #[test]
+    fn accepts_signed_config() {
+        let config = verify(BODY.as_bytes(), SIGNATURE.as_bytes(), &key(), 5).unwrap();
+        assert_eq!(config.serial, 5);
+        assert_eq!(config.server_name, "Nyaru");
+    }
+
+    #[test]
+    fn rejects_tampered_config() {
+        let body = BODY.replace("Nyaru", "Nyarv");
+        assert!(verify(body.as_bytes(), SIGNATURE.as_bytes(), &key(), 0).is_err());
+        assert!(verify(BODY.as_bytes(), b"not a signature", &key(), 0).is_err());
+    }
+
+    #[test]
+    fn rejects_replayed_config() {
+        let err = verify(BODY.as_bytes(), SIGNATURE.as_bytes(), &key(), 6)
+            .err()
+            .unwrap();
+        assert!(err.contains("serial 5"), "{}", err);
+    }
+
+    #[test]
+    fn requires_public_key() {
+        assert!(public_key(None).is_err());
+        assert!(public_key(Some("")).is_err());
+        assert!(public_key(Some("not base64")).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_bundled_config() {
+        let loaded = or_bundled(Err("offline".into()));
+        assert!(loaded.source == ConfigSource::Bundled);
+        assert_eq!(loaded.warning.as_deref(), Some("offline"));
+        assert_eq!(loaded.config.serial, RemoteConfig::bundled().serial);
+
+        let remote = verify(BODY.as_bytes(), SIGNATURE.as_bytes(), &key(), 0).unwrap();
+        let loaded = or_bundled(Ok(remote));
+        assert!(loaded.source == ConfigSource::Remote);
+        assert!(loaded.warning.is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::Serialize;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: Option<String>,
+    pub arch: String,
+    pub cpu_model: Option<String>,
+    pub cpu_threads: usize,
+    pub total_memory_mb: Option<u64>,
+    pub available_memory_mb: Option<u64>,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// `key: value` or `key=value` line of a system file such as `/proc/meminfo`.
+#[cfg(target_os = "linux")]
+fn proc_field(text: &str, key: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':').or_else(|| line.split_once('='))?;
+        (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn platform_details() -> (Option<String>, Option<String>, Option<u64>, Option<u64>) {
+    let os_version = std::fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|text| proc_field(&text, "PRETTY_NAME"));
+    let cpu_model = std::fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|text| proc_field(&text, "model name"));
+    let meminfo = std::fs::read_to_string("/proc/meminfo").unwrap_or_default();
+    let kb = |key| {
+        proc_field(&meminfo, key)
+            .and_then(|v| v.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map(|kb| kb / 1024)
+    };
+    (os_version, cpu_model, kb("MemTotal"), kb("MemAvailable"))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_details() -> (Option<String>, Option<String>, Option<u64>, Option<u64>) {
+    let os_version =
+        command_output("sw_vers", &["-productVersion"]).map(|v| format!("macOS {}", v));
+    let cpu_model = command_output("sysctl", &["-n", "machdep.cpu.brand_string"]);
+    let total = command_output("sysctl", &["-n", "hw.memsize"])
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|bytes| bytes / 1024 / 1024);
+    // Free and inactive pages can be handed to the game without swapping
+    let available = command_output("vm_stat", &[]).and_then(|text| {
+        let page_size: u64 = text
+            .lines()
+            .next()?
+            .split("page size of ")
+            .nth(1)?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()?;
+        let pages = |key: &str| -> u64 {
+            text.lines()
+                .find(|l| l.starts_with(key))
+                .and_then(|l| l.split(':').nth(1))
+                .and_then(|v| v.trim().trim_end_matches('.').parse().ok())
+                .unwrap_or(0)
+        };
+        let free = pages("Pages free") + pages("Pages inactive") + pages("Pages speculative");
+        Some(free * page_size / 1024 / 1024)
+    });
+    (os_version, cpu_model, total, available)
+}
+
+#[cfg(target_os = "windows")]
+fn platform_details() -> (Option<String>, Option<String>, Option<u64>, Option<u64>) {
+    let os_version = command_output("cmd", &["/C", "ver"]);
+    let cpu_model = std::env::var("PROCESSOR_IDENTIFIER").ok();
+    // Both in KB
+    let memory = command_output(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-Command",
+            "$os = Get-CimInstance Win32_OperatingSystem; \"$($os.TotalVisibleMemorySize) $($os.FreePhysicalMemory)\"",
+        ],
+    );
+    let mut values = memory
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|v| v.parse::<u64>().ok().map(|kb| kb / 1024));
+    let total = values.next().flatten();
+    let available = values.next().flatten();
+    (os_version, cpu_model, total, available)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn platform_details() -> (Option<String>, Option<String>, Option<u64>, Option<u64>) {
+    (None, None, None, None)
+}
+
+/// OS, CPU and memory of this machine; fields the platform does not reveal stay empty.
+pub fn info() -> SystemInfo {
+    let (os_version, cpu_model, total_memory_mb, available_memory_mb) = platform_details();
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version,
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_model,
+        cpu_threads: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        total_memory_mb,
+        available_memory_mb,
+    }
+}
@@ -0,0 +1,32 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Same store file the frontend opens with `Store.load`.
+const STORE_FILE: &str = "nyaru-settings.json";
+
+pub const NETWORK_SETTINGS_KEY: &str = "network_settings";
+
+/// Read a setting written by either side, falling back to `T::default()` when absent or invalid.
+pub fn load<T: DeserializeOwned + Default>(app: &AppHandle, key: &str) -> T {
+    app.store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(key))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    store.set(key, value);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Every stored setting, including those only the frontend reads.
+pub fn all(app: &AppHandle) -> serde_json::Map<String, serde_json::Value> {
+    app.store(STORE_FILE)
+        .map(|store| store.entries().into_iter().collect())
+        .unwrap_or_default()
+}
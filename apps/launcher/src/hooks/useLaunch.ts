@@ -1,6 +1,17 @@
 import { create } from "zustand";
 import { listen } from "@tauri-apps/api/event";
-import { checkInstallation, installMinecraft, launchMinecraft } from "../lib/minecraft";
+import {
+  checkInstallation,
+  formatInstallError,
+  type CrashReport,
+  type GameExitedEvent,
+  type GameLogEvent,
+  getRunningGames,
+  installMinecraft,
+  launchMinecraft,
+  type LaunchParams,
+  MOD_ERRORS_PREFIX,
+} from "../lib/minecraft";
 
 interface DownloadProgress {
   fileName: string;
@@ -17,19 +28,14 @@ interface LaunchState {
   isRunning: boolean;
   downloadProgress: DownloadProgress | null;
   gameLogs: string[];
+  lastCrash: CrashReport | null;
   error: string | null;
+  /** Parameters of a launch refused because of mod errors, kept for "launch anyway". */
+  blockedLaunch: LaunchParams | null;
   checkInstall: () => Promise<void>;
   install: () => Promise<void>;
-  launch: (params: {
-    javaPath: string;
-    maxMemoryMb: number;
-    playerName: string;
-    playerUuid: string;
-    accessToken: string;
-    serverHost: string;
-    serverPort: number;
-    gameDir?: string;
-  }) => Promise<void>;
+  launch: (params: LaunchParams) => Promise<void>;
+  launchAnyway: () => Promise<void>;
   initListeners: () => Promise<() => void>;
 }
 
@@ -40,7 +46,9 @@ export const useLaunch = create<LaunchState>((set, get) => ({
   isRunning: false,
   downloadProgress: null,
   gameLogs: [],
+  lastCrash: null,
   error: null,
+  blockedLaunch: null,
 
   checkInstall: async () => {
     try {
@@ -54,21 +62,43 @@ export const useLaunch = create<LaunchState>((set, get) => ({
   install: async () => {
     set({ isInstalling: true, error: null, downloadProgress: null });
     try {
-      await installMinecraft();
-      set({ isInstalling: false, isInstalled: true });
+      const report = await installMinecraft();
+      set({
+        isInstalling: false,
+        isInstalled: true,
+        error: report.complete ? null : `설치 미완료: ${report.problems.join(", ")}`,
+      });
     } catch (err) {
-      set({ isInstalling: false, error: String(err) });
+      set({ isInstalling: false, error: formatInstallError(err) });
     }
   },
 
 
   launch: async (params) => {
-    set({ isLaunching: true, error: null, gameLogs: [] });
+    set({
+      isLaunching: true,
+      error: null,
+      blockedLaunch: null,
+      gameLogs: [],
+      lastCrash: null,
+    });
     try {
       await launchMinecraft(params);
       set({ isLaunching: false, isRunning: true });
     } catch (err) {
-      set({ isLaunching: false, error: String(err) });
+      const error = String(err);
+      set({
+        isLaunching: false,
+        error,
+        blockedLaunch: error.startsWith(MOD_ERRORS_PREFIX) ? params : null,
+      });
+    }
+  },
+
+  launchAnyway: async () => {
+    const params = get().blockedLaunch;
+    if (params) {
+      await get().launch({ ...params, ignoreModErrors: true });
     }
   },
 
@@ -80,25 +110,43 @@ export const useLaunch = create<LaunchState>((set, get) => ({
       },
     );
 
-    const unlistenLog = await listen<string>("game-log", (event) => {
+    const unlistenLog = await listen<GameLogEvent>("game-log", (event) => {
+      const { level, message, throwable } = event.payload;
+      const line = level === "INFO" ? message : `[${level}] ${message}`;
       set((state) => ({
-        gameLogs: [...state.gameLogs.slice(-200), event.payload],
+        gameLogs: [
+          ...state.gameLogs.slice(-200),
+          throwable ? `${line}\n${throwable}` : line,
+        ],
       }));
     });
 
-    const unlistenStarted = await listen<boolean>("game-started", () => {
+    const unlistenStarted = await listen("game-started", () => {
       set({ isRunning: true, isLaunching: false });
     });
 
-    const unlistenExited = await listen<number>("game-exited", () => {
-      set({ isRunning: false });
+    // Other sessions may still be running
+    const unlistenExited = await listen<GameExitedEvent>("game-exited", async () => {
+      const running = await getRunningGames();
+      set({ isRunning: running.length > 0 });
     });
 
+    const unlistenCrashed = await listen<CrashReport>("game-crashed", (event) => {
+      set({ lastCrash: event.payload });
+    });
+
+    // A game started before a launcher restart is still running
+    const running = await getRunningGames();
+    if (running.length > 0) {
+      set({ isRunning: true });
+    }
+
     return () => {
       unlistenProgress();
       unlistenLog();
       unlistenStarted();
       unlistenExited();
+      unlistenCrashed();
     };
   },
 }));
@@ -8,6 +8,14 @@ export interface JavaInfo {
 export interface InstallStatus {
   installed: boolean;
   game_dir: string;
+  minecraft_version: string;
+  hud_mod: HudModStatus;
+}
+
+export interface HudModStatus {
+  installed: boolean;
+  version: string | null;
+  filename: string | null;
 }
 
 export async function detectJava(
@@ -22,12 +30,62 @@ export async function checkInstallation(): Promise<InstallStatus> {
   return invoke<InstallStatus>("check_installation");
 }
 
-export async function installMinecraft(): Promise<void> {
-  return invoke<void>("install_minecraft");
+export interface ModSyncReport {
+  added: string[];
+  updated: string[];
+  removed: string[];
+  unchanged: string[];
+  failed: { id: string; error: string }[];
+}
+
+export interface InstallReport {
+  complete: boolean;
+  problems: string[];
+  offline: boolean;
+  minecraftVersion: string;
+  configSource: "remote" | "bundled";
+  mods: ModSyncReport | null;
+  hudMod: { version: string; previousVersion: string | null; updated: boolean } | null;
+  skippedRemoteChecks: string[];
+}
+
+export interface VerifyReport {
+  checked: number;
+  missing: string[];
+  corrupted: string[];
+  skippedRemoteChecks: string[];
+}
+
+export type InstallError =
+  | {
+      kind: "insufficientSpace";
+      path: string;
+      requiredBytes: number;
+      availableBytes: number;
+    }
+  | { kind: "failed"; message: string };
+
+export function formatInstallError(err: unknown): string {
+  const e = err as InstallError;
+  if (e && typeof e === "object" && "kind" in e) {
+    if (e.kind === "insufficientSpace") {
+      const gb = (n: number) => (n / 1024 ** 3).toFixed(1);
+      return `디스크 공간이 부족합니다: ${gb(e.requiredBytes)} GB 필요, ${gb(e.availableBytes)} GB 사용 가능`;
+    }
+    return e.message;
+  }
+  return String(err);
+}
+
+export async function installMinecraft(offline = false): Promise<InstallReport> {
+  return invoke<InstallReport>("install_minecraft", { offline });
 }
 
+export async function verifyFiles(offline = false): Promise<VerifyReport> {
+  return invoke<VerifyReport>("verify_files", { offline });
+}
 
-export async function launchMinecraft(params: {
+export interface LaunchParams {
   javaPath: string;
   maxMemoryMb: number;
   playerName: string;
@@ -36,8 +94,15 @@ export async function launchMinecraft(params: {
   serverHost: string;
   serverPort: number;
   gameDir?: string;
-}): Promise<void> {
-  return invoke<void>("launch_minecraft", {
+  /** Start even though mod validation found errors. */
+  ignoreModErrors?: boolean;
+}
+
+/** Start of the error `launch_minecraft` returns when mod validation blocks the launch. */
+export const MOD_ERRORS_PREFIX = "모드 문제로 실행할 수 없습니다";
+
+export async function launchMinecraft(params: LaunchParams): Promise<string> {
+  return invoke<string>("launch_minecraft", {
     javaPath: params.javaPath,
     maxMemoryMb: params.maxMemoryMb,
     playerName: params.playerName,
@@ -46,5 +111,198 @@ export async function launchMinecraft(params: {
     serverHost: params.serverHost,
     serverPort: params.serverPort,
     gameDir: params.gameDir ?? null,
+    ignoreModErrors: params.ignoreModErrors ?? false,
+  });
+}
+
+export interface RunningGame {
+  sessionId: string;
+  pid: number;
+  startedAt: number;
+  instanceId: string;
+  gameDir: string;
+  playerName: string;
+  playerUuid: string;
+  attached: boolean;
+}
+
+export type SessionPolicy = "single" | "perInstance" | "unlimited";
+
+export interface GameLogEvent {
+  sessionId: string;
+  /** Unix milliseconds. */
+  time: number | null;
+  thread: string | null;
+  level: "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR" | "FATAL";
+  /** `"launcher"` for the launcher's own messages. */
+  logger: string | null;
+  message: string;
+  throwable: string | null;
+}
+
+export interface GameExitedEvent {
+  sessionId: string;
+  code: number;
+}
+
+export interface CrashReport {
+  sessionId: string;
+  exitCode: number;
+  reportPath: string | null;
+  description: string | null;
+  exception: string | null;
+  suspectedMods: string[];
+  mixinFailures: string[];
+  causes: {
+    id: "wrongJava" | "outOfMemory" | "missingDependency" | "graphicsDriver" | "mixinFailure";
+    message: string;
+  }[];
+}
+
+export interface DiagnosticsReport {
+  path: string;
+  files: string[];
+}
+
+export async function getRunningGames(): Promise<RunningGame[]> {
+  return invoke<RunningGame[]>("get_running_games");
+}
+
+export async function stopGame(
+  sessionId: string,
+  force = false,
+): Promise<{ sessionId: string; forced: boolean }> {
+  return invoke("stop_game", { sessionId, force });
+}
+
+export async function getSessionPolicy(): Promise<SessionPolicy> {
+  return invoke<SessionPolicy>("get_session_policy");
+}
+
+export async function setSessionPolicy(policy: SessionPolicy): Promise<void> {
+  return invoke<void>("set_session_policy", { policy });
+}
+
+/** Writes a redacted zip of logs, settings and system info for bug reports. */
+export async function exportDiagnostics(
+  outputPath: string,
+  sessionLogs?: number,
+): Promise<DiagnosticsReport> {
+  return invoke<DiagnosticsReport>("export_diagnostics", { outputPath, sessionLogs });
+}
+
+export interface UploadedLog {
+  id: string;
+  url: string;
+  rawUrl: string | null;
+  file: string;
+  analysis: {
+    title: string | null;
+    problems: { message: string; solutions: string[] }[];
+    information: { label: string; value: string }[];
+  } | null;
+}
+
+/** Redacts a session log (the newest when `sessionId` is omitted) and shares it on mclo.gs. */
+export async function uploadLog(sessionId?: string): Promise<UploadedLog> {
+  return invoke<UploadedLog>("upload_log", { sessionId: sessionId ?? null });
+}
+
+export interface LogUploadSettings {
+  /** `null` uses https://api.mclo.gs. */
+  apiBase: string | null;
+}
+
+export async function getLogUploadSettings(): Promise<LogUploadSettings> {
+  return invoke<LogUploadSettings>("get_log_upload_settings");
+}
+
+export async function setLogUploadSettings(logUpload: LogUploadSettings): Promise<void> {
+  return invoke<void>("set_log_upload_settings", { logUpload });
+}
+
+export interface SystemInfo {
+  os: string;
+  osVersion: string | null;
+  arch: string;
+  cpuModel: string | null;
+  cpuThreads: number;
+  totalMemoryMb: number | null;
+  availableMemoryMb: number | null;
+}
+
+export interface MemoryRecommendation {
+  profile: "vanilla" | "modded" | "heavyModpack";
+  modCount: number;
+  recommendedMb: number;
+  /** Largest heap that leaves the OS enough; `null` when total memory is unknown. */
+  maxSafeMb: number | null;
+  totalMemoryMb: number | null;
+  availableMemoryMb: number | null;
+}
+
+export async function getSystemInfo(): Promise<SystemInfo> {
+  return invoke<SystemInfo>("get_system_info");
+}
+
+export async function recommendMemory(instanceId?: string): Promise<MemoryRecommendation> {
+  return invoke<MemoryRecommendation>("recommend_memory", {
+    instanceId: instanceId ?? null,
+  });
+}
+
+export interface JvmPreset {
+  id: string;
+  name: string;
+  description: string;
+  minJava: number;
+  args: string[];
+}
+
+export interface LaunchOptions {
+  /** `null` is the `"default"` preset. */
+  preset: string | null;
+  /** Shell-style, e.g. `-XX:+UseLargePages "-Dfoo=a b"`. */
+  jvmArgs: string;
+  /** Shell-style, e.g. `--width 1920 --height 1080`. */
+  gameArgs: string;
+}
+
+export interface LaunchPreview {
+  /** Secrets such as the access token are masked. */
+  command: string;
+  warnings: string[];
+}
+
+export async function listJvmPresets(): Promise<JvmPreset[]> {
+  return invoke<JvmPreset[]>("list_jvm_presets");
+}
+
+export async function getLaunchOptions(instanceId: string): Promise<LaunchOptions> {
+  return invoke<LaunchOptions>("get_launch_options", { instanceId });
+}
+
+export async function setLaunchOptions(
+  instanceId: string,
+  options: LaunchOptions,
+): Promise<void> {
+  return invoke<void>("set_launch_options", { instanceId, options });
+}
+
+export async function previewLaunchCommand(params: {
+  instanceId: string;
+  javaPath: string;
+  maxMemoryMb: number;
+  playerName?: string;
+  serverHost?: string;
+  serverPort?: number;
+}): Promise<LaunchPreview> {
+  return invoke<LaunchPreview>("preview_launch_command", {
+    instanceId: params.instanceId,
+    javaPath: params.javaPath,
+    maxMemoryMb: params.maxMemoryMb,
+    playerName: params.playerName ?? null,
+    serverHost: params.serverHost ?? null,
+    serverPort: params.serverPort ?? null,
   });
 }
@@ -5,7 +5,7 @@ import { useAuth } from "../hooks/useAuth";
 import { useSettings } from "../hooks/useSettings";
 import { useServerStatus } from "../hooks/useServerStatus";
 import { useLaunch } from "../hooks/useLaunch";
-import { detectJava } from "../lib/minecraft";
+import { detectJava, formatInstallError } from "../lib/minecraft";
 import PlayerCard from "../components/PlayerCard";
 import ServerStatus from "../components/ServerStatus";
 import MemorySlider from "../components/MemorySlider";
@@ -28,9 +28,11 @@ export default function Home({ onNavigate }: HomeProps) {
     downloadProgress,
     gameLogs,
     error,
+    blockedLaunch,
     checkInstall,
     install,
     launch,
+    launchAnyway,
     initListeners,
   } = useLaunch();
 
@@ -76,7 +78,7 @@ export default function Home({ onNavigate }: HomeProps) {
           javaPath = await invoke<string>("install_java");
           await updateSettings({ javaPath });
         } catch (e) {
-          alert(`Java 설치 실패: ${e}\n설정에서 Java 경로를 직접 입력해주세요.`);
+          alert(`Java 설치 실패: ${formatInstallError(e)}\n설정에서 Java 경로를 직접 입력해주세요.`);
           onNavigate("settings");
           return;
         }
@@ -194,8 +196,18 @@ export default function Home({ onNavigate }: HomeProps) {
           {/* Error */}
           {error && (
             <div className="px-4 py-2 rounded-xl bg-red-500/10 border border-red-500/20
-              text-xs text-red-300 text-center max-w-[300px]">
+              text-xs text-red-300 text-center max-w-[300px] whitespace-pre-line">
               {error}
+              {blockedLaunch && (
+                <button
+                  onClick={launchAnyway}
+                  disabled={isLaunching}
+                  className="block mx-auto mt-2 px-3 py-1 rounded-lg bg-red-500/20
+                    hover:bg-red-500/30 text-red-200 transition-colors cursor-pointer"
+                >
+                  그래도 실행
+                </button>
+              )}
             </div>
           )}
 
@@ -1,6 +1,10 @@
 import { useEffect, useState } from "react";
 import { useSettings } from "../hooks/useSettings";
-import { detectJava } from "../lib/minecraft";
+import {
+  detectJava,
+  recommendMemory,
+  type MemoryRecommendation,
+} from "../lib/minecraft";
 
 interface SettingsProps {
   onNavigate: (page: "home" | "settings") => void;
@@ -10,9 +14,11 @@ export default function Settings({ onNavigate }: SettingsProps) {
   const { settings, loadSettings, updateSettings } = useSettings();
   const [javaVersion, setJavaVersion] = useState<string | null>(null);
   const [detecting, setDetecting] = useState(false);
+  const [memory, setMemory] = useState<MemoryRecommendation | null>(null);
 
   useEffect(() => {
     loadSettings();
+    recommendMemory().then(setMemory).catch(() => setMemory(null));
   }, []);
 
   useEffect(() => {
@@ -86,7 +92,7 @@ export default function Settings({ onNavigate }: SettingsProps) {
             <input
               type="range"
               min={1024}
-              max={8192}
+              max={memory?.maxSafeMb ?? 8192}
               step={512}
               value={settings.maxMemoryMb}
               onChange={(e) =>
@@ -107,6 +113,23 @@ export default function Settings({ onNavigate }: SettingsProps) {
               {(settings.maxMemoryMb / 1024).toFixed(1)} GB
             </span>
           </div>
+          {memory && (
+            <div className="flex items-center justify-between text-xs text-text-muted">
+              <span>
+                {memory.totalMemoryMb !== null &&
+                  `시스템 메모리 ${(memory.totalMemoryMb / 1024).toFixed(1)} GB · `}
+                권장 {(memory.recommendedMb / 1024).toFixed(1)} GB
+              </span>
+              {settings.maxMemoryMb !== memory.recommendedMb && (
+                <button
+                  onClick={() => updateSettings({ maxMemoryMb: memory.recommendedMb })}
+                  className="text-lavender hover:underline cursor-pointer"
+                >
+                  권장값 사용
+                </button>
+              )}
+            </div>
+          )}
         </section>
 
         {/* Game Directory */}